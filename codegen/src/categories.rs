@@ -0,0 +1,260 @@
+//! RPC categories and the Cargo features that gate them in the generated crate.
+//!
+//! Bitcoin Core groups its RPCs into categories (`blockchain`, `wallet`, ...), as listed by
+//! `help` and recorded in the schema's `category` field. The generated crate puts each
+//! category behind a feature, so a build that only needs blockchain RPCs can leave the
+//! wallet ones out. Methods of schemas without a `category` field are categorized by
+//! [`category_of`].
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::generators::verbosity;
+
+/// Category of methods no entry of the prefix table matches.
+pub const FALLBACK_CATEGORY: &str = "uncategorized";
+
+/// Method name prefixes and the category of the methods they start, for schemas without
+/// a `category` field. The longest matching prefix wins, so `listbanned` overrides `list`.
+const CATEGORY_PREFIXES: &[(&str, &str)] = &[
+    ("abandontransaction", "wallet"),
+    ("abortrescan", "wallet"),
+    ("addconnection", "hidden"),
+    ("addnode", "network"),
+    ("addpeeraddress", "hidden"),
+    ("analyzepsbt", "rawtransactions"),
+    ("backupwallet", "wallet"),
+    ("bumpfee", "wallet"),
+    ("clearbanned", "network"),
+    ("combine", "rawtransactions"),
+    ("converttopsbt", "rawtransactions"),
+    ("createmultisig", "util"),
+    ("createpsbt", "rawtransactions"),
+    ("createrawtransaction", "rawtransactions"),
+    ("createwallet", "wallet"),
+    ("decode", "rawtransactions"),
+    ("deriveaddresses", "util"),
+    ("descriptorprocesspsbt", "rawtransactions"),
+    ("disconnectnode", "network"),
+    ("dumptxoutset", "blockchain"),
+    ("echo", "hidden"),
+    ("encryptwallet", "wallet"),
+    ("enumeratesigners", "signer"),
+    ("estimaterawfee", "hidden"),
+    ("estimatesmartfee", "util"),
+    ("finalizepsbt", "rawtransactions"),
+    ("fundrawtransaction", "rawtransactions"),
+    ("generate", "hidden"),
+    ("getaddednodeinfo", "network"),
+    ("getaddress", "wallet"),
+    ("getaddrmaninfo", "network"),
+    ("getbalance", "wallet"),
+    ("getbestblockhash", "blockchain"),
+    ("getblock", "blockchain"),
+    ("getblocktemplate", "mining"),
+    ("getchain", "blockchain"),
+    ("getconnectioncount", "network"),
+    ("getdeploymentinfo", "blockchain"),
+    ("getdescriptoractivity", "blockchain"),
+    ("getdescriptorinfo", "util"),
+    ("getdifficulty", "blockchain"),
+    ("gethdkeys", "wallet"),
+    ("getindexinfo", "util"),
+    ("getmemoryinfo", "control"),
+    ("getmempool", "blockchain"),
+    ("getmininginfo", "mining"),
+    ("getnettotals", "network"),
+    ("getnetworkhashps", "mining"),
+    ("getnetworkinfo", "network"),
+    ("getnewaddress", "wallet"),
+    ("getnodeaddresses", "network"),
+    ("getorphantxs", "hidden"),
+    ("getpeerinfo", "network"),
+    ("getprioritisedtransactions", "mining"),
+    ("getrawaddrman", "hidden"),
+    ("getrawchangeaddress", "wallet"),
+    ("getrawmempool", "blockchain"),
+    ("getrawtransaction", "rawtransactions"),
+    ("getreceivedby", "wallet"),
+    ("getrpcinfo", "control"),
+    ("gettransaction", "wallet"),
+    ("gettxout", "blockchain"),
+    ("gettxspendingprevout", "blockchain"),
+    ("getwallet", "wallet"),
+    ("getzmq", "zmq"),
+    ("help", "control"),
+    ("import", "wallet"),
+    ("importmempool", "blockchain"),
+    ("invalidateblock", "hidden"),
+    ("joinpsbts", "rawtransactions"),
+    ("keypool", "wallet"),
+    ("list", "wallet"),
+    ("listbanned", "network"),
+    ("loadtxoutset", "blockchain"),
+    ("loadwallet", "wallet"),
+    ("lockunspent", "wallet"),
+    ("logging", "control"),
+    ("migratewallet", "wallet"),
+    ("mockscheduler", "hidden"),
+    ("ping", "network"),
+    ("preciousblock", "blockchain"),
+    ("prioritisetransaction", "mining"),
+    ("pruneblockchain", "blockchain"),
+    ("psbtbumpfee", "wallet"),
+    ("reconsiderblock", "hidden"),
+    ("removeprunedfunds", "wallet"),
+    ("rescanblockchain", "wallet"),
+    ("restorewallet", "wallet"),
+    ("savemempool", "blockchain"),
+    ("scanblocks", "blockchain"),
+    ("scantxoutset", "blockchain"),
+    ("schema", "control"),
+    ("send", "wallet"),
+    ("sendmsgtopeer", "hidden"),
+    ("sendrawtransaction", "rawtransactions"),
+    ("setban", "network"),
+    ("setlabel", "wallet"),
+    ("setmocktime", "hidden"),
+    ("setnetworkactive", "network"),
+    ("settxfee", "wallet"),
+    ("setwalletflag", "wallet"),
+    ("signmessage", "wallet"),
+    ("signmessagewithprivkey", "util"),
+    ("signrawtransactionwithkey", "rawtransactions"),
+    ("signrawtransactionwithwallet", "wallet"),
+    ("simulaterawtransaction", "wallet"),
+    ("stop", "control"),
+    ("submitblock", "mining"),
+    ("submitheader", "mining"),
+    ("submitpackage", "rawtransactions"),
+    ("syncwithvalidationinterfacequeue", "hidden"),
+    ("testmempoolaccept", "rawtransactions"),
+    ("unloadwallet", "wallet"),
+    ("uptime", "control"),
+    ("utxoupdatepsbt", "rawtransactions"),
+    ("validateaddress", "util"),
+    ("verifychain", "blockchain"),
+    ("verifymessage", "util"),
+    ("verifytxoutproof", "blockchain"),
+    ("waitfor", "blockchain"),
+    ("wallet", "wallet"),
+];
+
+/// The category of `method_name`, for schemas that don't record one.
+///
+/// Deterministic, so every codegen path groups a method the same way. Names matching no
+/// known prefix are put in [`FALLBACK_CATEGORY`].
+pub fn category_of(method_name: &str) -> &'static str {
+    CATEGORY_PREFIXES
+        .iter()
+        .filter(|(prefix, _)| method_name.starts_with(prefix))
+        .max_by_key(|(prefix, _)| prefix.len())
+        .map_or(FALLBACK_CATEGORY, |(_, category)| category)
+}
+
+/// The generated crate's feature for `category`: the category name, except `zmq-rpc` for
+/// `zmq`, which already names the `ZmqSubscriber` feature.
+pub fn category_feature(category: &str) -> String {
+    match category {
+        "zmq" => "zmq-rpc".to_string(),
+        category => category.to_string(),
+    }
+}
+
+/// The category of every method, used to put generated items behind their feature.
+///
+/// An empty set (the default) gates nothing, which generates the whole RPC surface
+/// unconditionally.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MethodCategories {
+    by_method: BTreeMap<String, String>,
+}
+
+impl MethodCategories {
+    /// The category of `method`; verbosity overloads share their base method's.
+    pub fn category(&self, method: &str) -> Option<&str> {
+        self.by_method.get(verbosity::rpc_name(method)).map(String::as_str)
+    }
+
+    /// The feature gating `method`, if it has a category.
+    pub fn feature(&self, method: &str) -> Option<String> {
+        self.category(method).map(category_feature)
+    }
+
+    /// `#[cfg(feature = "...")]` for `method` indented by `indent`, or an empty string
+    /// if it has no category.
+    pub fn cfg(&self, method: &str, indent: &str) -> String {
+        self.feature(method)
+            .map(|feature| format!("{indent}#[cfg(feature = \"{feature}\")]\n"))
+            .unwrap_or_default()
+    }
+
+    /// Every feature gating some method, sorted.
+    pub fn features(&self) -> BTreeSet<String> {
+        self.by_method.values().map(|category| category_feature(category)).collect()
+    }
+}
+
+impl FromIterator<(String, String)> for MethodCategories {
+    /// Collect `(method, category)` pairs.
+    fn from_iter<I: IntoIterator<Item = (String, String)>>(pairs: I) -> Self {
+        MethodCategories { by_method: pairs.into_iter().collect() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use super::*;
+
+    #[test]
+    fn test_inferred_categories_match_the_bundled_schema() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../bitcoin-core-api.json");
+        let schema: Value = serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+        for (name, method) in schema["methods"].as_object().unwrap() {
+            assert_eq!(Some(category_of(name)), method["category"].as_str(), "{name}");
+        }
+        assert_eq!(category_of("someday"), FALLBACK_CATEGORY);
+    }
+
+    #[test]
+    fn test_category_of_pins_a_method_to_each_category() {
+        for (method, category) in [
+            ("getblockheader", "blockchain"),
+            ("stop", "control"),
+            ("generatetoaddress", "hidden"),
+            ("getblocktemplate", "mining"),
+            ("getpeerinfo", "network"),
+            ("addnode", "network"),
+            ("decodepsbt", "rawtransactions"),
+            ("enumeratesigners", "signer"),
+            ("validateaddress", "util"),
+            ("listwallets", "wallet"),
+            ("sendtoaddress", "wallet"),
+            ("walletprocesspsbt", "wallet"),
+            ("getzmqnotifications", "zmq"),
+            ("frobnicate", "uncategorized"),
+        ] {
+            assert_eq!(category_of(method), category, "{method}");
+        }
+    }
+
+    #[test]
+    fn test_overloads_are_gated_with_their_method() {
+        let categories = [("getblock", "blockchain"), ("getzmqnotifications", "zmq")]
+            .into_iter()
+            .map(|(m, c)| (m.to_string(), c.to_string()))
+            .collect::<MethodCategories>();
+
+        assert_eq!(
+            categories.cfg("getblock_verbose2", "    "),
+            "    #[cfg(feature = \"blockchain\")]\n"
+        );
+        assert_eq!(categories.feature("getzmqnotifications").as_deref(), Some("zmq-rpc"));
+        assert_eq!(categories.cfg("getbalance", ""), "");
+        assert_eq!(
+            categories.features().into_iter().collect::<Vec<_>>(),
+            ["blockchain", "zmq-rpc"]
+        );
+    }
+}
@@ -0,0 +1,77 @@
+//! RPC components: the independently-addressable parts of Bitcoin Core serving RPCs.
+//!
+//! As Bitcoin Core moves toward separate processes (node, wallet, ...) with their own RPC
+//! servers, a method is only reachable at its component's endpoint. Every method belongs to
+//! one [`RpcComponent`], so the generated transport can route its calls there. For now
+//! wallet methods are classified by their category and everything else is the node's.
+
+use crate::categories::category_of;
+use crate::MethodCategories;
+
+/// An independently-addressable part of Bitcoin Core serving RPCs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RpcComponent {
+    /// The node: chain state, mempool, network and mining.
+    Node,
+    /// The wallet.
+    Wallet,
+    /// The optional indexes (`txindex`, `blockfilterindex`, ...).
+    Index,
+    /// The GUI.
+    Gui,
+}
+
+impl RpcComponent {
+    /// Every component.
+    pub const ALL: [RpcComponent; 4] =
+        [RpcComponent::Node, RpcComponent::Wallet, RpcComponent::Index, RpcComponent::Gui];
+
+    /// The component's name, e.g. `wallet`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RpcComponent::Node => "node",
+            RpcComponent::Wallet => "wallet",
+            RpcComponent::Index => "index",
+            RpcComponent::Gui => "gui",
+        }
+    }
+
+    /// The component serving the methods of `category`, [`RpcComponent::Node`] unless
+    /// another one is known to serve it.
+    pub fn for_category(category: &str) -> Self {
+        match category {
+            "wallet" => RpcComponent::Wallet,
+            _ => RpcComponent::Node,
+        }
+    }
+}
+
+/// The component of `method_name`, by its category in `categories` or, if it has none
+/// there, the one [`category_of`] infers.
+pub fn component_of(method_name: &str, categories: &MethodCategories) -> RpcComponent {
+    let category = categories.category(method_name).unwrap_or_else(|| category_of(method_name));
+    RpcComponent::for_category(category)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::load_method_categories_from_str;
+
+    #[test]
+    fn test_methods_of_the_bundled_schema_are_classified() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../bitcoin-core-api.json");
+        let schema = std::fs::read_to_string(path).unwrap();
+        let categories = load_method_categories_from_str(&schema).unwrap();
+
+        assert_eq!(component_of("sendtoaddress", &categories), RpcComponent::Wallet);
+        assert_eq!(component_of("getbalance", &categories), RpcComponent::Wallet);
+        assert_eq!(component_of("getblockcount", &categories), RpcComponent::Node);
+        assert_eq!(component_of("getindexinfo", &categories), RpcComponent::Node);
+        // Without categories, the inferred ones classify methods the same way
+        assert_eq!(
+            component_of("sendtoaddress", &MethodCategories::default()),
+            RpcComponent::Wallet
+        );
+    }
+}
@@ -2,14 +2,24 @@
 
 use std::fmt::Write;
 
-use bitcoin_rpc_conversions::TypeRegistry;
 use bitcoin_rpc_types::BtcMethod;
 
-use crate::utils::capitalize;
-use crate::CodeGenerator;
+use crate::utils::{capitalize, sanitize_ident};
+use crate::{type_registry, CodeGenerator, MethodCategories};
 
 /// Generates a fluent `BatchBuilder` with one method-per-RPC and an `.execute()` entrypoint.
-pub struct BatchBuilderGenerator;
+#[derive(Debug, Clone, Default)]
+pub struct BatchBuilderGenerator {
+    categories: MethodCategories,
+}
+
+impl BatchBuilderGenerator {
+    /// Put each method's queueing fn and result field behind the feature of its category.
+    pub fn with_categories(mut self, categories: MethodCategories) -> Self {
+        self.categories = categories;
+        self
+    }
+}
 
 impl CodeGenerator for BatchBuilderGenerator {
     fn generate(&self, methods: &[BtcMethod]) -> Vec<(String, String)> {
@@ -18,9 +28,13 @@ impl CodeGenerator for BatchBuilderGenerator {
         // Imports
         writeln!(
             code,
-            "use std::sync::Arc;
+            "use std::any::Any;
+use std::marker::PhantomData;
+use std::sync::Arc;
 use crate::transport::{{TransportTrait, TransportError, BatchTransport}};
+use crate::transport::core::rpc_error;
 use serde_json::{{Value, json}};
+use serde::de::DeserializeOwned;
 use serde::Deserialize;
 use crate::responses::*;"
         )
@@ -38,7 +52,8 @@ pub struct BatchResults {{
 
         // Add fields for each method
         for m in methods {
-            let field_name = m.name.clone();
+            let field_name = sanitize_ident(&m.name);
+            code.push_str(&self.categories.cfg(&m.name, "    "));
 
             // Check if this method returns void (no results or all results are "none")
             let returns_unit = m.results.is_empty() || m.results.iter().all(|r| r.type_ == "none");
@@ -49,7 +64,7 @@ pub struct BatchResults {{
             } else {
                 // For non-void methods, always use Option<T> since we may not call every method in a batch
                 let response_type = if m.results.len() == 1 {
-                    let (ty, _) = TypeRegistry::map_result_type(&m.results[0]);
+                    let (ty, _) = type_registry::map_result_type(&m.results[0]);
                     if ty == "()" {
                         "()".to_string()
                     } else {
@@ -67,10 +82,40 @@ pub struct BatchResults {{
         // Builder struct
         writeln!(
             code,
-            r#"/// Fluent builder for batching multiple RPC calls
+            r#"/// Deserializes one typed batch entry into the type its handle was created for
+type Decoder = Box<dyn FnOnce(Value) -> Result<Box<dyn Any + Send>, TransportError> + Send>;
+
+/// Handle to a call queued with [`BatchBuilder::add_typed`], redeemed with
+/// [`TypedBatchResults::take`]
+#[derive(Debug)]
+pub struct BatchHandle<R> {{
+    index: usize,
+    _marker: PhantomData<fn() -> R>,
+}}
+
+/// Results of [`BatchBuilder::send_typed`], one per [`BatchHandle`]
+pub struct TypedBatchResults {{
+    entries: Vec<Option<Result<Box<dyn Any + Send>, TransportError>>>,
+}}
+
+impl TypedBatchResults {{
+    /// Take the result of the call behind `handle`
+    ///
+    /// Each call succeeds or fails on its own: an RPC or deserialization error for one
+    /// call is returned for its handle only.
+    pub fn take<R: 'static>(&mut self, handle: BatchHandle<R>) -> Result<R, TransportError> {{
+        let entry = self.entries.get_mut(handle.index).and_then(Option::take).ok_or_else(|| {{
+            TransportError::Rpc(format!("no batch result for handle {{}}", handle.index))
+        }})?;
+        entry.map(|value| *value.downcast::<R>().expect("entries decode into their handle's type"))
+    }}
+}}
+
+/// Fluent builder for batching multiple RPC calls
 pub struct BatchBuilder {{
     tx: BatchTransport,
     calls: Vec<(&'static str, Vec<Value>)>,
+    typed: Vec<(String, Vec<Value>, Decoder)>,
 }}
 "#
         )
@@ -84,7 +129,48 @@ pub struct BatchBuilder {{
     pub fn new(inner: Arc<dyn TransportTrait>) -> Self {{
         let tx = BatchTransport::new(inner);
         tx.begin_batch();
-        BatchBuilder {{ tx, calls: Vec::new() }}
+        BatchBuilder {{ tx, calls: Vec::new(), typed: Vec::new() }}
+    }}
+
+    /// Queue a `method` call whose result is deserialized into `R`
+    ///
+    /// Typed calls are sent by [`send_typed`](Self::send_typed), separately from the
+    /// fluent per-RPC calls sent by [`execute`](Self::execute).
+    pub fn add_typed<R: DeserializeOwned + Send + 'static>(
+        &mut self,
+        method: &str,
+        params: &[Value],
+    ) -> BatchHandle<R> {{
+        let decode: Decoder = Box::new(|raw| {{
+            let value: Box<dyn Any + Send> = Box::new(serde_json::from_value::<R>(raw)?);
+            Ok(value)
+        }});
+        self.typed.push((method.to_string(), params.to_vec(), decode));
+        BatchHandle {{ index: self.typed.len() - 1, _marker: PhantomData }}
+    }}
+
+    /// Send the calls queued with [`add_typed`](Self::add_typed) as one batch
+    ///
+    /// Fails as a whole only if the batch cannot be sent; per-call errors are returned
+    /// by [`TypedBatchResults::take`].
+    pub async fn send_typed(self) -> Result<TypedBatchResults, TransportError> {{
+        let BatchBuilder {{ tx, typed, .. }} = self;
+        let mut decoders = Vec::with_capacity(typed.len());
+        for (method, params, decode) in typed {{
+            std::mem::drop(tx.send_request(&method, &params));
+            decoders.push(decode);
+        }}
+        let entries = tx
+            .end_batch_entries()
+            .await
+            .map_err(|e| TransportError::Rpc(e.to_string()))?;
+
+        let entries = entries
+            .into_iter()
+            .zip(decoders)
+            .map(|(entry, decode)| Some(entry.map_err(|error| rpc_error(&error)).and_then(decode)))
+            .collect();
+        Ok(TypedBatchResults {{ entries }})
     }}
 
 "#
@@ -93,19 +179,13 @@ pub struct BatchBuilder {{
 
         // Generate one queueing method per RPC
         for m in methods {
-            let name = m.name.clone();
+            let name = &m.name;
+            let fn_name = sanitize_ident(name);
             // Build argument list
             let args_list = m
                 .arguments
                 .iter()
-                .map(|arg| {
-                    let arg_name = if arg.names[0] == "type" {
-                        format!("r#{}", arg.names[0])
-                    } else {
-                        arg.names[0].clone()
-                    };
-                    format!(", {arg_name}: Value")
-                })
+                .map(|arg| format!(", {}: Value", sanitize_ident(&arg.names[0])))
                 .collect::<Vec<_>>()
                 .join("");
             // Build params vec literal
@@ -115,14 +195,7 @@ pub struct BatchBuilder {{
                 let elems = m
                     .arguments
                     .iter()
-                    .map(|arg| {
-                        let arg_name = if arg.names[0] == "type" {
-                            format!("r#{}", arg.names[0])
-                        } else {
-                            arg.names[0].clone()
-                        };
-                        format!("json!({arg_name})")
-                    })
+                    .map(|arg| format!("json!({})", sanitize_ident(&arg.names[0])))
                     .collect::<Vec<_>>()
                     .join(", ");
                 format!("vec![{elems}]")
@@ -135,10 +208,12 @@ pub struct BatchBuilder {{
                 ""
             };
 
+            let cfg = self.categories.cfg(name, "    ");
+
             writeln!(
                 code,
                 r#"{clippy_allow}    /// Queue a `{name}` RPC call
-    pub fn {name}(mut self{args_list}) -> Self {{
+{cfg}    pub fn {fn_name}(mut self{args_list}) -> Self {{
         self.calls.push(("{name}", {params}));
         self
     }}
@@ -152,7 +227,7 @@ pub struct BatchBuilder {{
             code,
             r#"    /// Executes the batch and returns typed results
     pub async fn execute(self) -> Result<BatchResults, TransportError> {{
-        let BatchBuilder {{tx, calls }} = self;
+        let BatchBuilder {{ tx, calls, .. }} = self;
         // queue all calls into the transport
         for (method, params) in &calls {{
             std::mem::drop(tx.send_request(method, params));
@@ -169,8 +244,9 @@ pub struct BatchBuilder {{
 
         // Generate field assignments - initialize all to None/()
         for m in methods {
-            let field_name = m.name.clone();
+            let field_name = sanitize_ident(&m.name);
             let returns_unit = m.results.is_empty() || m.results.iter().all(|r| r.type_ == "none");
+            code.push_str(&self.categories.cfg(&m.name, "            "));
 
             if returns_unit {
                 writeln!(code, "            {field_name}: (),").unwrap();
@@ -193,9 +269,10 @@ pub struct BatchBuilder {{
 
         // Generate match arms for each method
         for method in methods {
-            let field_name = method.name.clone();
+            let field_name = sanitize_ident(&method.name);
             let returns_unit =
                 method.results.is_empty() || method.results.iter().all(|r| r.type_ == "none");
+            code.push_str(&self.categories.cfg(&method.name, "                "));
 
             if returns_unit {
                 writeln!(
@@ -224,6 +301,34 @@ pub struct BatchBuilder {{
         Ok(results)
     }}
 }}
+
+#[cfg(test)]
+mod tests {{
+    use super::*;
+    use crate::transport::MockTransport;
+
+    #[tokio::test]
+    async fn typed_batch_decodes_each_call_separately() {{
+        let mock = MockTransport::new();
+        mock.expect("getblockcount", Ok(json!(101)))
+            .expect("getbestblockhash", Ok(json!("00ff")))
+            .expect("getbalance", Err(TransportError::RpcCode {{
+                code: -18,
+                message: "no wallet".into(),
+                data: None,
+            }}));
+
+        let mut batch = BatchBuilder::new(Arc::new(mock));
+        let count = batch.add_typed::<u64>("getblockcount", &[]);
+        let hash = batch.add_typed::<String>("getbestblockhash", &[]);
+        let balance = batch.add_typed::<f64>("getbalance", &[]);
+        let mut results = batch.send_typed().await.unwrap();
+
+        assert_eq!(results.take(count).unwrap(), 101);
+        assert_eq!(results.take(hash).unwrap(), "00ff");
+        assert_eq!(results.take(balance).unwrap_err().rpc_code(), Some(-18));
+    }}
+}}
 "#
         )
         .unwrap();
@@ -231,3 +336,25 @@ pub struct BatchBuilder {{
         vec![("batch_builder.rs".to_string(), code)]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_builder_supports_typed_calls() {
+        let files = BatchBuilderGenerator::default().generate(&[]);
+        let src = &files[0].1;
+        assert!(src.contains("pub fn add_typed<R: DeserializeOwned + Send + 'static>("), "{src}");
+        assert!(
+            src.contains(
+                "pub async fn send_typed(self) -> Result<TypedBatchResults, TransportError>"
+            ),
+            "{src}"
+        );
+        assert!(
+            src.contains("pub fn take<R: 'static>(&mut self, handle: BatchHandle<R>)"),
+            "{src}"
+        );
+    }
+}
@@ -0,0 +1,116 @@
+//! Responses captured from regtest nodes, which the generated response types must decode.
+//!
+//! [`ResponseTypeCodeGenerator`](super::ResponseTypeCodeGenerator) turns each one into a
+//! test of the generated crate, so a field typed too narrowly (a `u32` for a `u64`, an
+//! unsigned amount for a negative one) fails `cargo test` there instead of a user's call.
+
+/// A response captured for `method`, with assertions on the decoded value.
+#[derive(Debug, Clone, Copy)]
+pub struct CapturedResponse {
+    /// The generated method (or verbosity overload) whose response type decodes `json`.
+    pub method: &'static str,
+    /// The `result` Bitcoin Core returned.
+    pub json: &'static str,
+    /// Statements run on the decoded `response`, e.g. `assert_eq!(response.fee, ..);`.
+    pub checks: &'static str,
+}
+
+/// A regtest `gettransaction` response for a payment sent by the wallet, whose `amount`
+/// and `fee` are negative.
+const GETTRANSACTION_SEND: &str = r#"{
+    "amount": -1.00000000,
+    "fee": -0.00000141,
+    "confirmations": 1,
+    "blockhash": "746a606ff20cda994bf809a6927c1b0f689ca2cad6397ab2bfdaaa8708f30b97",
+    "blockheight": 102,
+    "blockindex": 1,
+    "blocktime": 1700000000,
+    "txid": "834e4cd2824b6da55dbf7100de526019bf0b57342063019fad79b7cdaf66e64c",
+    "wtxid": "919c3d5e8e4792daa0df4d699598a0a9695b58d33dd728ee32df001c872e4ad9",
+    "walletconflicts": [],
+    "mempoolconflicts": [],
+    "time": 1699999990,
+    "timereceived": 1699999990,
+    "bip125-replaceable": "no",
+    "details": [{
+        "address": "bcrt1q5juj78fuy3lsp4s8telwe76ztyaehygr87t9nn",
+        "category": "send",
+        "amount": -1.00000000,
+        "vout": 0,
+        "fee": -0.00000141,
+        "abandoned": false
+    }],
+    "hex": "020000000001013a5f9dcbe94162c776fd87a2f7cee11c69db541c30c496db2d47b77d4c7688020000000000fdffffff0200e1f50500000000160014a4b92f1d3c247f00d6075e7eecfb42593b9b9103ecd90f2401000000160014a699d3682e1982d54cc8d6ea981160e729b9f235024730440220454349e422f05297191ead13e21d3db520e5abef52055e4964b82fb213f593a102201f3a718774c572bd8a25adbeb1bfcd5c0256ae11cecf9f9c3f925d0e52beaf890121028174099687a26621f4e2cdd7cc03b3dacedb3fb962255b1aafd033cabe83153000000000",
+    "lastprocessedblock": {
+        "hash": "746a606ff20cda994bf809a6927c1b0f689ca2cad6397ab2bfdaaa8708f30b97",
+        "height": 102
+    }
+}"#;
+
+/// A regtest `getrawtransaction <txid> 1` response for a confirmed P2WPKH spend.
+const GETRAWTRANSACTION_VERBOSE: &str = r#"{
+    "txid": "834e4cd2824b6da55dbf7100de526019bf0b57342063019fad79b7cdaf66e64c",
+    "hash": "919c3d5e8e4792daa0df4d699598a0a9695b58d33dd728ee32df001c872e4ad9",
+    "version": 2,
+    "size": 222,
+    "vsize": 141,
+    "weight": 561,
+    "locktime": 0,
+    "vin": [{
+        "txid": "0288764c7db7472ddb96c4301c54db691ce1cef7a287fd76c76241e9cb9d5f3a",
+        "vout": 0,
+        "scriptSig": {"asm": "", "hex": ""},
+        "txinwitness": [
+            "30440220454349e422f05297191ead13e21d3db520e5abef52055e4964b82fb213f593a102201f3a718774c572bd8a25adbeb1bfcd5c0256ae11cecf9f9c3f925d0e52beaf8901",
+            "028174099687a26621f4e2cdd7cc03b3dacedb3fb962255b1aafd033cabe831530"
+        ],
+        "sequence": 4294967293
+    }],
+    "vout": [{
+        "value": 1.0,
+        "n": 0,
+        "scriptPubKey": {
+            "asm": "0 a4b92f1d3c247f00d6075e7eecfb42593b9b9103",
+            "desc": "addr(bcrt1q5juj78fuy3lsp4s8telwe76ztyaehygr87t9nn)#vz5r2vuh",
+            "hex": "0014a4b92f1d3c247f00d6075e7eecfb42593b9b9103",
+            "address": "bcrt1q5juj78fuy3lsp4s8telwe76ztyaehygr87t9nn",
+            "type": "witness_v0_keyhash"
+        }
+    }, {
+        "value": 48.999859,
+        "n": 1,
+        "scriptPubKey": {
+            "asm": "0 a699d3682e1982d54cc8d6ea981160e729b9f235",
+            "desc": "addr(bcrt1q56vax6pwrxpd2nxg6m4fsytquu5mnu34lxls74)#0sxuph75",
+            "hex": "0014a699d3682e1982d54cc8d6ea981160e729b9f235",
+            "address": "bcrt1q56vax6pwrxpd2nxg6m4fsytquu5mnu34lxls74",
+            "type": "witness_v0_keyhash"
+        }
+    }],
+    "hex": "020000000001013a5f9dcbe94162c776fd87a2f7cee11c69db541c30c496db2d47b77d4c7688020000000000fdffffff0200e1f50500000000160014a4b92f1d3c247f00d6075e7eecfb42593b9b9103ecd90f2401000000160014a699d3682e1982d54cc8d6ea981160e729b9f235024730440220454349e422f05297191ead13e21d3db520e5abef52055e4964b82fb213f593a102201f3a718774c572bd8a25adbeb1bfcd5c0256ae11cecf9f9c3f925d0e52beaf890121028174099687a26621f4e2cdd7cc03b3dacedb3fb962255b1aafd033cabe83153000000000",
+    "blockhash": "746a606ff20cda994bf809a6927c1b0f689ca2cad6397ab2bfdaaa8708f30b97",
+    "confirmations": 1,
+    "time": 1700000000,
+    "blocktime": 1700000000
+}"#;
+
+/// Every captured response, in the order their tests are generated.
+pub const CAPTURED_RESPONSES: &[CapturedResponse] = &[
+    CapturedResponse {
+        method: "gettransaction",
+        json: GETTRANSACTION_SEND,
+        checks: "assert_eq!(response.amount, bitcoin::SignedAmount::from_sat(-100_000_000));
+        assert_eq!(response.fee, Some(bitcoin::SignedAmount::from_sat(-141)));
+        assert_eq!(response.details[0].fee, Some(bitcoin::SignedAmount::from_sat(-141)));",
+    },
+    CapturedResponse {
+        method: "getrawtransaction_verbose",
+        json: GETRAWTRANSACTION_VERBOSE,
+        checks: "assert_eq!(response.vin.len(), 1);
+        assert_eq!(response.vout.len(), 2);
+        let script = response.script_pubkey(0).unwrap().unwrap();
+        assert!(script.is_p2wpkh());
+        assert_eq!(script.to_hex_string(), \"0014a4b92f1d3c247f00d6075e7eecfb42593b9b9103\");
+        assert!(response.script_pubkey(2).is_none());",
+    },
+];
@@ -1,46 +1,88 @@
 // codegen/src/generators/client_trait.rs
 
-use bitcoin_rpc_conversions::TypeRegistry;
-use bitcoin_rpc_types::BtcMethod;
+use bitcoin_rpc_types::{BtcArgument, BtcMethod};
 
-use crate::utils::capitalize;
-use crate::CodeGenerator;
+use crate::generators::{doc_comment, verbosity};
+use crate::utils::{capitalize, sanitize_ident};
+use crate::{type_registry, CodeGenerator, MethodCategories, Version};
 
 /// Generator for creating Bitcoin RPC client traits for specific versions
 pub struct ClientTraitGenerator {
     version: String,
+    blocking: bool,
+    categories: MethodCategories,
 }
 
 impl ClientTraitGenerator {
     /// Create a new generator targeting a specific Bitcoin Core RPC version
     pub fn new(version: impl Into<String>) -> Self {
-        ClientTraitGenerator { version: version.into() }
+        ClientTraitGenerator {
+            version: version.into(),
+            blocking: false,
+            categories: MethodCategories::default(),
+        }
+    }
+
+    /// Also emit `blocking.rs` with `BlockingBitcoinClientV*`, a synchronous mirror of the
+    /// client trait backed by a `BlockingTransportTrait`.
+    ///
+    /// The module is declared behind the generated crate's `blocking` feature.
+    pub fn with_blocking(mut self, enabled: bool) -> Self {
+        self.blocking = enabled;
+        self
+    }
+
+    /// Put each method and its parameter struct behind the feature of its category.
+    pub fn with_categories(mut self, categories: MethodCategories) -> Self {
+        self.categories = categories;
+        self
     }
 }
 
 impl CodeGenerator for ClientTraitGenerator {
     fn generate(&self, methods: &[BtcMethod]) -> Vec<(String, String)> {
         // render client_trait.rs
-        let template = include_str!("../../../templates/client_trait.rs");
-        let client_trait = render_client_trait(template, methods, &self.version);
+        let template = include_str!("../../templates/client_trait.rs");
+        let client_trait = render_client_trait(template, methods, &self.version, &self.categories);
 
         // render mod.rs that re-exports the trait
         let version_no = format!(
             "V{}",
             self.version.trim_start_matches('v').trim_start_matches('V').replace('.', "_")
         );
-        let mod_rs = format!(
+        let mut mod_rs = format!(
             "//! Auto-generated module for BitcoinClient{version_no}\n\
              pub mod client;\n\
              pub use self::client::BitcoinClient{version_no};\n"
         );
+        let mut files = vec![("client.rs".into(), client_trait)];
+
+        if self.blocking {
+            let template = include_str!("../../templates/blocking_client_trait.rs");
+            files.push((
+                "blocking.rs".into(),
+                render_blocking_client_trait(template, methods, &self.version, &self.categories),
+            ));
+            mod_rs.push_str(&format!(
+                "#[cfg(feature = \"blocking\")]\n\
+                 pub mod blocking;\n\
+                 #[cfg(feature = \"blocking\")]\n\
+                 pub use self::blocking::BlockingBitcoinClient{version_no};\n"
+            ));
+        }
 
-        vec![("client.rs".into(), client_trait), ("mod.rs".into(), mod_rs)]
+        files.push(("mod.rs".into(), mod_rs));
+        files
     }
 }
 
-/// Render the client trait
-pub fn render_client_trait(template: &str, methods: &[BtcMethod], version: &str) -> String {
+/// Render the client trait, gating each method by `categories`
+pub fn render_client_trait(
+    template: &str,
+    methods: &[BtcMethod],
+    version: &str,
+    categories: &MethodCategories,
+) -> String {
     let mut out = template.to_owned();
 
     let version_no =
@@ -48,37 +90,166 @@ pub fn render_client_trait(template: &str, methods: &[BtcMethod], version: &str)
     out = out.replace("{{VERSION}}", version);
     out = out.replace("{{VERSION_NODOTS}}", &version_no);
 
-    out = out.replace("{{IMPORTS}}", &build_imports());
+    out = out.replace("{{IMPORTS}}", &build_imports(version));
 
     let param_structs = methods
         .iter()
-        .filter_map(|m| MethodTemplate::new(m).generate_param_struct())
+        .filter_map(|m| MethodTemplate::new(m).gated(categories).generate_param_struct())
         .collect::<Vec<_>>()
         .join("\n\n");
     out = out.replace("{{PARAM_STRUCTS}}", &param_structs);
 
-    let trait_methods =
-        methods.iter().map(|m| MethodTemplate::new(m).render()).collect::<Vec<_>>().join("\n\n");
-    out.replace("{{TRAIT_METHODS}}", &trait_methods)
+    let trait_method_decls = methods
+        .iter()
+        .map(|m| MethodTemplate::new(m).gated(categories).render_decl())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    out = out.replace("{{TRAIT_METHOD_DECLS}}", &trait_method_decls);
+
+    let trait_methods = methods
+        .iter()
+        .map(|m| MethodTemplate::new(m).gated(categories).render())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    out = out.replace("{{TRAIT_METHODS}}", &trait_methods);
+    out.push_str(&unset_arguments_test(methods, categories));
+    out
 }
 
-/// Bring in all the generated response types (e.g. `FooResponse`)
-fn build_imports() -> String {
-    ["crate::responses::*", "std::future::Future", "bitcoin_rpc_types::HashOrHeight"]
+/// A test module checking, through `MockTransport`, that calling the first method taking
+/// only optional arguments with all of them `None` sends no params at all, or an empty
+/// string if no method qualifies
+fn unset_arguments_test(methods: &[BtcMethod], categories: &MethodCategories) -> String {
+    use crate::utils::needs_parameter_reordering;
+
+    let Some(method) = methods.iter().find(|m| {
+        !m.arguments.is_empty()
+            && m.arguments.iter().all(|arg| !arg.required)
+            && !needs_parameter_reordering(&m.arguments)
+            && m.arguments
+                .iter()
+                .all(|arg| verbosity::fixed_value(&m.name, &arg.names[0]).is_none())
+            && doc_comment::deprecated_attribute(&m.description, "").is_empty()
+    }) else {
+        return String::new();
+    };
+    let template = MethodTemplate::new(method).gated(categories);
+    let rpc = verbosity::rpc_name(&method.name);
+    let nones = vec!["None"; method.arguments.len()].join(", ");
+    format!(
+        "
+
+#[cfg(test)]
+{cfg}mod tests {{
+    use super::*;
+    use crate::transport::MockTransport;
+
+    #[tokio::test]
+    async fn unset_optional_arguments_are_not_sent() {{
+        let mock = MockTransport::new();
+        mock.expect(\"{rpc}\", Ok(serde_json::Value::Null));
+        // Only the params sent matter; a `null` result need not decode
+        let _ = mock.{name}({nones}).await;
+        assert_eq!(mock.calls(\"{rpc}\"), vec![Vec::<serde_json::Value>::new()]);
+    }}
+}}
+",
+        cfg = template.cfg(""),
+        name = template.fn_name(),
+    )
+}
+
+/// Render the blocking client trait, mirroring [`render_client_trait`] without `async`
+pub fn render_blocking_client_trait(
+    template: &str,
+    methods: &[BtcMethod],
+    version: &str,
+    categories: &MethodCategories,
+) -> String {
+    let mut out = template.to_owned();
+
+    let version_no =
+        format!("V{}", version.trim_start_matches('v').trim_start_matches('V').replace('.', "_"));
+    out = out.replace("{{VERSION}}", version);
+    out = out.replace("{{VERSION_NODOTS}}", &version_no);
+
+    let responses = responses_glob(version);
+    let imports = [responses.as_str()].into_iter().chain(argument_imports()).collect::<Vec<_>>();
+    out = out.replace("{{IMPORTS}}", &render_imports(&imports));
+
+    let trait_method_decls = methods
+        .iter()
+        .map(|m| MethodTemplate::new(m).gated(categories).render_blocking_decl())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    out = out.replace("{{TRAIT_METHOD_DECLS}}", &trait_method_decls);
+
+    let trait_methods = methods
         .iter()
-        .map(|p| format!("use {p};"))
+        .map(|m| MethodTemplate::new(m).gated(categories).render_blocking())
         .collect::<Vec<_>>()
-        .join("\n")
+        .join("\n\n");
+    out.replace("{{TRAIT_METHODS}}", &trait_methods)
+}
+
+/// Bring in all the generated response types (e.g. `FooResponse`) of `version`
+fn build_imports(version: &str) -> String {
+    let responses = responses_glob(version);
+    let imports = [responses.as_str(), "std::future::Future"]
+        .into_iter()
+        .chain(argument_imports())
+        .collect::<Vec<_>>();
+    render_imports(&imports)
+}
+
+/// The types some arguments are mapped to by name (e.g. `HashOrHeight`), which any
+/// client trait may use
+fn argument_imports() -> impl Iterator<Item = &'static str> {
+    type_registry::NAMED_ARGUMENT_IMPORTS.iter().map(|(_, path)| *path)
+}
+
+/// Glob import of the response types module of `version`, e.g.
+/// `crate::responses::v30_responses::*`, so clients of several versions can share a crate
+fn responses_glob(version: &str) -> String {
+    let module = Version::from_string(version)
+        .map_or_else(|_| version.replace('.', "_"), |version| version.as_module_name());
+    format!("crate::responses::{module}_responses::*")
+}
+
+/// One `use` line per path
+fn render_imports(paths: &[&str]) -> String {
+    paths.iter().map(|p| format!("use {p};")).collect::<Vec<_>>().join("\n")
 }
 
+/// The parameter (or params-struct field) name for `arg`, e.g. `_blockhash`
+///
+/// The underscore prefix distinguishes parameters from other identifiers and already
+/// keeps keywords and leading digits valid (`_type`, `_10th`).
+fn param_ident(arg: &BtcArgument) -> String { format!("_{}", arg.names[0]) }
+
 /// Tiny DSL to turn one BtcMethod into its doc-comment + fn
 pub struct MethodTemplate<'a> {
     method: &'a BtcMethod,
+    feature: Option<String>,
 }
 
 impl<'a> MethodTemplate<'a> {
     /// Create a new MethodTemplate for the given BtcMethod
-    pub fn new(method: &'a BtcMethod) -> Self { MethodTemplate { method } }
+    pub fn new(method: &'a BtcMethod) -> Self { MethodTemplate { method, feature: None } }
+
+    /// Put the rendered items behind the feature of the method's category
+    pub fn gated(mut self, categories: &MethodCategories) -> Self {
+        self.feature = categories.feature(&self.method.name);
+        self
+    }
+
+    /// `#[cfg(feature = ..)]` line indented by `indent` if the method is gated
+    pub(crate) fn cfg(&self, indent: &str) -> String {
+        self.feature
+            .as_ref()
+            .map(|feature| format!("{indent}#[cfg(feature = \"{feature}\")]\n"))
+            .unwrap_or_default()
+    }
 
     /// Generate parameter struct for methods that require argument reordering
     pub fn generate_param_struct(&self) -> Option<String> {
@@ -94,55 +265,68 @@ impl<'a> MethodTemplate<'a> {
 
         let mut fields = Vec::new();
         for arg in &reordered_args {
-            let field_name = if arg.names[0] == "type" {
-                "r#_type".to_string()
-            } else {
-                format!("_{}", arg.names[0])
-            };
+            let field_name = param_ident(arg);
 
-            let (base_ty, _) = TypeRegistry::map_argument_type(arg);
+            let (base_ty, _) = type_registry::map_argument_type(arg);
             let field_type =
                 if !arg.required { format!("Option<{base_ty}>") } else { base_ty.to_string() };
 
             fields.push(format!("    pub {}: {},", field_name, field_type));
         }
 
-        // Generate custom serialization that converts struct to array in original order
+        // Generate custom serialization that converts struct to array in original order,
+        // leaving out the unset optional arguments after the last one sent
+        let mut present = Vec::new();
         let mut serialize_fields = Vec::new();
         for (original_idx, _) in self.method.arguments.iter().enumerate() {
             let reordered_idx = param_mapping.iter().position(|&x| x == original_idx).unwrap();
             let arg = &reordered_args[reordered_idx];
-            let field_name =
-                if arg.names[0] == "type" { "r#_type" } else { &format!("_{}", arg.names[0]) };
-            serialize_fields.push(format!("        seq.serialize_element(&self.{})?;", field_name));
+            let field_name = param_ident(arg);
+            present.push(if arg.required {
+                "true".to_string()
+            } else {
+                format!("self.{field_name}.is_some()")
+            });
+            serialize_fields.push(format!(
+                "        if len > {original_idx} {{\n            seq.serialize_element(&self.{field_name})?;\n        }}"
+            ));
         }
 
         Some(format!(
-            "#[derive(Debug, Clone, Deserialize)]\n\
+            "{}#[derive(Debug, Clone, Deserialize)]\n\
             pub struct {} {{\n\
             {}\n\
             }}\n\
             \n\
-            impl serde::Serialize for {} {{\n\
+            {}impl serde::Serialize for {} {{\n\
                 fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>\n\
                 where\n\
                     S: serde::Serializer,\n\
                 {{\n\
-                    let mut seq = serializer.serialize_seq(Some({}))?;\n\
+                    let present = [{}];\n\
+                    let len = present.iter().rposition(|p| *p).map_or(0, |i| i + 1);\n\
+                    let mut seq = serializer.serialize_seq(Some(len))?;\n\
             {}\n\
                     seq.end()\n\
                 }}\n\
             }}",
+            self.cfg(""),
             struct_name,
             fields.join("\n"),
+            self.cfg(""),
             struct_name,
-            self.method.arguments.len(),
+            present.join(", "),
             serialize_fields.join("\n")
         ))
     }
 
+    /// The literal an overload fixes `arg` to (see [`verbosity`]), if any
+    fn fixed_value(&self, arg: &BtcArgument) -> Option<&'static str> {
+        verbosity::fixed_value(&self.method.name, &arg.names[0])
+    }
+
     /// Render the /// doc lines
-    fn doc(&self) -> String {
+    pub(crate) fn doc(&self) -> String {
         self.method
             .description
             .trim()
@@ -152,36 +336,39 @@ impl<'a> MethodTemplate<'a> {
             .join("\n")
     }
 
+    /// `#[deprecated]` for the trait declaration of a deprecated method (the impls can't
+    /// carry one)
+    pub(crate) fn deprecated(&self) -> String {
+        doc_comment::deprecated_attribute(&self.method.description, "    ")
+    }
+
     /// Build the `, name: Type, ...` part of the fn signature
-    fn signature(&self) -> String {
+    fn signature(&self) -> String { self.signature_in("") }
+
+    /// [`Self::signature`], naming the parameter struct (if any) by the module prefix
+    /// `params_module`, e.g. `crate::client_trait::client::`
+    pub(crate) fn signature_in(&self, params_module: &str) -> String {
         use crate::utils::needs_parameter_reordering;
 
         // Check if this method requires argument reordering
         if needs_parameter_reordering(&self.method.arguments) {
             // Use a parameter struct for methods with ordering issues
             let struct_name = format!("{}Params", capitalize(&self.method.name));
-            format!(", params: {}", struct_name)
+            format!(", params: {params_module}{struct_name}")
         } else {
             // Use individual parameters for methods that don't require argument reordering
             let args = self
                 .method
                 .arguments
                 .iter()
+                .filter(|arg| self.fixed_value(arg).is_none())
                 .map(|arg| {
-                    // Add underscore prefix to all parameter names for consistency and clarity.
-                    // This distinguishes parameters from other identifiers and follows Rust conventions
-                    // for intentionally prefixed names. The special case for "type" uses r#_type
-                    // to properly escape the reserved keyword.
-                    let name = if arg.names[0] == "type" {
-                        "r#_type".to_string()
-                    } else {
-                        format!("_{}", arg.names[0])
-                    };
-                    let (base_ty, _) = TypeRegistry::map_argument_type(arg);
+                    let name = param_ident(arg);
+                    let (base_ty, _) = type_registry::map_argument_type(arg);
                     let ty = if !arg.required {
                         format!("Option<{base_ty}>")
                     } else {
-                        base_ty.to_string()
+                        type_registry::parameter_type(base_ty)
                     };
                     format!("{name}: {ty}")
                 })
@@ -194,9 +381,24 @@ impl<'a> MethodTemplate<'a> {
         }
     }
 
+    /// The `, name, ...` arguments that pass the signature's parameters on to another call
+    pub(crate) fn forwarded_args(&self) -> String {
+        use crate::utils::needs_parameter_reordering;
+
+        if needs_parameter_reordering(&self.method.arguments) {
+            return ", params".into();
+        }
+        self.method
+            .arguments
+            .iter()
+            .filter(|arg| self.fixed_value(arg).is_none())
+            .map(|arg| format!(", {}", param_ident(arg)))
+            .collect()
+    }
+
     /// Decide whether we return `()` or `FooResponse`
-    fn return_type(&self) -> String {
-        let none = self.method.results.first().is_none_or(|r| r.type_.eq_ignore_ascii_case("none"));
+    pub(crate) fn return_type(&self) -> String {
+        let none = self.method.results.first().is_none_or(|r| r.type_ == "none");
         if none {
             "()".into()
         } else {
@@ -217,10 +419,13 @@ impl<'a> MethodTemplate<'a> {
                 .arguments
                 .iter()
                 .map(|arg| {
-                    let name = if arg.names[0] == "type" {
-                        "r#_type"
-                    } else {
-                        &format!("_{}", arg.names[0])
+                    let name = match self.fixed_value(arg) {
+                        Some(value) => value.to_string(),
+                        None if arg.required => type_registry::parameter_value(
+                            type_registry::map_argument_type(arg).0,
+                            &param_ident(arg),
+                        ),
+                        None => param_ident(arg),
                     };
                     format!("            serde_json::json!({name}),")
                 })
@@ -229,30 +434,256 @@ impl<'a> MethodTemplate<'a> {
         }
     }
 
-    /// Assemble the full async fn stub
-    fn body(&self) -> String {
-        let name = self.method.name.to_lowercase();
+    /// Statement dropping the trailing `null`s of unset optional arguments from `params`,
+    /// so bitcoind sees the shortest positional list (empty if no optional argument trails)
+    fn trim_params(&self) -> String {
+        use crate::utils::needs_parameter_reordering;
+
+        // Parameter structs already serialize only up to the last argument sent
+        if needs_parameter_reordering(&self.method.arguments) {
+            return String::new();
+        }
+        let args = &self.method.arguments;
+        let sent = args
+            .iter()
+            .rposition(|arg| arg.required || self.fixed_value(arg).is_some())
+            .map_or(0, |i| i + 1);
+        if sent == args.len() {
+            return String::new();
+        }
+        format!(
+            "
+        while params.len() > {sent} && params.last().is_some_and(serde_json::Value::is_null) {{
+            params.pop();
+        }}"
+        )
+    }
+
+    /// `let params = vec![..];` with the arguments, trimmed by [`Self::trim_params`]
+    fn params_statement(&self) -> String {
+        let json = self.json_params();
+        match self.trim_params() {
+            trim if trim.is_empty() => format!("let params = vec![\n{json}\n        ];"),
+            trim => format!("let mut params = vec![\n{json}\n        ];{trim}"),
+        }
+    }
+
+    /// `async fn name(&self, ...) -> Result<..>` without a body
+    fn header(&self) -> String { self.fn_header("async fn") }
+
+    /// `<keyword> name(&self, ...) -> Result<..>` without a body
+    fn fn_header(&self, keyword: &str) -> String {
+        let name = self.fn_name();
         let sig = self.signature();
         let ret = self.return_type();
-        let json = self.json_params();
-        let rpc = &self.method.name;
+        let clippy_allow = self.clippy_allow();
+
+        format!("{clippy_allow}{keyword} {name}(&self{sig}) -> Result<{ret}, TransportError>")
+    }
+
+    /// The Rust name of the method, e.g. `getblockhash`
+    pub(crate) fn fn_name(&self) -> String { sanitize_ident(&self.method.name.to_lowercase()) }
 
-        // Add clippy allow for too many arguments if needed
-        let clippy_allow = if self.method.arguments.len() > 7 {
+    /// Clippy allow for too many arguments, if needed
+    pub(crate) fn clippy_allow(&self) -> &'static str {
+        if self.method.arguments.len() > 7 {
             "#[allow(clippy::too_many_arguments)]\n    "
         } else {
             ""
-        };
+        }
+    }
+
+    /// Assemble the full async fn stub
+    fn body(&self) -> String {
+        let header = self.header();
+        let ret = self.return_type();
+        let params = self.params_statement();
+        let rpc = verbosity::rpc_name(&self.method.name);
 
         format!(
-            "{clippy_allow}async fn {name}(&self{sig}) -> Result<{ret}, TransportError> {{
-        let params = vec![
-{json}
-        ];
+            "{header} {{
+        {params}
         self.dispatch_json::<{ret}>(\"{rpc}\", &params).await
     }}"
         )
     }
 
-    fn render(&self) -> String { format!("{}\n{}", self.doc(), self.body()) }
+    fn render(&self) -> String { format!("{}\n{}{}", self.doc(), self.cfg("    "), self.body()) }
+
+    /// Doc comment plus the bodiless declaration used in the trait definition
+    fn render_decl(&self) -> String {
+        format!("{}\n{}{}    {};", self.doc(), self.cfg("    "), self.deprecated(), self.header())
+    }
+
+    /// Assemble the full blocking fn, sending through `BlockingTransportTrait`
+    fn blocking_body(&self) -> String {
+        let header = self.fn_header("fn");
+        let params = self.params_statement();
+        let rpc = verbosity::rpc_name(&self.method.name);
+
+        format!(
+            "{header} {{
+        {params}
+        crate::transport::decode_result(\"{rpc}\", &self.send_request_blocking(\"{rpc}\", &params)?)
+    }}"
+        )
+    }
+
+    fn render_blocking(&self) -> String {
+        format!("{}\n{}{}", self.doc(), self.cfg("    "), self.blocking_body())
+    }
+
+    /// Doc comment plus the bodiless blocking declaration
+    fn render_blocking_decl(&self) -> String {
+        format!(
+            "{}\n{}{}    {};",
+            self.doc(),
+            self.cfg("    "),
+            self.deprecated(),
+            self.fn_header("fn")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_trait_is_declared_without_bodies_or_supertraits() {
+        let method: BtcMethod = serde_json::from_value(serde_json::json!({
+            "name": "getblockcount",
+            "description": "Returns the height of the most-work fully-validated chain.",
+            "arguments": [],
+            "results": [{"type": "number", "description": "The current block count"}],
+        }))
+        .unwrap();
+        let template = include_str!("../../templates/client_trait.rs");
+        let src = render_client_trait(template, &[method], "v30", &MethodCategories::default());
+
+        assert!(src.contains("pub trait BitcoinClientV30: Send + Sync {"), "{src}");
+        assert!(src.contains("use crate::responses::v30_responses::*;"), "{src}");
+        let decl = "async fn getblockcount(&self) -> Result<GetblockcountResponse, TransportError>";
+        assert!(src.contains(&format!("{decl};")), "{src}");
+        assert!(src.contains(&format!("{decl} {{")), "{src}");
+        // The escape hatch for un-modeled arguments is part of the trait
+        assert!(src.contains("    async fn call_extra(\n"), "{src}");
+    }
+
+    #[test]
+    fn test_blocking_client_mirrors_async_signatures() {
+        let method: BtcMethod = serde_json::from_value(serde_json::json!({
+            "name": "getblockhash",
+            "description": "Returns hash of block in best-block-chain at height provided.",
+            "arguments": [{"names": ["height"], "description": "The height index", "type": "number", "required": true}],
+            "results": [{"type": "string", "description": "The block hash"}],
+        }))
+        .unwrap();
+
+        let files = ClientTraitGenerator::new("v30").generate(std::slice::from_ref(&method));
+        assert!(files.iter().all(|(name, _)| name != "blocking.rs"));
+
+        let files = ClientTraitGenerator::new("v30").with_blocking(true).generate(&[method]);
+        let (_, src) = files.iter().find(|(name, _)| name == "blocking.rs").unwrap();
+        assert!(src.contains("pub trait BlockingBitcoinClientV30: Send + Sync {"), "{src}");
+        assert!(
+            src.contains("impl<T: BlockingTransportTrait> BlockingBitcoinClientV30 for T {"),
+            "{src}"
+        );
+        let decl =
+            "fn getblockhash(&self, _height: u64) -> Result<GetblockhashResponse, TransportError>";
+        assert!(src.contains(&format!("    {decl};")), "{src}");
+        assert!(src.contains(&format!("{decl} {{")), "{src}");
+        assert!(!src.contains("async fn"), "{src}");
+        assert!(src
+            .contains("    fn call_extra(&self, method: &str, params: &[Value], extra: &[Value])"));
+        assert!(src.contains("self.send_request_blocking(\"getblockhash\", &params)?"), "{src}");
+
+        let (_, mod_rs) = files.iter().find(|(name, _)| name == "mod.rs").unwrap();
+        assert!(mod_rs.contains("#[cfg(feature = \"blocking\")]\npub mod blocking;"), "{mod_rs}");
+    }
+
+    #[test]
+    fn test_unset_trailing_optional_arguments_are_not_sent() {
+        let method: BtcMethod = serde_json::from_value(serde_json::json!({
+            "name": "getblockhash",
+            "description": "",
+            "arguments": [
+                {"names": ["height"], "description": "", "type": "number", "required": true},
+                {"names": ["verbose"], "description": "", "type": "boolean", "required": false},
+            ],
+            "results": [{"type": "string", "description": "The block hash"}],
+        }))
+        .unwrap();
+        let body = MethodTemplate::new(&method).body();
+        assert!(body.contains(
+            "let mut params = vec![\n            serde_json::json!(_height),\n            serde_json::json!(_verbose),\n        ];\n        \
+             while params.len() > 1 && params.last().is_some_and(serde_json::Value::is_null) {"
+        ), "{body}");
+
+        // Optional arguments ahead of a required one keep their slot
+        let reordered: BtcMethod = serde_json::from_value(serde_json::json!({
+            "name": "sendmany",
+            "description": "",
+            "arguments": [
+                {"names": ["dummy"], "description": "", "type": "string", "required": false},
+                {"names": ["amounts"], "description": "", "type": "object", "required": true},
+                {"names": ["minconf"], "description": "", "type": "number", "required": false},
+            ],
+            "results": [],
+        }))
+        .unwrap();
+        let params = MethodTemplate::new(&reordered).generate_param_struct().unwrap();
+        assert!(
+            params
+                .contains("let present = [self._dummy.is_some(), true, self._minconf.is_some()];"),
+            "{params}"
+        );
+        assert!(
+            params.contains("if len > 2 {\n            seq.serialize_element(&self._minconf)?;")
+        );
+        assert!(!MethodTemplate::new(&reordered).body().contains("while params.len()"));
+    }
+
+    #[test]
+    fn test_generated_crate_checks_the_params_of_an_all_none_call() {
+        let getblockhash: BtcMethod = serde_json::from_value(serde_json::json!({
+            "name": "getblockhash",
+            "description": "",
+            "arguments": [{"names": ["height"], "description": "", "type": "number", "required": true}],
+            "results": [{"type": "string", "description": "The block hash"}],
+        }))
+        .unwrap();
+        let getbalance: BtcMethod = serde_json::from_value(serde_json::json!({
+            "name": "getbalance",
+            "description": "",
+            "arguments": [
+                {"names": ["dummy"], "description": "", "type": "string", "required": false},
+                {"names": ["minconf"], "description": "", "type": "number", "required": false},
+            ],
+            "results": [{"type": "amount", "description": "The balance"}],
+        }))
+        .unwrap();
+        let categories: MethodCategories =
+            [("getbalance".to_string(), "wallet".to_string())].into_iter().collect();
+        let template = include_str!("../../templates/client_trait.rs");
+
+        let src = render_client_trait(template, &[getblockhash, getbalance], "v30", &categories);
+        assert!(
+            src.contains(
+                "#[cfg(test)]\n#[cfg(feature = \"wallet\")]\nmod tests {\n    use super::*;"
+            ),
+            "{src}"
+        );
+        assert!(src.contains("async fn unset_optional_arguments_are_not_sent() {"), "{src}");
+        assert!(src.contains("mock.expect(\"getbalance\", Ok(serde_json::Value::Null));"));
+        assert!(src.contains("let _ = mock.getbalance(None, None).await;"), "{src}");
+        assert!(src.contains(
+            "assert_eq!(mock.calls(\"getbalance\"), vec![Vec::<serde_json::Value>::new()]);"
+        ));
+
+        // Nothing to check without a method taking only optional arguments
+        let src = render_client_trait(template, &[], "v30", &MethodCategories::default());
+        assert!(!src.contains("mod tests"), "{src}");
+    }
 }
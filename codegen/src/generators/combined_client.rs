@@ -0,0 +1,352 @@
+// codegen/src/generators/combined_client.rs
+
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+use bitcoin_rpc_types::BtcMethod;
+
+use crate::generators::client_trait::MethodTemplate;
+use crate::generators::response_type;
+use crate::{type_registry, CodeGenerator, MethodCategories, Version};
+
+/// Generator for `combined.rs`, whose `CombinedClient` spans every generated Core version
+///
+/// The client detects the node's version on first use and has one method per RPC of any
+/// version. Each call matches on the node's version and goes through the client trait of
+/// that version, failing with `CombinedClientError::Unsupported` on a node whose version
+/// lacks the method. Versions whose method takes the same arguments and returns the same
+/// response type share one method; an older version differing from the newest gets its
+/// own method, suffixed with its module name (e.g. `getblockstats_v29`).
+pub struct CombinedClientGenerator {
+    version: Version,
+    categories: MethodCategories,
+    older: Vec<(Version, Vec<BtcMethod>)>,
+}
+
+impl CombinedClientGenerator {
+    /// Create a new generator whose primary (newest) version is `version`
+    pub fn new(version: Version) -> Self {
+        CombinedClientGenerator {
+            version,
+            categories: MethodCategories::default(),
+            older: Vec::new(),
+        }
+    }
+
+    /// Gate the methods routed to the primary version like its client trait does
+    pub fn with_categories(mut self, categories: MethodCategories) -> Self {
+        self.categories = categories;
+        self
+    }
+
+    /// Also route to the client trait of the older `version`, generated from `methods`
+    pub fn with_older_version(mut self, version: Version, methods: Vec<BtcMethod>) -> Self {
+        self.older.push((version, methods));
+        self
+    }
+}
+
+impl CodeGenerator for CombinedClientGenerator {
+    /// `methods` are the ones the primary version's client trait was generated from
+    fn generate(&self, methods: &[BtcMethod]) -> Vec<(String, String)> {
+        let mut versions = self.older.iter().map(|(v, m)| (v, m.as_slice())).collect::<Vec<_>>();
+        versions.push((&self.version, methods));
+        versions.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let imports = type_registry::NAMED_ARGUMENT_IMPORTS
+            .iter()
+            .map(|(_, path)| format!("use {path};\n"))
+            .collect::<String>();
+        let mut out = COMBINED_CLIENT_HEADER.replace("{{ARGUMENT_IMPORTS}}", &imports);
+        let mut seen = BTreeSet::new();
+        for (_, methods) in versions.iter().rev() {
+            for method in *methods {
+                if !seen.insert(method.name.as_str()) {
+                    continue;
+                }
+                // The versions having the method, newest first, grouped by its shape there
+                let mut groups: Vec<(&Version, &BtcMethod, Vec<&Version>)> = Vec::new();
+                for (version, methods) in versions.iter().rev() {
+                    let Some(m) = methods.iter().find(|m| m.name == method.name) else {
+                        continue;
+                    };
+                    match groups.iter_mut().find(|(_, first, _)| shape(first) == shape(m)) {
+                        Some((_, _, members)) => members.push(version),
+                        None => groups.push((version, m, vec![version])),
+                    }
+                }
+
+                let suffixed = groups[1..]
+                    .iter()
+                    .map(|(version, _, _)| (*version, suffixed_name(method, version)))
+                    .collect::<Vec<_>>();
+                for (i, (version, method, members)) in groups.iter().enumerate() {
+                    let is_primary = *version == &self.version;
+                    let categories = if is_primary {
+                        self.categories.clone()
+                    } else {
+                        MethodCategories::default()
+                    };
+                    let template = MethodTemplate::new(method).gated(&categories);
+                    let client_module = if is_primary {
+                        "crate::client_trait::client".to_string()
+                    } else {
+                        format!("crate::client_trait::{}::client", version.as_module_name())
+                    };
+                    let route = Route {
+                        name: if i == 0 {
+                            template.fn_name()
+                        } else {
+                            suffixed_name(method, version)
+                        },
+                        version,
+                        client_module,
+                        members,
+                        version_count: versions.len(),
+                        others: if i == 0 { &suffixed } else { &[] },
+                    };
+                    render_method(&mut out, &template, &route);
+                }
+            }
+        }
+        out.push_str("}\n");
+
+        vec![("combined.rs".to_string(), out)]
+    }
+}
+
+/// The `ClientVersion` variant of `version`, e.g. `V29_1`
+fn variant(version: &Version) -> String {
+    format!("V{}", version.as_str().trim_start_matches('v').replace('.', "_"))
+}
+
+/// What a call through a client trait depends on: the parameters (and parameter struct) it
+/// takes and the response type it decodes into, without their doc comments
+fn shape(method: &BtcMethod) -> (String, String, String) {
+    let without_docs = |src: String| {
+        src.lines().filter(|line| !line.trim_start().starts_with("///")).collect::<String>()
+    };
+    let template = MethodTemplate::new(method);
+    let params = template.generate_param_struct().unwrap_or_default();
+    let response = response_type::build_return_type(method).ok().flatten().unwrap_or_default();
+    (template.signature_in(""), without_docs(params), without_docs(response))
+}
+
+/// The `CombinedClient` method calling `method` on `version` when it differs from the
+/// newest version's, e.g. `getblockstats_v29`
+fn suffixed_name(method: &BtcMethod, version: &Version) -> String {
+    format!("{}_{}", MethodTemplate::new(method).fn_name(), version.as_module_name())
+}
+
+/// Where a `CombinedClient` method sends its call
+struct Route<'a> {
+    /// Name of the `CombinedClient` method
+    name: String,
+    /// The newest version of `members`, whose client trait makes the call
+    version: &'a Version,
+    /// The module of that client trait
+    client_module: String,
+    /// The versions sharing the method's shape, which the method serves
+    members: &'a [&'a Version],
+    /// How many versions are generated
+    version_count: usize,
+    /// The suffixed methods serving older versions whose shape differs, as
+    /// `(version, method name)`
+    others: &'a [(&'a Version, String)],
+}
+
+/// Append the `CombinedClient` method calling `template`'s method through the client trait
+/// of the node's version, as the route says
+fn render_method(out: &mut String, template: &MethodTemplate, route: &Route) {
+    let name = &route.name;
+    let version = route.version;
+    let client_module = &route.client_module;
+    let sig = template.signature_in(&format!("{client_module}::"));
+    let ret = match template.return_type() {
+        unit if unit == "()" => unit,
+        ty => format!("crate::responses::{}_responses::{ty}", version.as_module_name()),
+    };
+    let client_trait = format!("{client_module}::BitcoinClient{}", variant(version));
+    let args = template.forwarded_args();
+    let supported_docs =
+        route.members.iter().map(|version| version.as_str()).collect::<Vec<_>>().join(", ");
+    let other_docs = route
+        .others
+        .iter()
+        .map(|(version, method)| {
+            format!(
+                "\n    ///\n    /// Bitcoin Core {} takes other arguments or returns another type; \
+                 call [`Self::{method}`] there.",
+                version.as_str()
+            )
+        })
+        .collect::<String>();
+    let pattern = route
+        .members
+        .iter()
+        .map(|version| format!("ClientVersion::{}", variant(version)))
+        .collect::<Vec<_>>()
+        .join(" | ");
+    let unsupported = if route.members.len() == route.version_count {
+        String::new()
+    } else {
+        format!(
+            "            version => Err(CombinedClientError::Unsupported {{ method: \"{name}\", version }}),\n"
+        )
+    };
+
+    writeln!(
+        out,
+        "{doc}\n    ///\n    /// Supported on Bitcoin Core {supported_docs}.{other_docs}\n\
+         {cfg}{deprecated}    {clippy_allow}pub async fn {name}(&self{sig}) -> Result<{ret}, CombinedClientError> {{\n        \
+         match self.version().await? {{\n            \
+         {pattern} => Ok({client_trait}::{method}(&self.transport{args}).await?),\n\
+         {unsupported}        \
+         }}\n    \
+         }}\n",
+        doc = template.doc(),
+        cfg = template.cfg("    "),
+        deprecated = template.deprecated(),
+        clippy_allow = template.clippy_allow(),
+        method = template.fn_name(),
+    )
+    .unwrap();
+}
+
+/// Everything of `combined.rs` but the RPC methods, which close its last `impl` block
+const COMBINED_CLIENT_HEADER: &str = r#"//! `CombinedClient`, one client for every Bitcoin Core version this crate has clients for.
+
+use std::sync::OnceLock;
+
+use serde_json::Value;
+
+{{ARGUMENT_IMPORTS}}use crate::transport::{NodeVersion, TransportError, TransportTrait};
+use crate::versions::ClientVersion;
+
+/// Error of a [`CombinedClient`] call
+#[derive(Debug, thiserror::Error)]
+pub enum CombinedClientError {
+    /// The call failed in the transport or on the node
+    #[error(transparent)]
+    Transport(#[from] TransportError),
+    /// The node's version has no such method
+    #[error("`{method}` is unsupported on Bitcoin Core v{}.{}.{}", .version.core_version().0, .version.core_version().1, .version.core_version().2)]
+    Unsupported { method: &'static str, version: ClientVersion },
+    /// The node predates every generated client
+    #[error("Bitcoin Core {0} predates every generated client")]
+    UnsupportedNode(NodeVersion),
+}
+
+/// A client for every Bitcoin Core version this crate has clients for
+///
+/// It asks the node for its version on first use (see [`ClientVersion::for_node`]). Each
+/// method calls the client trait of the node's version, and fails with
+/// [`CombinedClientError::Unsupported`] if that version lacks the method. Where an older
+/// version's method takes other arguments or returns another type than the newest's, it
+/// has its own method suffixed with the version, e.g. `getblockstats_v29`.
+#[derive(Debug)]
+pub struct CombinedClient<T> {
+    transport: T,
+    version: OnceLock<ClientVersion>,
+}
+
+impl<T: TransportTrait> CombinedClient<T> {
+    /// Wrap `transport`, detecting the node's version on first use
+    pub fn new(transport: T) -> Self { Self { transport, version: OnceLock::new() } }
+
+    /// Wrap `transport` for a node served by `version`, skipping detection
+    pub fn with_version(transport: T, version: ClientVersion) -> Self {
+        Self { transport, version: OnceLock::from(version) }
+    }
+
+    /// The wrapped transport
+    pub fn transport(&self) -> &T { &self.transport }
+
+    /// The client version serving the node, asking the node via `getnetworkinfo` only once
+    pub async fn version(&self) -> Result<ClientVersion, CombinedClientError> {
+        if let Some(version) = self.version.get() {
+            return Ok(*version);
+        }
+        let info = self.transport.send_request("getnetworkinfo", &[]).await?;
+        let version = info.get("version").and_then(Value::as_u64).ok_or_else(|| {
+            TransportError::Rpc("getnetworkinfo returned no integer `version`".to_string())
+        })?;
+        let node = NodeVersion::from_core_int(version);
+        let version =
+            ClientVersion::for_node(node).ok_or(CombinedClientError::UnsupportedNode(node))?;
+        Ok(*self.version.get_or_init(|| version))
+    }
+}
+
+#[allow(deprecated)]
+impl<T: TransportTrait> CombinedClient<T> {
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn method(name: &str, result_type: &str) -> BtcMethod {
+        serde_json::from_value(serde_json::json!({
+            "name": name,
+            "description": format!("Calls {name}."),
+            "arguments": [],
+            "results": [{"type": result_type, "description": "A value"}],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_methods_dispatch_on_the_node_version() {
+        let v29 = Version::from_string("v29").unwrap();
+        let v30 = Version::from_string("v30").unwrap();
+        let files = CombinedClientGenerator::new(v30)
+            .with_older_version(
+                v29,
+                vec![
+                    method("getblockcount", "number"),
+                    method("getoldinfo", "number"),
+                    method("getchanged", "string"),
+                ],
+            )
+            .generate(&[
+                method("getblockcount", "number"),
+                method("getnewinfo", "number"),
+                method("getchanged", "number"),
+            ]);
+        let (name, src) = &files[0];
+        assert_eq!(name, "combined.rs");
+
+        // Same shape on both versions: one method serving both, with no fallback arm
+        assert!(src.contains(
+            "match self.version().await? {\n            \
+             ClientVersion::V30 | ClientVersion::V29 => Ok(crate::client_trait::client::BitcoinClientV30::getblockcount(&self.transport).await?),\n        \
+             }"
+        ), "{src}");
+        assert!(src.contains(
+            "pub async fn getoldinfo(&self) -> Result<crate::responses::v29_responses::GetoldinfoResponse, CombinedClientError>"
+        ), "{src}");
+        assert!(src.contains(
+            "ClientVersion::V29 => Ok(crate::client_trait::v29::client::BitcoinClientV29::getoldinfo(&self.transport).await?),\n            \
+             version => Err(CombinedClientError::Unsupported { method: \"getoldinfo\", version }),"
+        ), "{src}");
+        assert!(src.contains("ClientVersion::V30 => Ok(crate::client_trait::client::BitcoinClientV30::getnewinfo("), "{src}");
+
+        // A differing response type gets a method of its own on the older version
+        assert!(src.contains(
+            "pub async fn getchanged(&self) -> Result<crate::responses::v30_responses::GetchangedResponse, CombinedClientError>"
+        ), "{src}");
+        assert!(src.contains("/// Bitcoin Core v29 takes other arguments or returns another type; call [`Self::getchanged_v29`] there."), "{src}");
+        assert!(src.contains(
+            "pub async fn getchanged_v29(&self) -> Result<crate::responses::v29_responses::GetchangedResponse, CombinedClientError>"
+        ), "{src}");
+        assert!(src.contains(
+            "ClientVersion::V29 => Ok(crate::client_trait::v29::client::BitcoinClientV29::getchanged(&self.transport).await?),"
+        ), "{src}");
+
+        // Argument types are imported from the registry
+        for (_, path) in type_registry::NAMED_ARGUMENT_IMPORTS {
+            assert!(src.contains(&format!("use {path};\n")), "{src}");
+        }
+    }
+}
@@ -1,6 +1,9 @@
 // codegen/src/doc_comment_generator.rs
 
-use bitcoin_rpc_types::BtcMethod;
+use bitcoin_rpc_types::{BtcArgument, BtcMethod};
+
+use crate::type_registry;
+use crate::utils::{camel_to_snake_case, rust_type_for_argument, sanitize_ident};
 
 /// Format documentation comments
 pub fn format_doc_comment(description: &str) -> String {
@@ -119,55 +122,143 @@ pub fn format_struct_field(field_name: &str, field_type: &str, description: &str
     }
 }
 
-/// Generate example usage documentation for an RPC method
-pub fn generate_example_docs(method: &BtcMethod, version: &str) -> String {
-    let mut docs = String::new();
-    docs.push_str("//! This file is auto-generated. Do not edit manually.\n");
-    docs.push_str(&format!("//! Generated from Bitcoin Core v{}\n\n", version));
-
-    if !method.description.trim().is_empty() {
-        for line in method.description.lines().filter(|l| !l.trim().is_empty()) {
-            docs.push_str("/// ");
-            docs.push_str(line.trim());
-            docs.push('\n');
-        }
+/// The `//!` header of a generated file
+pub fn generated_file_header(version: &str) -> String {
+    format!(
+        "//! This file is auto-generated. Do not edit manually.\n\
+         //! Generated from Bitcoin Core v{version}\n"
+    )
+}
+
+/// `///` lines for a method's description, skipping blank lines
+pub fn format_description(description: &str) -> String {
+    description
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(|l| format!("/// {l}\n"))
+        .collect()
+}
+
+/// The `#[deprecated]` note for a method or argument whose `description` carries Core's
+/// `DEPRECATED` marker, or `None` if it is not deprecated.
+///
+/// A replacement named in the description ("Identical to getbalances().mine.untrusted_pending",
+/// "use X instead") is suggested in the note.
+pub fn deprecation_note(description: &str) -> Option<String> {
+    if !description.contains("DEPRECATED") {
+        return None;
     }
+    let replacement = ["Identical in functionality to ", "Identical to ", "identical to "]
+        .iter()
+        .find_map(|marker| description.split_once(marker).map(|(_, rest)| rest))
+        .and_then(|rest| rest.split_whitespace().next())
+        .or_else(|| {
+            let words = description.split_whitespace().collect::<Vec<_>>();
+            words.windows(3).find_map(|w| {
+                (w[0].eq_ignore_ascii_case("use") && w[2].starts_with("instead")).then_some(w[1])
+            })
+        })
+        .map(|name| name.trim_matches(|c: char| c == '`' || c == ',' || c == '.'))
+        .filter(|name| !name.is_empty());
+    Some(match replacement {
+        Some(name) => format!("deprecated by Bitcoin Core; use `{name}` instead"),
+        None => "deprecated by Bitcoin Core".to_string(),
+    })
+}
+
+/// A `#[deprecated]` line indented by `indent`, if `description` is deprecated (see
+/// [`deprecation_note`]); otherwise an empty string
+pub fn deprecated_attribute(description: &str, indent: &str) -> String {
+    deprecation_note(description)
+        .map(|note| format!("{indent}#[deprecated(note = {note:?})]\n"))
+        .unwrap_or_default()
+}
 
-    // Add example usage
-    let params_comment = if !method.arguments.is_empty() { "/* params */" } else { "" };
-    let params_with_comma = if !method.arguments.is_empty() { ", /* params */" } else { "" };
+/// Generate example usage documentation for an RPC method
+///
+/// Both examples show a typed call with placeholder arguments: the high-level client
+/// first, then the transport function. They are `rust,ignore` because they need a
+/// running node. Arguments without a literal placeholder are passed as variables named
+/// after the argument, declared with their type on hidden lines.
+pub fn generate_example_docs(method: &BtcMethod) -> String {
+    let mut bindings = Vec::new();
+    let client_args = method
+        .arguments
+        .iter()
+        .map(|a| example_argument(a, &rust_type_for_argument(&a.names[0], &a.type_), &mut bindings))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let transport_args = method
+        .arguments
+        .iter()
+        .map(|a| {
+            let (ty, _) = type_registry::map_argument_type(a);
+            if a.required {
+                format!(", {}", example_argument(a, ty, &mut bindings))
+            } else {
+                ", None".to_string()
+            }
+        })
+        .collect::<String>();
+    let bindings = bindings.concat();
 
-    docs.push_str(&format!(
-        "/// # Example: High-Level Client Usage (Recommended)
-/// ```rust
-/// use bitcoin_rpc_midas::*;
+    format!(
+        "/// # Examples
+///
+/// With the high-level client:
+/// ```rust,ignore
+/// use bitcoin_rpc_midas::prelude::*;
 ///
-/// async fn example() -> Result<(), Box<dyn std::error::Error>> {{
-/// let client = BitcoinTestClient::new().await?;
-/// let result = client.{name}({p1}).await?;
-/// # Ok(())
-/// # }}
+{bindings}/// let client = BitcoinTestClient::new().await?;
+/// let result = client.{client_fn}({client_args}).await?;
 /// ```
-/// # Example: Advanced - Direct Transport Function Usage
-/// This approach is for advanced users who need direct control over the transport layer.
-/// Most users should prefer the high-level client approach above.
-/// ```rust
-/// use bitcoin_rpc_midas::transport::{name};
-/// use bitcoin_rpc_midas::transport::{{TransportTrait, DefaultTransport}};
 ///
-/// async fn example() -> Result<(), Box<dyn std::error::Error>> {{
-/// let transport = DefaultTransport::new(
+/// With the transport function directly, for full control over the transport layer:
+/// ```rust,ignore
+/// use bitcoin_rpc_midas::transport::{{{name}, DefaultTransport}};
+///
+{bindings}/// let transport = DefaultTransport::new(
 ///     \"http://127.0.0.1:18443\".to_string(),
-///     Some((\"rpcuser\".to_string(), \"rpcpassword\".to_string()))
+///     Some((\"rpcuser\".to_string(), \"rpcpassword\".to_string())),
 /// );
-/// let result = {name}(&transport{p2}).await?;
-/// # Ok(())
-/// # }}
+/// let result = {name}(&transport{transport_args}).await?;
 /// ```",
         name = method.name,
-        p1 = params_comment,
-        p2 = params_with_comma,
-    ));
+        client_fn = camel_to_snake_case(&method.name),
+    )
+}
+
+/// The example expression for `arg`, of type `rust_ty`: a literal placeholder if there is
+/// one, else a variable named after the argument, whose hidden declaration is added to
+/// `bindings`
+fn example_argument(arg: &BtcArgument, rust_ty: &str, bindings: &mut Vec<String>) -> String {
+    example_value(rust_ty).unwrap_or_else(|| {
+        let ident = sanitize_ident(&camel_to_snake_case(&arg.names[0]));
+        let binding = format!("/// # let {ident}: {rust_ty} = unimplemented!();\n");
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+        ident
+    })
+}
 
-    docs.trim_end().to_string()
+/// A placeholder expression of type `rust_ty` for an example call, if it has a literal one
+fn example_value(rust_ty: &str) -> Option<String> {
+    let value = match rust_ty {
+        ty if ty.starts_with("Option<") => "None".to_string(),
+        ty if ty.starts_with("Vec<") => "Vec::new()".to_string(),
+        "u8" | "u16" | "u32" | "u64" | "usize" | "i32" | "i64" => "0".to_string(),
+        "f64" => "0.0".to_string(),
+        "bool" => "false".to_string(),
+        "String" => "String::new()".to_string(),
+        "serde_json::Value" => "serde_json::json!({})".to_string(),
+        "bitcoin::Amount" => "bitcoin::Amount::ZERO".to_string(),
+        "AddressType" => "AddressType::Bech32m".to_string(),
+        "EstimateMode" => "EstimateMode::Unset".to_string(),
+        "bitcoin::BlockHash" | "bitcoin::Txid" | "bitcoin::Wtxid" =>
+            format!("{rust_ty}::all_zeros()"),
+        _ => return None,
+    };
+    Some(value)
 }
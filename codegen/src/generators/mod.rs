@@ -19,6 +19,9 @@ pub mod doc_comment;
 pub mod response_type;
 pub use response_type::ResponseTypeCodeGenerator;
 
+/// Regtest responses the generated response types are tested against.
+pub mod captured_responses;
+
 /// Sub-crate generates: **`client_trait`**
 ///
 /// Generates the client trait that defines the interface for Bitcoin RPC clients.
@@ -26,6 +29,13 @@ pub use response_type::ResponseTypeCodeGenerator;
 pub mod client_trait;
 pub use client_trait::ClientTraitGenerator;
 
+/// Sub-crate generates: **`combined_client`**
+///
+/// Generates `CombinedClient`, which detects the node's version and routes each call to
+/// the generated client trait of that version.
+pub mod combined_client;
+pub use combined_client::CombinedClientGenerator;
+
 // Sub-crate generates: **`batch_builder`**
 ///
 /// Generates a fluent `BatchBuilder` with one method-per-RPC that queues
@@ -33,4 +43,32 @@ pub use client_trait::ClientTraitGenerator;
 pub mod batch_builder;
 pub use batch_builder::BatchBuilderGenerator;
 
+/// Sub-crate generates: **`option_object`**
+///
+/// Documents the sub-fields of `object` arguments and generates typed builders for the
+/// best-known option objects (e.g. `listunspent`'s `query_options`).
+pub mod option_object;
+pub use option_object::OptionObjectGenerator;
+
+/// Sub-crate generates: **`rpc_error`**
+///
+/// Emits the shared `BitcoinRpcError` enum of Bitcoin Core's error codes, and records the
+/// `RPC_*` errors each method's description documents.
+pub mod rpc_error;
+pub use rpc_error::RpcErrorGenerator;
+
+/// Sub-crate generates: **`rpc_component`**
+///
+/// Emits the `RpcComponent` serving each method and `ComponentTransport`, which routes
+/// calls to a transport per component (e.g. a separate wallet process).
+pub mod rpc_component;
+pub use rpc_component::RpcComponentGenerator;
+
 pub mod test_node;
+
+/// Sub-crate generates: **`verbosity`**
+///
+/// Declares the verbosity overloads (e.g. `getblock_hex`, `getblock_verbose2`) generated
+/// next to methods whose result shape depends on a verbosity argument, one typed wrapper
+/// and response per form.
+pub mod verbosity;
@@ -0,0 +1,256 @@
+// codegen/src/generators/option_object.rs
+
+use std::fmt::Write;
+
+use bitcoin_rpc_types::{BtcArgument, BtcMethod};
+
+use crate::utils::{camel_to_snake_case, capitalize, sanitize_ident};
+use crate::{type_registry, CodeGenerator};
+
+/// Option objects that get a typed builder, as `(method, argument)`.
+///
+/// Entries missing from the schema being generated (e.g. `createwallet` has no options
+/// object in recent Core versions) are skipped.
+pub const TYPED_OPTION_OBJECTS: &[(&str, &str)] =
+    &[("createwallet", "options"), ("listunspent", "query_options")];
+
+/// An `object` argument together with the sub-fields its schema documents.
+///
+/// `BtcArgument` does not carry nested schemas, so these are loaded separately with
+/// [`load_option_objects_from_file`](crate::load_option_objects_from_file).
+#[derive(Debug, Clone)]
+pub struct OptionObject {
+    /// The method taking the argument.
+    pub method: String,
+    /// The argument's name.
+    pub argument: String,
+    /// The documented sub-fields, in schema order.
+    pub fields: Vec<BtcArgument>,
+}
+
+impl OptionObject {
+    /// Name of the typed builder, e.g. `ListunspentQueryOptions`.
+    pub fn struct_name(&self) -> String {
+        format!("{}{}", capitalize(&self.method), capitalize(&self.argument))
+    }
+
+    /// Whether this object gets a typed builder (see [`TYPED_OPTION_OBJECTS`]).
+    pub fn is_typed(&self) -> bool {
+        TYPED_OPTION_OBJECTS.iter().any(|(m, a)| *m == self.method && *a == self.argument)
+    }
+
+    /// `///` lines listing the sub-fields, for the doc comment of a function taking this
+    /// object, plus a pointer to its typed builder if it has one.
+    pub fn field_docs(&self) -> String {
+        let mut doc = format!("///\n/// # `{}` fields\n", self.argument);
+        for field in &self.fields {
+            let presence = if field.required { "required" } else { "optional" };
+            let description = field.description.split_whitespace().collect::<Vec<_>>().join(" ");
+            write!(doc, "/// - `{}` ({}, {presence})", field.names[0], field.type_).unwrap();
+            if !description.is_empty() {
+                write!(doc, ": {}", description.replace('`', "``")).unwrap();
+            }
+            doc.push('\n');
+        }
+        if self.is_typed() {
+            writeln!(
+                doc,
+                "///\n/// Build it with [`{0}`](crate::transport::{0}).",
+                self.struct_name()
+            )
+            .unwrap();
+        }
+        doc
+    }
+}
+
+/// Generates `options.rs` with a typed builder for each option object in
+/// [`TYPED_OPTION_OBJECTS`].
+///
+/// Every builder serializes to the JSON object Bitcoin Core expects, and converts into the
+/// `serde_json::Value` the generated wrappers take for the argument.
+pub struct OptionObjectGenerator {
+    objects: Vec<OptionObject>,
+}
+
+impl OptionObjectGenerator {
+    /// Create a generator for the typed objects among `objects`.
+    pub fn new(objects: &[OptionObject]) -> Self {
+        Self { objects: objects.iter().filter(|o| o.is_typed()).cloned().collect() }
+    }
+}
+
+impl CodeGenerator for OptionObjectGenerator {
+    fn generate(&self, methods: &[BtcMethod]) -> Vec<(String, String)> {
+        let mut code = String::from(
+            "//! Typed builders for option-object arguments.\n\n\
+             use serde::Serialize;\n\
+             use serde_json::Value;\n",
+        );
+        for object in &self.objects {
+            if methods.iter().any(|m| m.name == object.method) {
+                code.push('\n');
+                code.push_str(&render_builder(object));
+            }
+        }
+        vec![("options".to_string(), code)]
+    }
+}
+
+/// The Rust field name for a sub-field, e.g. `minimum_amount` for `minimumAmount`.
+fn field_ident(field: &BtcArgument) -> String {
+    sanitize_ident(&camel_to_snake_case(&field.names[0].replace('-', "_")))
+}
+
+/// Render one builder struct with its setters and `Value` conversion.
+fn render_builder(object: &OptionObject) -> String {
+    let name = object.struct_name();
+    let mut fields = String::new();
+    let mut setters = String::new();
+    for field in &object.fields {
+        // Keyword fields such as `type` keep their raw identifier in the setter too
+        let ident = field_ident(field);
+        let (ty, _) = type_registry::map_argument_type(field);
+        let description = field.description.split_whitespace().collect::<Vec<_>>().join(" ");
+
+        if !description.is_empty() {
+            writeln!(fields, "    /// {description}").unwrap();
+        }
+        write!(fields, "    #[serde(rename = \"{}\"", field.names[0]).unwrap();
+        if ty == "bitcoin::Amount" {
+            fields.push_str(", with = \"bitcoin::amount::serde::as_btc::opt\"");
+        }
+        writeln!(fields, ", skip_serializing_if = \"Option::is_none\")]").unwrap();
+        writeln!(fields, "    pub {ident}: Option<{ty}>,").unwrap();
+
+        writeln!(
+            setters,
+            "    /// Set `{}`.\n    \
+             pub fn {ident}(mut self, {ident}: {ty}) -> Self {{\n        \
+             self.{ident} = Some({ident});\n        \
+             self\n    \
+             }}\n",
+            field.names[0]
+        )
+        .unwrap();
+    }
+
+    format!(
+        "/// The `{argument}` argument of `{method}`.\n\
+         ///\n\
+         /// Unset fields are omitted, so Bitcoin Core applies its defaults.\n\
+         #[derive(Debug, Clone, Default, PartialEq, Serialize)]\n\
+         pub struct {name} {{\n\
+         {fields}}}\n\
+         \n\
+         impl {name} {{\n    \
+         /// An empty object: every field uses Bitcoin Core's default.\n    \
+         pub fn new() -> Self {{ Self::default() }}\n\n\
+         {setters}}}\n\
+         \n\
+         impl From<{name}> for Value {{\n    \
+         fn from(options: {name}) -> Self {{\n        \
+         serde_json::to_value(options).expect(\"option objects always serialize\")\n    \
+         }}\n\
+         }}\n",
+        argument = object.argument,
+        method = object.method,
+        setters = setters.trim_end().to_string() + "\n",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(name: &str, type_: &str, description: &str) -> BtcArgument {
+        BtcArgument {
+            names: vec![name.into()],
+            description: description.into(),
+            oneline_description: String::new(),
+            also_positional: false,
+            type_str: None,
+            required: false,
+            hidden: false,
+            type_: type_.into(),
+        }
+    }
+
+    fn query_options() -> OptionObject {
+        OptionObject {
+            method: "listunspent".into(),
+            argument: "query_options".into(),
+            fields: vec![
+                field("minimumAmount", "amount", "Minimum value of each UTXO in BTC"),
+                field("include_immature_coinbase", "boolean", "Include immature coinbase UTXOs"),
+            ],
+        }
+    }
+
+    #[test]
+    fn field_docs_list_sub_fields_and_builder() {
+        let docs = query_options().field_docs();
+        assert!(docs.contains("/// # `query_options` fields"));
+        assert!(docs.contains(
+            "/// - `minimumAmount` (amount, optional): Minimum value of each UTXO in BTC"
+        ));
+        assert!(
+            docs.contains("[`ListunspentQueryOptions`](crate::transport::ListunspentQueryOptions)")
+        );
+
+        let untyped = OptionObject { method: "bumpfee".into(), ..query_options() };
+        assert!(!untyped.field_docs().contains("Build it with"));
+    }
+
+    #[test]
+    fn builder_renames_fields_and_serializes_amounts_as_btc() {
+        let listunspent = BtcMethod {
+            name: "listunspent".into(),
+            description: String::new(),
+            examples: String::new(),
+            argument_names: vec![],
+            arguments: vec![],
+            results: vec![],
+        };
+        let files = OptionObjectGenerator::new(&[query_options()]).generate(&[listunspent]);
+        let src = &files[0].1;
+
+        assert!(src.contains("pub struct ListunspentQueryOptions {"));
+        assert!(src.contains(
+            "#[serde(rename = \"minimumAmount\", with = \"bitcoin::amount::serde::as_btc::opt\", \
+             skip_serializing_if = \"Option::is_none\")]"
+        ));
+        assert!(src.contains("pub minimum_amount: Option<bitcoin::Amount>,"));
+        assert!(src.contains("pub fn include_immature_coinbase(mut self"));
+        assert!(src.contains("impl From<ListunspentQueryOptions> for Value"));
+
+        // Objects of methods missing from the schema are skipped
+        assert!(!OptionObjectGenerator::new(&[query_options()]).generate(&[])[0]
+            .1
+            .contains("ListunspentQueryOptions"));
+    }
+
+    #[test]
+    fn keyword_fields_get_raw_setters() {
+        let change_type = OptionObject {
+            method: "fundrawtransaction".into(),
+            argument: "options".into(),
+            fields: vec![field("type", "string", "The output type")],
+        };
+        let fundrawtransaction = BtcMethod {
+            name: "fundrawtransaction".into(),
+            description: String::new(),
+            examples: String::new(),
+            argument_names: vec![],
+            arguments: vec![],
+            results: vec![],
+        };
+        let files = OptionObjectGenerator::new(&[change_type]).generate(&[fundrawtransaction]);
+        let src = &files[0].1;
+
+        assert!(src.contains("pub r#type: Option<"), "{src}");
+        assert!(src.contains("pub fn r#type(mut self, r#type: "), "{src}");
+        assert!(src.contains("self.r#type = Some(r#type);"), "{src}");
+        assert!(!src.contains("fn type("), "{src}");
+    }
+}
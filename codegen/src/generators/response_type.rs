@@ -9,11 +9,13 @@
 use std::fmt::Write as _;
 
 use anyhow::Result;
-use bitcoin_rpc_conversions::TypeRegistry;
 use bitcoin_rpc_types::{BtcMethod, BtcResult};
 
-use crate::utils::{camel_to_snake_case, capitalize};
-use crate::Version;
+use crate::generators::captured_responses::CAPTURED_RESPONSES;
+use crate::generators::verbosity;
+use crate::type_registry::{self, BitcoinRpcType};
+use crate::utils::{camel_to_snake_case, capitalize, sanitize_ident};
+use crate::{MethodCategories, Version};
 
 /* --------------------------------------------------------------------- */
 /*  Primitive → Rust helpers                                             */
@@ -24,59 +26,139 @@ fn field_ident(res: &BtcResult, idx: usize) -> String {
         // Remove angle brackets and other invalid characters for Rust identifiers
         let sanitized = res.key_name.replace(['<', '>'], "").replace('-', "_");
 
-        // Convert camelCase to snake_case
-        let snake_case = camel_to_snake_case(&sanitized);
-
-        // Handle all Rust keywords that need escaping
-        let needs_escape = matches!(
-            snake_case.as_str(),
-            "type"
-                | "const"
-                | "static"
-                | "struct"
-                | "enum"
-                | "trait"
-                | "impl"
-                | "fn"
-                | "let"
-                | "mut"
-                | "ref"
-                | "self"
-                | "Self"
-                | "super"
-                | "crate"
-                | "extern"
-                | "use"
-                | "where"
-                | "async"
-                | "await"
-                | "break"
-                | "continue"
-                | "else"
-                | "if"
-                | "loop"
-                | "match"
-                | "return"
-                | "while"
-                | "for"
-                | "in"
-                | "move"
-                | "yield"
-                | "dyn"
-                | "unsafe"
-                | "union"
-        );
-
-        if needs_escape {
-            format!("r#{snake_case}")
-        } else {
-            snake_case
-        }
+        // Convert camelCase to snake_case, then escape keywords and leading digits
+        // (e.g. `10th_percentile_feerate`)
+        sanitize_ident(&camel_to_snake_case(&sanitized))
     } else {
         format!("field_{idx}")
     }
 }
 
+/// JSON key a field is (de)serialized from.
+///
+/// Angle brackets are placeholder markers in the schema and are stripped, but
+/// everything else (hyphens, camelCase) is kept exactly as Bitcoin Core emits it.
+fn json_key(res: &BtcResult) -> String { res.key_name.replace(['<', '>'], "") }
+
+/// `#[serde(rename)]` attribute mapping `ident` back to `key`, if they differ.
+///
+/// Raw identifiers need no rename because serde already strips the `r#` prefix.
+fn rename_attr(ident: &str, key: &str) -> Option<String> {
+    if ident.trim_start_matches("r#") == key {
+        None
+    } else {
+        Some(format!("#[serde(rename = \"{key}\")]"))
+    }
+}
+
+/// Name of the generated helper deserializing a required BTC float into `bitcoin::Amount`.
+pub const AMOUNT_FROM_BTC_FLOAT: &str = "amount_from_btc_float";
+
+/// Name of the generated helper deserializing an optional BTC float into `bitcoin::Amount`.
+pub const OPTION_AMOUNT_FROM_BTC_FLOAT: &str = "option_amount_from_btc_float";
+
+/// Name of the generated helper deserializing a required BTC float into `bitcoin::SignedAmount`.
+pub const SIGNED_AMOUNT_FROM_BTC_FLOAT: &str = "signed_amount_from_btc_float";
+
+/// Name of the generated helper deserializing an optional BTC float into
+/// `bitcoin::SignedAmount`.
+pub const OPTION_SIGNED_AMOUNT_FROM_BTC_FLOAT: &str = "option_signed_amount_from_btc_float";
+
+/// Rust type of amount fields that can be negative.
+const SIGNED_AMOUNT: &str = "bitcoin::SignedAmount";
+
+/// Wallet RPCs describing transactions from the wallet's side, whose `amount` and `fee`
+/// are negative for the `send` category.
+const WALLET_TRANSACTION_METHODS: &[&str] =
+    &["gettransaction", "listtransactions", "listsinceblock"];
+
+/// Helpers referenced by the `deserialize_with` attributes on `bitcoin::Amount` and
+/// `bitcoin::SignedAmount` fields.
+///
+/// Emitted into every responses file so the attributes always resolve; transport
+/// wrappers that inline a response struct import them from `crate::responses`.
+pub fn amount_helpers() -> String {
+    format!(
+        r#"/// Deserializes the BTC float Bitcoin Core returns into a [`bitcoin::Amount`].
+pub fn {AMOUNT_FROM_BTC_FLOAT}<'de, D>(deserializer: D) -> Result<bitcoin::Amount, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{{
+    let btc = f64::deserialize(deserializer)?;
+    bitcoin::Amount::from_btc(btc)
+        .map_err(|e| serde::de::Error::custom(format!("invalid BTC amount {{btc}}: {{e}}")))
+}}
+
+/// Like [`{AMOUNT_FROM_BTC_FLOAT}`], but maps `null` to `None`.
+pub fn {OPTION_AMOUNT_FROM_BTC_FLOAT}<'de, D>(
+    deserializer: D,
+) -> Result<Option<bitcoin::Amount>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{{
+    match Option::<f64>::deserialize(deserializer)? {{
+        Some(btc) => bitcoin::Amount::from_btc(btc)
+            .map(Some)
+            .map_err(|e| serde::de::Error::custom(format!("invalid BTC amount {{btc}}: {{e}}"))),
+        None => Ok(None),
+    }}
+}}
+
+/// Deserializes a BTC float that may be negative, like the `fee` of a sent transaction,
+/// into a [`bitcoin::SignedAmount`].
+pub fn {SIGNED_AMOUNT_FROM_BTC_FLOAT}<'de, D>(
+    deserializer: D,
+) -> Result<bitcoin::SignedAmount, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{{
+    let btc = f64::deserialize(deserializer)?;
+    bitcoin::SignedAmount::from_btc(btc)
+        .map_err(|e| serde::de::Error::custom(format!("invalid BTC amount {{btc}}: {{e}}")))
+}}
+
+/// Like [`{SIGNED_AMOUNT_FROM_BTC_FLOAT}`], but maps `null` to `None`.
+pub fn {OPTION_SIGNED_AMOUNT_FROM_BTC_FLOAT}<'de, D>(
+    deserializer: D,
+) -> Result<Option<bitcoin::SignedAmount>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{{
+    match Option::<f64>::deserialize(deserializer)? {{
+        Some(btc) => bitcoin::SignedAmount::from_btc(btc)
+            .map(Some)
+            .map_err(|e| serde::de::Error::custom(format!("invalid BTC amount {{btc}}: {{e}}"))),
+        None => Ok(None),
+    }}
+}}
+"#
+    )
+}
+
+/// `deserialize_with` attribute for a field of Rust type `ty`, if it is an amount.
+fn amount_attr(ty: &str, optional: bool) -> Option<String> {
+    let (required_helper, optional_helper) = if ty == SIGNED_AMOUNT {
+        (SIGNED_AMOUNT_FROM_BTC_FLOAT, OPTION_SIGNED_AMOUNT_FROM_BTC_FLOAT)
+    } else if ty == BitcoinRpcType::BitcoinAmount.to_rust_type() {
+        (AMOUNT_FROM_BTC_FLOAT, OPTION_AMOUNT_FROM_BTC_FLOAT)
+    } else {
+        return None;
+    };
+    if optional {
+        Some(format!("#[serde(default, deserialize_with = \"{optional_helper}\")]"))
+    } else {
+        Some(format!("#[serde(deserialize_with = \"{required_helper}\")]"))
+    }
+}
+
+/// Whether amount field `f` can be negative: the `amount` and `fee` of the
+/// [`WALLET_TRANSACTION_METHODS`], or any amount documented as negative (e.g. the
+/// `balance_change` of `simulaterawtransaction`).
+fn is_signed_amount(f: &BtcResult, wallet_transaction: bool) -> bool {
+    (wallet_transaction && matches!(f.key_name.as_str(), "amount" | "fee"))
+        || f.description.to_lowercase().contains("negative")
+}
+
 fn sanitize_doc_comment(comment: &str) -> String {
     comment
         .lines()
@@ -110,6 +192,17 @@ fn sanitize_doc_comment(comment: &str) -> String {
 /// Intended to be used as part of a version-aware code generation pipeline.
 pub struct ResponseTypeCodeGenerator {
     version: String,
+    options: ResponseTypeOptions,
+    categories: MethodCategories,
+}
+
+/// Optional extras for generated response types; all off by default.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ResponseTypeOptions {
+    /// See [`ResponseTypeCodeGenerator::with_comparison_derives`].
+    pub comparison_derives: bool,
+    /// See [`ResponseTypeCodeGenerator::with_deny_unknown_fields`].
+    pub deny_unknown_fields: bool,
 }
 
 impl ResponseTypeCodeGenerator {
@@ -117,7 +210,72 @@ impl ResponseTypeCodeGenerator {
     ///
     /// The provided `version` string is used to namespace or suffix generated types,
     /// ensuring compatibility with different versions of the RPC interface.
-    pub fn new(version: impl Into<String>) -> Self { Self { version: version.into() } }
+    pub fn new(version: impl Into<String>) -> Self {
+        Self {
+            version: version.into(),
+            options: ResponseTypeOptions::default(),
+            categories: MethodCategories::default(),
+        }
+    }
+
+    /// Also derive `PartialEq`, plus `Eq`/`Hash` for types whose fields all support them.
+    ///
+    /// Off by default. Types containing `f64` fields never get `Eq`/`Hash`, and types
+    /// containing raw JSON values never get `Hash`.
+    pub fn with_comparison_derives(mut self, enabled: bool) -> Self {
+        self.options.comparison_derives = enabled;
+        self
+    }
+
+    /// Add `#[serde(deny_unknown_fields)]` to every generated struct with named fields.
+    ///
+    /// Off by default, since production clients should tolerate new fields. Turn it on
+    /// to detect schema drift: deserializing a response with a field the types don't
+    /// model then fails, naming the field.
+    pub fn with_deny_unknown_fields(mut self, enabled: bool) -> Self {
+        self.options.deny_unknown_fields = enabled;
+        self
+    }
+
+    /// Put the types of each method behind the feature of its category.
+    ///
+    /// A gated method's types are wrapped in a private module named after it and
+    /// re-exported, so one `#[cfg]` covers its nested types and impls as well.
+    pub fn with_categories(mut self, categories: MethodCategories) -> Self {
+        self.categories = categories;
+        self
+    }
+
+    /// A test module decoding each of the [`CAPTURED_RESPONSES`] whose method is among
+    /// `methods`, or nothing if there are none.
+    fn captured_response_tests(&self, methods: &[BtcMethod]) -> String {
+        let mut tests = String::new();
+        for captured in CAPTURED_RESPONSES {
+            let Some(m) = methods.iter().find(|m| m.name == captured.method) else { continue };
+            if is_void(m) {
+                continue;
+            }
+            let cfg = match self.categories.feature(&m.name) {
+                Some(feature) => format!("    #[cfg(feature = \"{feature}\")]\n"),
+                None => String::new(),
+            };
+            write!(
+                tests,
+                "\n{cfg}    #[test]\n    fn {name}_decodes_captured_response() {{\n        \
+                 let response: {ty} = serde_json::from_str(r#\"{json}\"#).unwrap();\n        \
+                 {checks}\n    }}\n",
+                name = m.name,
+                ty = response_struct_name(m),
+                json = captured.json,
+                checks = captured.checks,
+            )
+            .unwrap();
+        }
+        if tests.is_empty() {
+            return tests;
+        }
+        format!("#[cfg(test)]\nmod tests {{\n    use super::*;\n{tests}}}\n")
+    }
 }
 
 impl crate::CodeGenerator for ResponseTypeCodeGenerator {
@@ -126,15 +284,38 @@ impl crate::CodeGenerator for ResponseTypeCodeGenerator {
             "//! Generated RPC response types\n\
              use serde::{Deserialize, Serialize};\n\n",
         );
+        out.push_str(&amount_helpers());
+        out.push('\n');
+        out.push_str(&consensus_decode_helpers());
+        out.push('\n');
 
         for m in methods {
-            let response_struct = build_return_type(m).unwrap_or_default();
-            if let Some(def) = response_struct {
-                out.push_str(&def);
-                out.push('\n');
+            let response_struct =
+                build_return_type_with_options(m, self.options).unwrap_or_default();
+            let Some(def) = response_struct else { continue };
+            match self.categories.feature(&m.name) {
+                Some(feature) => {
+                    let module = sanitize_ident(&m.name);
+                    writeln!(
+                        out,
+                        "#[cfg(feature = \"{feature}\")]\n\
+                         mod {module} {{\n\
+                         use super::*;\n\n\
+                         {def}}}\n\
+                         #[cfg(feature = \"{feature}\")]\n\
+                         pub use {module}::*;\n"
+                    )
+                    .unwrap();
+                }
+                None => {
+                    out.push_str(&def);
+                    out.push('\n');
+                }
             }
         }
 
+        out.push_str(&self.captured_response_tests(methods));
+
         vec![(
             format!(
                 "{}_responses.rs",
@@ -145,18 +326,115 @@ impl crate::CodeGenerator for ResponseTypeCodeGenerator {
     }
 }
 
+/// Responses carrying consensus-encoded hex that get `TryFrom` conversions into a
+/// `bitcoin` type, as `(method, type)`. Verbosity overloads of these methods get them too.
+pub const CONSENSUS_DECODABLE: &[(&str, &str)] = &[
+    ("getblock", "bitcoin::Block"),
+    ("getblockheader", "bitcoin::block::Header"),
+    ("getrawtransaction", "bitcoin::Transaction"),
+];
+
+/// Name of the error type shared by the generated consensus conversions.
+pub const CONSENSUS_DECODE_ERROR: &str = "ConsensusDecodeError";
+
+/// The error type shared by the generated consensus conversions.
+pub fn consensus_decode_helpers() -> String {
+    "/// Failure converting a response into a `bitcoin` type.
+#[derive(Debug, thiserror::Error)]
+pub enum ConsensusDecodeError {
+    /// The response was requested in a verbose form that carries no encoded data;
+    /// request it with `verbosity = 0` or `verbose = false`.
+    #[error(\"response carries no consensus-encoded data\")]
+    NotEncoded,
+    /// The hex or the consensus encoding is invalid.
+    #[error(\"invalid consensus encoding: {0}\")]
+    Decode(#[from] bitcoin::consensus::encode::FromHexError),
+}
+"
+    .to_string()
+}
+
+/// Whether a result holds consensus-encoded hex.
+fn is_hex_string(r: &BtcResult) -> bool { matches!(r.type_.as_str(), "string" | "hex") }
+
+/// The `hex` field of an object result, as `(ident, optional)`.
+fn hex_field(r: &BtcResult) -> Option<(String, bool)> {
+    r.inner
+        .iter()
+        .find(|f| f.key_name == "hex" && is_hex_string(f))
+        .map(|f| (field_ident(f, 0), type_registry::map_result_type(f).1))
+}
+
+/// The body of `consensus_hex` for an enum response.
+///
+/// `arms` are the `match self` arms yielding the hex; `exhaustive` is false when some
+/// variant carries none.
+fn consensus_hex_match(arms: &[String], exhaustive: bool) -> String {
+    let mut body = String::from("match self {\n");
+    for arm in arms {
+        writeln!(body, "            {arm},").unwrap();
+    }
+    if !exhaustive {
+        body.push_str("            _ => None,\n");
+    }
+    body.push_str("        }");
+    body
+}
+
+/// `consensus_hex` (returning `hex_body`) plus `TryFrom` impls decoding `struct_name`
+/// into `target`.
+fn consensus_conversions(struct_name: &str, target: &str, hex_body: &str) -> String {
+    format!(
+        "impl {struct_name} {{
+    /// The consensus-encoded data as hex, if this response form carries it.
+    pub fn consensus_hex(&self) -> Option<&str> {{
+        {hex_body}
+    }}
+}}
+
+impl TryFrom<&{struct_name}> for {target} {{
+    type Error = ConsensusDecodeError;
+
+    fn try_from(response: &{struct_name}) -> Result<Self, Self::Error> {{
+        let hex = response.consensus_hex().ok_or(ConsensusDecodeError::NotEncoded)?;
+        Ok(bitcoin::consensus::encode::deserialize_hex(hex)?)
+    }}
+}}
+
+impl TryFrom<{struct_name}> for {target} {{
+    type Error = ConsensusDecodeError;
+
+    fn try_from(response: {struct_name}) -> Result<Self, Self::Error> {{ Self::try_from(&response) }}
+}}
+
+"
+    )
+}
+
 /// Build a single response type, or return `Ok(None)` to skip.
 pub fn build_return_type(method: &BtcMethod) -> Result<Option<String>> {
+    build_return_type_with_options(method, ResponseTypeOptions::default())
+}
+
+/// Like [`build_return_type`], with the extras selected in `options`.
+pub fn build_return_type_with_options(
+    method: &BtcMethod,
+    options: ResponseTypeOptions,
+) -> Result<Option<String>> {
     if is_void(method) {
         return Ok(None);
     }
 
     let struct_name = response_struct_name(method);
     let mut buf = String::new();
-
-    let doc = sanitize_doc_comment(&method.description);
-    writeln!(&mut buf, "/// {doc}")?;
-    writeln!(&mut buf, "#[derive(Debug, Deserialize, Serialize)]")?;
+    // Rust types of every field, used to decide which derives are sound
+    let mut field_types = Vec::new();
+    let mut nested = NestedTypes::new(&struct_name, &method.name, options);
+    // How `consensus_hex` finds the encoded data: `match self` arms for enums (and
+    // whether every variant has some), or an expression for structs
+    let mut hex_arms = Vec::new();
+    let mut hex_exhaustive = true;
+    let mut hex_expr = None;
 
     if has_conditional_results(method) {
         // Results with conditions → enum with variants
@@ -165,7 +443,7 @@ pub fn build_return_type(method: &BtcMethod) -> Result<Option<String>> {
         writeln!(&mut buf, "pub enum {struct_name} {{")?;
 
         // Track used variant names to avoid duplicates
-        let mut used_names = std::collections::HashSet::new();
+        let mut used_names = std::collections::BTreeSet::new();
 
         for (i, result) in method.results.iter().enumerate() {
             if result.type_ == "none" {
@@ -191,36 +469,57 @@ pub fn build_return_type(method: &BtcMethod) -> Result<Option<String>> {
             match &result.type_[..] {
                 "object" if !result.inner.is_empty() => {
                     // Check if this is a map-like structure (single inner object with key_name)
-                    if result.inner.len() == 1 && !result.inner[0].key_name.is_empty() {
+                    if is_map_like(result) {
                         // This is a map structure - use serde_json::Value for dynamic keys
+                        hex_exhaustive = false;
+                        field_types.push("serde_json::Value".to_string());
                         writeln!(&mut buf, "    {variant_name}(serde_json::Value),")?;
                     } else {
                         // Regular object structure
+                        match hex_field(result) {
+                            Some((hex, true)) => {
+                                // `{ hex: None, .. }` is left for the fallback arm
+                                hex_exhaustive = false;
+                                hex_arms.push(format!(
+                                    "Self::{variant_name} {{ {hex}: Some(hex), .. }} => Some(hex.as_str())"
+                                ))
+                            }
+                            Some((hex, false)) => hex_arms.push(format!(
+                                "Self::{variant_name} {{ {hex}, .. }} => Some({hex}.as_str())"
+                            )),
+                            None => hex_exhaustive = false,
+                        }
                         writeln!(&mut buf, "    {variant_name} {{")?;
+                        let owner = format!("{struct_name}{variant_name}");
                         for f in &result.inner {
-                            let (ty, opt) = TypeRegistry::map_result_type(f);
+                            let (_, opt) = type_registry::map_result_type(f);
+                            let ty = nested.field_type(f, &owner);
                             let name = field_ident(f, 0);
-                            let ty = if opt { format!("Option<{ty}>") } else { ty.to_string() };
-                            writeln!(
-                                &mut buf,
-                                "        {}{}: {},",
-                                serde_attrs_for_field(f),
-                                name,
-                                ty
-                            )?;
+                            field_types.push(ty.clone());
+                            let attrs = serde_attrs_for_field(f, &ty);
+                            let ty = if opt { format!("Option<{ty}>") } else { ty };
+                            writeln!(&mut buf, "        {attrs}{name}: {ty},")?;
                         }
                         writeln!(&mut buf, "    }},")?;
                     }
                 }
                 "array" if !result.inner.is_empty() => {
-                    // Array type - get element type from inner field
-                    let (element_ty, _) = TypeRegistry::map_result_type(&result.inner[0]);
-                    let array_ty = format!("Vec<{element_ty}>");
+                    // Array type - typed from its element schema
+                    hex_exhaustive = false;
+                    let array_ty =
+                        nested.field_type(result, &format!("{struct_name}{variant_name}"));
+                    field_types.push(array_ty.clone());
                     writeln!(&mut buf, "    {variant_name}({array_ty}),")?;
                 }
                 _ => {
                     // primitive → transparent wrapper
-                    let (ty, _) = TypeRegistry::map_result_type(result);
+                    let (ty, _) = type_registry::map_result_type(result);
+                    if is_hex_string(result) && ty == "String" {
+                        hex_arms.push(format!("Self::{variant_name}(hex) => Some(hex.as_str())"));
+                    } else {
+                        hex_exhaustive = false;
+                    }
+                    field_types.push(ty.to_string());
                     writeln!(&mut buf, "    {variant_name}({ty}),")?;
                 }
             }
@@ -228,8 +527,10 @@ pub fn build_return_type(method: &BtcMethod) -> Result<Option<String>> {
         writeln!(&mut buf, "}}\n")?;
     } else if is_multi_variant(method) {
         // multiple object shapes or primitives → flattened struct with optional fields
+        buf.push_str(nested.struct_attrs());
         writeln!(&mut buf, "pub struct {struct_name} {{")?;
-        for field in collect_fields(method) {
+        for field in collect_fields(method, &mut nested) {
+            field_types.push(field.ty.clone());
             let ty = if field.always_present {
                 field.ty.clone()
             } else {
@@ -243,25 +544,281 @@ pub fn build_return_type(method: &BtcMethod) -> Result<Option<String>> {
         let r = &method.results[0];
         match &r.type_[..] {
             "object" if !r.inner.is_empty() => {
+                hex_expr = hex_field(r).map(|(hex, optional)| {
+                    if optional {
+                        format!("self.{hex}.as_deref()")
+                    } else {
+                        format!("Some(self.{hex}.as_str())")
+                    }
+                });
+                buf.push_str(nested.struct_attrs());
                 writeln!(&mut buf, "pub struct {struct_name} {{")?;
                 for f in &r.inner {
-                    let (ty, opt) = TypeRegistry::map_result_type(f);
+                    let (_, opt) = type_registry::map_result_type(f);
+                    let ty = nested.field_type(f, &struct_name);
                     let name = field_ident(f, 0);
-                    let ty = if opt { format!("Option<{ty}>") } else { ty.to_string() };
-                    writeln!(&mut buf, "    {}pub {}: {},", serde_attrs_for_field(f), name, ty)?;
+                    field_types.push(ty.clone());
+                    let attrs = serde_attrs_for_field(f, &ty);
+                    let ty = if opt { format!("Option<{ty}>") } else { ty };
+                    writeln!(&mut buf, "    {attrs}pub {name}: {ty},")?;
                 }
                 writeln!(&mut buf, "}}\n")?;
             }
             _ => {
                 // primitive or array → transparent wrapper
-                let (ty, _) = TypeRegistry::map_result_type(r);
+                let ty = nested.field_type(r, &struct_name);
+                if is_hex_string(r) && ty == "String" {
+                    hex_expr = Some("Some(self.0.as_str())".to_string());
+                }
+                field_types.push(ty.clone());
                 writeln!(&mut buf, "#[serde(transparent)]")?;
                 writeln!(&mut buf, "pub struct {struct_name}(pub {ty});\n")?;
             }
         }
     }
 
-    Ok(Some(buf))
+    let doc = sanitize_doc_comment(&method.description);
+    let derives = nested.derives_for(&field_types);
+    let mut out = format!("/// {doc}\n#[derive({})]\n{buf}", derives.join(", "));
+    for def in nested.defs {
+        out.push_str(&def);
+    }
+    if let Some((_, target)) =
+        CONSENSUS_DECODABLE.iter().find(|(m, _)| *m == verbosity::rpc_name(&method.name))
+    {
+        let hex_body = match hex_expr {
+            Some(expr) => Some(expr),
+            None if !hex_arms.is_empty() => Some(consensus_hex_match(&hex_arms, hex_exhaustive)),
+            None => None,
+        };
+        if let Some(hex_body) = hex_body {
+            out.push('\n');
+            out.push_str(&consensus_conversions(&struct_name, target, &hex_body));
+        }
+    }
+    if let Some(helper) = script_pubkey_helper(method, &struct_name) {
+        out.push('\n');
+        out.push_str(&helper);
+    }
+    Ok(Some(out))
+}
+
+/// `script_pubkey(n)` for a response describing a transaction whose outputs (`vout`) carry
+/// their `scriptPubKey`, like verbose `getrawtransaction` and `decoderawtransaction`.
+fn script_pubkey_helper(method: &BtcMethod, struct_name: &str) -> Option<String> {
+    if has_conditional_results(method) || is_multi_variant(method) {
+        return None;
+    }
+    let vout = method.results[0].inner.iter().find(|f| f.key_name == "vout")?;
+    let script = match &vout.inner[..] {
+        [output] if is_structurable(output) =>
+            output.inner.iter().find(|f| f.key_name == "scriptPubKey")?,
+        _ => return None,
+    };
+    // Only required fields typed as generated structs can be reached without unwrapping
+    let output_ty = format!("{struct_name}{}", nested_type_suffix(vout));
+    let mut nested = NestedTypes::new(struct_name, &method.name, ResponseTypeOptions::default());
+    let typed = nested.field_type(vout, struct_name) == format!("Vec<{output_ty}>")
+        && nested.field_type(script, &output_ty)
+            == format!("{output_ty}{}", nested_type_suffix(script));
+    let (hex, hex_optional) = hex_field(script)?;
+    if !typed || !vout.required() || !script.required() || hex_optional {
+        return None;
+    }
+
+    Some(format!(
+        "impl {struct_name} {{
+    /// The `scriptPubKey` of output `n` (`vout[n]`), or `None` if there is no such output.
+    pub fn script_pubkey(
+        &self,
+        n: usize,
+    ) -> Option<Result<bitcoin::ScriptBuf, bitcoin::hex::HexToBytesError>> {{
+        self.{vout}.get(n).map(|output| bitcoin::ScriptBuf::from_hex(&output.{script}.{hex}))
+    }}
+}}
+
+",
+        vout = field_ident(vout, 0),
+        script = field_ident(script, 0),
+    ))
+}
+
+/// Named types generated for the nested parts of one response type.
+///
+/// Object fields with known fields get a generated struct named after the owner and
+/// field (e.g. `GetblockchaininfoResponseSoftforks`), recursively. Arrays whose element
+/// schema is known get a typed `Vec`, with a generated struct for object elements
+/// (e.g. `ListunspentResponseItem`).
+struct NestedTypes {
+    root: String,
+    options: ResponseTypeOptions,
+    /// Whether the response is one of the [`WALLET_TRANSACTION_METHODS`]
+    wallet_transaction: bool,
+    defs: Vec<String>,
+    /// `(Eq, Hash)` support of each generated struct, for the derives of its parents
+    support: std::collections::BTreeMap<String, (bool, bool)>,
+}
+
+impl NestedTypes {
+    fn new(root: &str, method: &str, options: ResponseTypeOptions) -> Self {
+        Self {
+            root: root.to_string(),
+            options,
+            wallet_transaction: WALLET_TRANSACTION_METHODS.contains(&verbosity::rpc_name(method)),
+            defs: Vec::new(),
+            support: Default::default(),
+        }
+    }
+
+    /// Rust type of `f` (without the `Option` for optional fields) owned by type `owner`.
+    fn field_type(&mut self, f: &BtcResult, owner: &str) -> String {
+        if is_structurable(f) {
+            let name = format!("{owner}{}", nested_type_suffix(f));
+            self.push_struct(&name, &f.inner);
+            return name;
+        }
+
+        let (ty, _) = type_registry::map_result_type(f);
+        // Keep specific array categories (e.g. `txids`); only refine the generic fallback
+        if f.type_ == "array" && ty == BitcoinRpcType::GenericArray.to_rust_type() {
+            if let [element] = &f.inner[..] {
+                let name = format!("{owner}{}", nested_type_suffix(f));
+                if let Some(element_ty) = self.element_type(element, &name) {
+                    return format!("Vec<{element_ty}>");
+                }
+            }
+        }
+        if ty == BitcoinRpcType::BitcoinAmount.to_rust_type()
+            && is_signed_amount(f, self.wallet_transaction)
+        {
+            return SIGNED_AMOUNT.to_string();
+        }
+        ty.to_string()
+    }
+
+    /// Rust type of an array element, or `None` if the schema does not say.
+    fn element_type(&mut self, element: &BtcResult, name: &str) -> Option<String> {
+        match &element.type_[..] {
+            "object" if is_structurable(element) => {
+                self.push_struct(name, &element.inner);
+                Some(name.to_string())
+            }
+            "object" => None,
+            "array" => Some(self.field_type(element, name)),
+            _ => {
+                let (ty, _) = type_registry::map_result_type(element);
+                (ty != BitcoinRpcType::Unknown.to_rust_type()).then(|| ty.to_string())
+            }
+        }
+    }
+
+    /// Generate struct `name` with `fields`.
+    fn push_struct(&mut self, name: &str, fields: &[BtcResult]) {
+        let mut body = String::new();
+        let mut field_types = Vec::new();
+        for f in fields {
+            let (_, opt) = type_registry::map_result_type(f);
+            let ty = self.field_type(f, name);
+            field_types.push(ty.clone());
+            let attrs = serde_attrs_for_field(f, &ty);
+            let ty = if opt { format!("Option<{ty}>") } else { ty };
+            body.push_str(&format!("    {}pub {}: {},\n", attrs, field_ident(f, 0), ty));
+        }
+
+        let derives = self.derives_for(&field_types);
+        self.support.insert(name.to_string(), support_of(&field_types, &self.support));
+        self.defs.push(format!(
+            "/// Nested type of [`{root}`].\n#[derive({derives})]\n{attrs}pub struct {name} {{\n{body}}}\n\n",
+            root = self.root,
+            derives = derives.join(", "),
+            attrs = self.struct_attrs(),
+        ));
+    }
+
+    /// Container attributes for a generated struct with named fields.
+    fn struct_attrs(&self) -> &'static str {
+        if self.options.deny_unknown_fields {
+            "#[serde(deny_unknown_fields)]\n"
+        } else {
+            ""
+        }
+    }
+
+    /// Derives for a type whose fields have the given Rust types.
+    fn derives_for(&self, field_types: &[String]) -> Vec<&'static str> {
+        let mut derives = vec!["Debug", "Clone", "Deserialize", "Serialize"];
+        if self.options.comparison_derives {
+            derives.push("PartialEq");
+            let (eq, hash) = support_of(field_types, &self.support);
+            if eq {
+                derives.push("Eq");
+            }
+            if eq && hash {
+                derives.push("Hash");
+            }
+        }
+        derives
+    }
+}
+
+/// Suffix naming the type nested in field `f`: its key in PascalCase, or `Item` for
+/// unnamed array elements.
+fn nested_type_suffix(f: &BtcResult) -> String {
+    let key: String =
+        json_key(f).chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }).collect();
+    match capitalize(&key) {
+        suffix if suffix.is_empty() => "Item".to_string(),
+        suffix => suffix,
+    }
+}
+
+/// A single named inner field marks an object with dynamic keys (a map).
+fn is_map_like(r: &BtcResult) -> bool { r.inner.len() == 1 && !r.inner[0].key_name.is_empty() }
+
+/// An object whose fields are all named, so it can become a struct.
+///
+/// Maps and objects with elided (`...`) or unnamed entries stay dynamic.
+fn is_structurable(r: &BtcResult) -> bool {
+    r.type_ == "object"
+        && !r.inner.is_empty()
+        && !is_map_like(r)
+        && r.inner.iter().all(|f| !f.key_name.is_empty())
+}
+
+/// Whether all of `field_types` implement `(Eq, Hash)`.
+fn support_of(
+    field_types: &[String],
+    nested: &std::collections::BTreeMap<String, (bool, bool)>,
+) -> (bool, bool) {
+    field_types
+        .iter()
+        .map(|ty| eq_hash_support(ty, nested))
+        .fold((true, true), |(eq, hash), (e, h)| (eq && e, hash && h))
+}
+
+/// Whether a mapped field type implements `(Eq, Hash)`, looking through `Option`/`Vec`.
+fn eq_hash_support(
+    ty: &str,
+    nested: &std::collections::BTreeMap<String, (bool, bool)>,
+) -> (bool, bool) {
+    let mut inner = ty;
+    while let Some(rest) = inner.strip_prefix("Option<").or_else(|| inner.strip_prefix("Vec<")) {
+        inner = rest.strip_suffix('>').unwrap_or(rest);
+    }
+
+    if let Some(support) = nested.get(inner) {
+        *support
+    } else if inner == BitcoinRpcType::Float.to_rust_type() {
+        // Floats are only `PartialEq`
+        (false, false)
+    } else if inner == BitcoinRpcType::Unknown.to_rust_type()
+        || inner == BitcoinRpcType::BitcoinObject.to_rust_type()
+    {
+        // serde_json values and maps are `Eq` but not `Hash`
+        (true, false)
+    } else {
+        (true, true)
+    }
 }
 
 // Helpers
@@ -326,18 +883,19 @@ fn extract_variant_name(condition: &str, fallback_idx: usize) -> String {
 fn response_struct_name(m: &BtcMethod) -> String { format!("{}Response", capitalize(&m.name)) }
 
 /// Gather every possible field exactly once, preserving order.
-fn collect_fields(m: &BtcMethod) -> Vec<Field> {
-    let mut seen = std::collections::HashSet::new();
+fn collect_fields(m: &BtcMethod, nested: &mut NestedTypes) -> Vec<Field> {
+    let mut seen = std::collections::BTreeSet::new();
     let mut out = Vec::new();
+    let owner = nested.root.clone();
 
     for r in &m.results {
         if r.type_ == "object" {
             for f in &r.inner {
                 let name = field_ident(f, 0);
                 if seen.insert(name.clone()) {
-                    let (ty, _) = TypeRegistry::map_result_type(f);
+                    let ty = nested.field_type(f, &owner);
                     let always = is_field_always_present(&name, &m.results);
-                    out.push(Field { name, ty: ty.to_string(), always_present: always });
+                    out.push(Field { name, key: json_key(f), ty, always_present: always });
                 }
             }
         }
@@ -349,6 +907,7 @@ fn collect_fields(m: &BtcMethod) -> Vec<Field> {
 /// Single field info.
 struct Field {
     name: String,
+    key: String,
     ty: String,
     always_present: bool,
 }
@@ -362,28 +921,43 @@ fn is_field_always_present(name: &str, results: &[BtcResult]) -> bool {
 
 /// Render serde attributes for a flattened multi-variant struct field.
 fn serde_attrs_for(field: &Field) -> String {
+    let mut attrs = Vec::new();
+
+    if let Some(rename) = rename_attr(&field.name, &field.key) {
+        attrs.push(rename);
+    }
+
+    if let Some(amount) = amount_attr(&field.ty, !field.always_present) {
+        attrs.push(amount);
+    }
+
     if !field.always_present {
-        "    #[serde(skip_serializing_if = \"Option::is_none\")]\n    ".into()
-    } else {
+        attrs.push("#[serde(skip_serializing_if = \"Option::is_none\")]".to_string());
+    }
+
+    if attrs.is_empty() {
         "".into()
+    } else {
+        format!("    {}\n    ", attrs.join("\n    "))
     }
 }
 
-/// Render serde attrs for a single `BtcResult`.
-fn serde_attrs_for_field(r: &BtcResult) -> String {
+/// Render serde attrs for a single `BtcResult` of Rust type `ty` (without the `Option`).
+fn serde_attrs_for_field(r: &BtcResult, ty: &str) -> String {
     let mut attrs = Vec::new();
 
     // Add field name mapping if the JSON field name differs from the Rust field name
     if !r.key_name.is_empty() {
-        let rust_field_name = field_ident(r, 0);
-        let json_field_name = r.key_name.replace(['<', '>'], "").replace('-', "_");
-
-        // Only add rename if the names are different
-        if rust_field_name != json_field_name {
-            attrs.push(format!("#[serde(rename = \"{}\")]", json_field_name));
+        if let Some(rename) = rename_attr(&field_ident(r, 0), &json_key(r)) {
+            attrs.push(rename);
         }
     }
 
+    let (_, opt) = type_registry::map_result_type(r);
+    if let Some(amount) = amount_attr(ty, opt) {
+        attrs.push(amount);
+    }
+
     // Add optional field handling
     if !r.required() {
         attrs.push("#[serde(skip_serializing_if = \"Option::is_none\")]".to_string());
@@ -395,3 +969,356 @@ fn serde_attrs_for_field(r: &BtcResult) -> String {
         format!("{}\n    ", attrs.join("\n    "))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CodeGenerator;
+
+    fn result(type_: &str, key_name: &str, inner: Vec<BtcResult>) -> BtcResult {
+        BtcResult { type_: type_.into(), key_name: key_name.into(), inner, ..Default::default() }
+    }
+
+    fn method(name: &str, results: Vec<BtcResult>) -> BtcMethod {
+        serde_json::from_value(serde_json::json!({
+            "name": name,
+            "description": "",
+            "arguments": [],
+            "results": results,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_renames_fields_whose_ident_differs_from_json_key() {
+        let fields = vec![
+            result("string", "scriptPubKey", vec![]),
+            result("boolean", "bip125-replaceable", vec![]),
+            result("string", "type", vec![]),
+            result("string", "hex", vec![]),
+        ];
+        let src = build_return_type(&method("gettxout", vec![result("object", "", fields)]))
+            .unwrap()
+            .unwrap();
+
+        assert!(
+            src.contains("#[serde(rename = \"scriptPubKey\")]\n    pub script_pubkey"),
+            "{src}"
+        );
+        assert!(
+            src.contains("#[serde(rename = \"bip125-replaceable\")]\n    pub bip125_replaceable"),
+            "{src}"
+        );
+        assert!(src.contains("    pub r#type: String"), "{src}");
+        assert!(!src.contains("rename = \"type\""), "{src}");
+        assert!(!src.contains("rename = \"hex\""), "{src}");
+    }
+
+    #[test]
+    fn test_renames_flattened_multi_variant_fields() {
+        let results = vec![
+            result("object", "", vec![result("string", "scriptPubKey", vec![])]),
+            result("string", "", vec![]),
+        ];
+        let src = build_return_type(&method("decodescript", results)).unwrap().unwrap();
+
+        assert!(
+            src.contains(
+                "#[serde(rename = \"scriptPubKey\")]\n    #[serde(skip_serializing_if = \"Option::is_none\")]\n    pub script_pubkey: Option<String>"
+            ),
+            "{src}"
+        );
+    }
+
+    #[test]
+    fn test_amount_fields_deserialize_from_btc_floats() {
+        let mut optional = result("number", "unconfirmed_balance", vec![]);
+        optional.optional = true;
+        let fields = vec![result("number", "balance", vec![]), optional];
+        let src = build_return_type(&method("getbalances", vec![result("object", "", fields)]))
+            .unwrap()
+            .unwrap();
+
+        assert!(
+            src.contains(
+                "#[serde(deserialize_with = \"amount_from_btc_float\")]\n    pub balance: bitcoin::Amount"
+            ),
+            "{src}"
+        );
+        assert!(src.contains("#[serde(default, deserialize_with = \"option_amount_from_btc_float\")]\n    #[serde(skip_serializing_if = \"Option::is_none\")]\n    pub unconfirmed_balance: Option<bitcoin::Amount>"), "{src}");
+
+        let files = ResponseTypeCodeGenerator::new("v30").generate(&[]);
+        assert!(files[0].1.contains("pub fn amount_from_btc_float<'de, D>"));
+        assert!(files[0].1.contains("pub fn option_amount_from_btc_float<'de, D>"));
+        assert!(files[0].1.contains("pub fn signed_amount_from_btc_float<'de, D>"));
+        assert!(files[0].1.contains("pub fn option_signed_amount_from_btc_float<'de, D>"));
+    }
+
+    #[test]
+    fn test_wallet_transaction_amounts_are_signed() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../bitcoin-core-api.json");
+        let methods = crate::load_api_methods_from_file(path).unwrap();
+        let find = |name: &str| methods.iter().find(|m| m.name == name).unwrap();
+
+        let src = build_return_type(find("gettransaction")).unwrap().unwrap();
+        assert!(
+            src.contains("#[serde(deserialize_with = \"signed_amount_from_btc_float\")]\n    pub amount: bitcoin::SignedAmount,"),
+            "{src}"
+        );
+        assert!(src.contains("#[serde(default, deserialize_with = \"option_signed_amount_from_btc_float\")]\n    #[serde(skip_serializing_if = \"Option::is_none\")]\n    pub fee: Option<bitcoin::SignedAmount>,"), "{src}");
+        let src = build_return_type(find("listtransactions")).unwrap().unwrap();
+        assert!(src.contains("pub fee: Option<bitcoin::SignedAmount>,"), "{src}");
+        // Documented as negative outside the wallet transaction RPCs
+        let src = build_return_type(find("simulaterawtransaction")).unwrap().unwrap();
+        assert!(src.contains("pub balance_change: bitcoin::SignedAmount,"), "{src}");
+        // Received amounts stay unsigned
+        let src = build_return_type(find("listunspent")).unwrap().unwrap();
+        assert!(src.contains("pub amount: bitcoin::Amount,"), "{src}");
+
+        // The generated crate decodes a captured send with a negative fee
+        let files = ResponseTypeCodeGenerator::new("v30").generate(&methods);
+        let src = &files[0].1;
+        assert!(src.contains("fn gettransaction_decodes_captured_response() {"), "{src}");
+        assert!(src.contains("let response: GettransactionResponse = serde_json::from_str(r#\"{"));
+        assert!(src.contains("\"fee\": -0.00000141,"));
+        assert!(src.contains("bitcoin::SignedAmount::from_sat(-141)"));
+    }
+
+    #[test]
+    fn test_comparison_derives_respect_field_types() {
+        let floats = method(
+            "getmininginfo",
+            vec![result("object", "", vec![result("number", "difficulty", vec![])])],
+        );
+        let strings = method(
+            "getnetworkinfo",
+            vec![result("object", "", vec![result("string", "subversion", vec![])])],
+        );
+
+        let comparison = ResponseTypeOptions { comparison_derives: true, ..Default::default() };
+        let plain = build_return_type(&floats).unwrap().unwrap();
+        assert!(plain.contains("#[derive(Debug, Clone, Deserialize, Serialize)]"), "{plain}");
+
+        let src = build_return_type_with_options(&floats, comparison).unwrap().unwrap();
+        assert!(src.contains("pub difficulty: f64"), "{src}");
+        assert!(
+            src.contains("#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]"),
+            "{src}"
+        );
+
+        let src = build_return_type_with_options(&strings, comparison).unwrap().unwrap();
+        assert!(
+            src.contains("#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, Hash)]"),
+            "{src}"
+        );
+    }
+
+    #[test]
+    fn test_arrays_are_typed_from_their_element_schema() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../bitcoin-core-api.json");
+        let methods = crate::load_api_methods_from_file(path).unwrap();
+        let find = |name: &str| methods.iter().find(|m| m.name == name).unwrap();
+
+        let src = build_return_type(find("getrawmempool")).unwrap().unwrap();
+        assert!(src.contains("Raw(Vec<bitcoin::Txid>)"), "{src}");
+
+        let src = build_return_type(find("listunspent")).unwrap().unwrap();
+        assert!(
+            src.contains("pub struct ListunspentResponse(pub Vec<ListunspentResponseItem>);"),
+            "{src}"
+        );
+        assert!(src.contains("pub struct ListunspentResponseItem {"), "{src}");
+        assert!(src.contains("pub txid: bitcoin::Txid,"), "{src}");
+    }
+
+    #[test]
+    fn test_nested_array_structs_inform_parent_derives() {
+        let element = result(
+            "object",
+            "",
+            vec![result("number", "difficulty", vec![]), result("string", "chain", vec![])],
+        );
+        let array = result("array", "entries", vec![element]);
+        let method = method("getstats", vec![result("object", "", vec![array])]);
+        let comparison = ResponseTypeOptions { comparison_derives: true, ..Default::default() };
+        let src = build_return_type_with_options(&method, comparison).unwrap().unwrap();
+
+        assert!(src.contains("pub entries: Vec<GetstatsResponseEntries>,"), "{src}");
+        assert!(
+            src.contains("pub struct GetstatsResponseEntries {\n    pub difficulty: f64,"),
+            "{src}"
+        );
+        // The element holds an f64, so neither type may derive `Eq`
+        assert!(!src.contains(", Eq"), "{src}");
+    }
+
+    #[test]
+    fn test_deny_unknown_fields_is_opt_in() {
+        let stats = result(
+            "object",
+            "stats",
+            vec![result("number", "count", vec![]), result("number", "bytes", vec![])],
+        );
+        let info = method(
+            "getmempoolinfo",
+            vec![result("object", "", vec![result("boolean", "loaded", vec![]), stats])],
+        );
+
+        let lenient = build_return_type(&info).unwrap().unwrap();
+        assert!(!lenient.contains("deny_unknown_fields"), "{lenient}");
+
+        let strict = ResponseTypeOptions { deny_unknown_fields: true, ..Default::default() };
+        let src = build_return_type_with_options(&info, strict).unwrap().unwrap();
+        let attr = "#[serde(deny_unknown_fields)]\npub struct";
+        assert!(src.contains(&format!("{attr} GetmempoolinfoResponse {{")), "{src}");
+        assert!(src.contains(&format!("{attr} GetmempoolinfoResponseStats {{")), "{src}");
+
+        // Transparent wrappers have no fields of their own to check
+        let count = method("getblockcount", vec![result("number", "", vec![])]);
+        let src = build_return_type_with_options(&count, strict).unwrap().unwrap();
+        assert!(!src.contains("deny_unknown_fields"), "{src}");
+    }
+
+    #[test]
+    fn test_nested_objects_become_named_structs() {
+        let bip9 = result(
+            "object",
+            "bip9",
+            vec![result("string", "status", vec![]), result("number", "since", vec![])],
+        );
+        let softforks =
+            result("object", "softforks", vec![bip9, result("boolean", "active", vec![])]);
+        let method = method(
+            "getblockchaininfo",
+            vec![result("object", "", vec![result("string", "chain", vec![]), softforks])],
+        );
+        let src = build_return_type(&method).unwrap().unwrap();
+
+        assert!(src.contains("pub softforks: GetblockchaininfoResponseSoftforks,"), "{src}");
+        assert!(src.contains("pub struct GetblockchaininfoResponseSoftforks {"), "{src}");
+        assert!(src.contains("pub bip9: GetblockchaininfoResponseSoftforksBip9,"), "{src}");
+        assert!(src.contains("pub struct GetblockchaininfoResponseSoftforksBip9 {"), "{src}");
+        assert!(src.contains("pub since: u64,"), "{src}");
+    }
+
+    #[test]
+    fn test_leading_digit_keys_become_valid_idents() {
+        let field = result("number", "10th_percentile_feerate", vec![]);
+        assert_eq!(field_ident(&field, 0), "_10th_percentile_feerate");
+        assert_eq!(
+            rename_attr(&field_ident(&field, 0), &json_key(&field)).as_deref(),
+            Some("#[serde(rename = \"10th_percentile_feerate\")]")
+        );
+    }
+
+    #[test]
+    fn test_gettxout_schema_renames_script_pubkey() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../bitcoin-core-api.json");
+        let methods = crate::load_api_methods_from_file(path).unwrap();
+        let gettxout = methods.iter().find(|m| m.name == "gettxout").unwrap();
+        let src = build_return_type(gettxout).unwrap().unwrap();
+        let src = src.split_whitespace().collect::<Vec<_>>().join(" ");
+
+        assert!(src.contains("#[serde(rename = \"scriptPubKey\")] script_pubkey:"), "{src}");
+    }
+
+    /// Assert that `schema` describes `value` the way the generated type deserializes it:
+    /// every key is a field and every required field is present, recursively.
+    fn assert_schema_describes(schema: &BtcResult, value: &serde_json::Value, path: &str) {
+        match value {
+            serde_json::Value::Object(object) => {
+                for (key, value) in object {
+                    let field = schema.inner.iter().find(|f| json_key(f) == *key);
+                    let field =
+                        field.unwrap_or_else(|| panic!("{path}.{key} is not in the schema"));
+                    assert_schema_describes(field, value, &format!("{path}.{key}"));
+                }
+                for field in schema.inner.iter().filter(|f| f.required()) {
+                    assert!(
+                        object.contains_key(&json_key(field)),
+                        "{path}.{} is missing",
+                        field.key_name
+                    );
+                }
+            }
+            serde_json::Value::Array(items) =>
+                for (i, item) in items.iter().enumerate() {
+                    assert_schema_describes(&schema.inner[0], item, &format!("{path}[{i}]"));
+                },
+            _ => assert!(schema.inner.is_empty(), "{path} is not a {}", schema.type_),
+        }
+    }
+
+    #[test]
+    fn test_verbose_transactions_get_nested_structs() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../bitcoin-core-api.json");
+        let methods =
+            verbosity::with_overloads(&crate::load_api_methods_from_file(path).unwrap()).unwrap();
+        let verbose = methods.iter().find(|m| m.name == "getrawtransaction_verbose").unwrap();
+        let src = build_return_type(verbose).unwrap().unwrap();
+
+        assert!(src.contains("pub struct GetrawtransactionVerboseResponse {"), "{src}");
+        assert!(src.contains("pub vin: Vec<GetrawtransactionVerboseResponseVin>,"), "{src}");
+        assert!(src.contains("pub vout: Vec<GetrawtransactionVerboseResponseVout>,"), "{src}");
+        assert!(
+            src.contains("pub script_pubkey: GetrawtransactionVerboseResponseVoutScriptPubKey,"),
+            "{src}"
+        );
+        assert!(src.contains("pub struct GetrawtransactionVerboseResponseVoutScriptPubKey {"));
+        assert!(src.contains(
+            "self.vout.get(n).map(|output| bitcoin::ScriptBuf::from_hex(&output.script_pubkey.hex))"
+        ), "{src}");
+
+        let captured = CAPTURED_RESPONSES.iter().find(|c| c.method == verbose.name).unwrap();
+        let captured = serde_json::from_str(captured.json).unwrap();
+        assert_schema_describes(&verbose.results[0], &captured, "getrawtransaction");
+
+        // The generated crate decodes the same response into the nested structs
+        let files = ResponseTypeCodeGenerator::new("v30").generate(&methods);
+        let src = &files[0].1;
+        assert!(src.contains("fn getrawtransaction_verbose_decodes_captured_response() {"));
+        assert!(src.contains("let response: GetrawtransactionVerboseResponse ="), "{src}");
+
+        // Only the transaction forms describe outputs
+        let hex = methods.iter().find(|m| m.name == "getrawtransaction_hex").unwrap();
+        assert!(!build_return_type(hex).unwrap().unwrap().contains("fn script_pubkey"));
+    }
+
+    #[test]
+    fn test_consensus_encoded_responses_convert_into_bitcoin_types() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../bitcoin-core-api.json");
+        let methods = crate::load_api_methods_from_file(path).unwrap();
+        let find = |name: &str| methods.iter().find(|m| m.name == name).unwrap();
+
+        let src = build_return_type(find("getrawtransaction")).unwrap().unwrap();
+        assert!(src.contains("impl TryFrom<&GetrawtransactionResponse> for bitcoin::Transaction"));
+        assert!(src.contains("Self::Raw(hex) => Some(hex.as_str()),"), "{src}");
+        assert!(src.contains("Self::Verbose { hex, .. } => Some(hex.as_str()),"), "{src}");
+
+        // Verbose blocks carry no hex, so they are reported as not encoded
+        let src = build_return_type(find("getblock")).unwrap().unwrap();
+        assert!(src.contains("impl TryFrom<GetblockResponse> for bitcoin::Block"), "{src}");
+        assert!(src.contains("            _ => None,"), "{src}");
+
+        let src = build_return_type(find("getblockheader")).unwrap().unwrap();
+        assert!(src.contains("for bitcoin::block::Header"), "{src}");
+
+        let src = build_return_type(find("getblockcount")).unwrap().unwrap();
+        assert!(!src.contains("TryFrom"), "{src}");
+    }
+
+    #[test]
+    fn test_optional_hex_fields_fall_back_to_none() {
+        let mut hex = result("string", "hex", vec![]);
+        hex.optional = true;
+        let mut verbose = result("object", "", vec![result("string", "txid", vec![]), hex]);
+        verbose.condition = "if verbose is set to true".into();
+        let mut raw = result("string", "", vec![]);
+        raw.condition = "if verbose is not set or set to false".into();
+        let src =
+            build_return_type(&method("getrawtransaction", vec![raw, verbose])).unwrap().unwrap();
+
+        assert!(src.contains("{ hex: Some(hex), .. } => Some(hex.as_str()),"), "{src}");
+        assert!(src.contains("            _ => None,\n        }"), "{src}");
+    }
+}
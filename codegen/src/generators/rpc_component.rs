@@ -0,0 +1,190 @@
+// codegen/src/generators/rpc_component.rs
+
+use std::fmt::Write;
+
+use bitcoin_rpc_types::BtcMethod;
+
+use crate::components::{component_of, RpcComponent};
+use crate::{CodeGenerator, MethodCategories};
+
+/// Generates `components.rs`: the `RpcComponent` of every method and `ComponentTransport`,
+/// which sends each call to its component's transport.
+#[derive(Debug, Clone, Default)]
+pub struct RpcComponentGenerator {
+    categories: MethodCategories,
+}
+
+impl RpcComponentGenerator {
+    /// Classify methods by their category in `categories` rather than the inferred one.
+    pub fn with_categories(mut self, categories: MethodCategories) -> Self {
+        self.categories = categories;
+        self
+    }
+}
+
+impl CodeGenerator for RpcComponentGenerator {
+    fn generate(&self, methods: &[BtcMethod]) -> Vec<(String, String)> {
+        let mut names = methods.iter().map(|m| m.name.as_str()).collect::<Vec<_>>();
+        names.sort_unstable();
+        names.dedup();
+
+        let mut registry = String::new();
+        for name in names {
+            let component = component_of(name, &self.categories);
+            writeln!(registry, "    (\"{name}\", RpcComponent::{component:?}),").unwrap();
+        }
+
+        let mut variants = String::new();
+        for component in RpcComponent::ALL {
+            writeln!(variants, "    /// `{}`\n    {component:?},", component.as_str()).unwrap();
+        }
+
+        let code = format!(
+            r#"//! The Bitcoin Core component serving each RPC method, and a transport routing calls to it.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde_json::Value;
+
+use super::{{TransportError, TransportTrait}};
+
+/// An independently-addressable part of Bitcoin Core serving RPCs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RpcComponent {{
+{variants}}}
+
+impl RpcComponent {{
+    /// The component serving `method`; methods missing from [`METHOD_COMPONENTS`] are the node's.
+    pub fn of_method(method: &str) -> Self {{
+        METHOD_COMPONENTS
+            .binary_search_by_key(&method, |(name, _)| *name)
+            .map_or(RpcComponent::Node, |index| METHOD_COMPONENTS[index].1)
+    }}
+}}
+
+/// The component of every method, sorted by method name.
+pub const METHOD_COMPONENTS: &[(&str, RpcComponent)] = &[
+{registry}];
+
+/// A transport sending each call to the transport of its method's [`RpcComponent`].
+///
+/// Components without a route of their own use the default transport, so wallet RPCs can
+/// go to a separate wallet process while everything else still reaches the node.
+#[derive(Clone)]
+pub struct ComponentTransport {{
+    default: Arc<dyn TransportTrait>,
+    routes: HashMap<RpcComponent, Arc<dyn TransportTrait>>,
+}}
+
+impl ComponentTransport {{
+    /// Route every call to `default` until [`with_route`](Self::with_route) says otherwise.
+    pub fn new(default: Arc<dyn TransportTrait>) -> Self {{
+        Self {{ default, routes: HashMap::new() }}
+    }}
+
+    /// Send the calls of `component`'s methods to `transport`.
+    pub fn with_route(mut self, component: RpcComponent, transport: Arc<dyn TransportTrait>) -> Self {{
+        self.routes.insert(component, transport);
+        self
+    }}
+
+    /// The transport serving `component`.
+    pub fn transport_for(&self, component: RpcComponent) -> &Arc<dyn TransportTrait> {{
+        self.routes.get(&component).unwrap_or(&self.default)
+    }}
+
+    fn route(&self, method: &str) -> &Arc<dyn TransportTrait> {{
+        self.transport_for(RpcComponent::of_method(method))
+    }}
+}}
+
+impl TransportTrait for ComponentTransport {{
+    fn send_request<'a>(&'a self, method: &'a str, params: &'a [Value]) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<Value, TransportError>> + Send + 'a>> {{
+        self.route(method).send_request(method, params)
+    }}
+
+    /// Send the batch to the transport of its methods, which must all share one.
+    fn send_batch<'a>(
+        &'a self,
+        bodies: &'a [Value],
+    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<Vec<Value>, TransportError>> + Send + 'a>> {{
+        Box::pin(async move {{
+            let mut transports = bodies
+                .iter()
+                .map(|body| self.route(body.get("method").and_then(Value::as_str).unwrap_or_default()));
+            let transport = transports.next().unwrap_or(&self.default);
+            if transports.any(|other| !Arc::ptr_eq(other, transport)) {{
+                return Err(TransportError::Rpc(
+                    "A batch cannot mix methods of components with different transports".to_string(),
+                ));
+            }}
+            transport.send_batch(bodies).await
+        }})
+    }}
+
+    fn url(&self) -> &str {{ self.default.url() }}
+
+    /// Route wallet methods to the `/wallet/<name>` endpoint of the wallet's transport.
+    fn for_wallet(&self, wallet_name: &str) -> Option<Arc<dyn TransportTrait>> {{
+        let wallet = self.transport_for(RpcComponent::Wallet).for_wallet(wallet_name)?;
+        Some(Arc::new(self.clone().with_route(RpcComponent::Wallet, wallet)))
+    }}
+
+    fn send_request_named<'a>(
+        &'a self,
+        method: &'a str,
+        params: &'a serde_json::Map<String, Value>,
+    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<Value, TransportError>> + Send + 'a>> {{
+        self.route(method).send_request_named(method, params)
+    }}
+
+    fn send_request_long_poll<'a>(
+        &'a self,
+        method: &'a str,
+        params: &'a [Value],
+        max_wait: std::time::Duration,
+    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<Option<Value>, TransportError>> + Send + 'a>> {{
+        self.route(method).send_request_long_poll(method, params, max_wait)
+    }}
+}}
+"#
+        );
+
+        vec![("components.rs".to_string(), code)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_is_sorted_and_classifies_wallet_methods() {
+        let methods = ["sendtoaddress", "getblockcount", "getbalance"]
+            .into_iter()
+            .map(|name| {
+                serde_json::from_value(serde_json::json!({
+                    "name": name,
+                    "description": "",
+                    "arguments": [],
+                    "results": [],
+                }))
+                .unwrap()
+            })
+            .collect::<Vec<BtcMethod>>();
+
+        let files = RpcComponentGenerator::default().generate(&methods);
+        let (name, src) = &files[0];
+        assert_eq!(name, "components.rs");
+        assert!(
+            src.contains(
+                "    (\"getbalance\", RpcComponent::Wallet),\n    \
+                 (\"getblockcount\", RpcComponent::Node),\n    \
+                 (\"sendtoaddress\", RpcComponent::Wallet),\n"
+            ),
+            "{src}"
+        );
+        assert!(src.contains("    /// `wallet`\n    Wallet,\n"), "{src}");
+    }
+}
@@ -0,0 +1,272 @@
+// codegen/src/generators/rpc_error.rs
+
+use std::fmt::Write;
+
+use bitcoin_rpc_types::BtcMethod;
+
+use crate::utils::capitalize;
+use crate::CodeGenerator;
+
+/// Bitcoin Core's RPC error codes (`src/rpc/protocol.h`), as `(name, code)`.
+///
+/// Each code's first entry is its canonical name; later entries with the same code are
+/// backward-compatible aliases (e.g. `RPC_TRANSACTION_ALREADY_IN_UTXO_SET`).
+pub const RPC_ERROR_CODES: &[(&str, i32)] = &[
+    // Standard JSON-RPC 2.0 errors
+    ("RPC_INVALID_REQUEST", -32600),
+    ("RPC_METHOD_NOT_FOUND", -32601),
+    ("RPC_INVALID_PARAMS", -32602),
+    ("RPC_INTERNAL_ERROR", -32603),
+    ("RPC_PARSE_ERROR", -32700),
+    // General application defined errors
+    ("RPC_MISC_ERROR", -1),
+    ("RPC_FORBIDDEN_BY_SAFE_MODE", -2),
+    ("RPC_TYPE_ERROR", -3),
+    ("RPC_INVALID_ADDRESS_OR_KEY", -5),
+    ("RPC_OUT_OF_MEMORY", -7),
+    ("RPC_INVALID_PARAMETER", -8),
+    ("RPC_DATABASE_ERROR", -20),
+    ("RPC_DESERIALIZATION_ERROR", -22),
+    ("RPC_VERIFY_ERROR", -25),
+    ("RPC_VERIFY_REJECTED", -26),
+    ("RPC_VERIFY_ALREADY_IN_UTXO_SET", -27),
+    ("RPC_IN_WARMUP", -28),
+    ("RPC_METHOD_DEPRECATED", -32),
+    ("RPC_TRANSACTION_ERROR", -25),
+    ("RPC_TRANSACTION_REJECTED", -26),
+    ("RPC_TRANSACTION_ALREADY_IN_UTXO_SET", -27),
+    // P2P client errors
+    ("RPC_CLIENT_NOT_CONNECTED", -9),
+    ("RPC_CLIENT_IN_INITIAL_DOWNLOAD", -10),
+    ("RPC_CLIENT_NODE_ALREADY_ADDED", -23),
+    ("RPC_CLIENT_NODE_NOT_ADDED", -24),
+    ("RPC_CLIENT_NODE_NOT_CONNECTED", -29),
+    ("RPC_CLIENT_INVALID_IP_OR_SUBNET", -30),
+    ("RPC_CLIENT_P2P_DISABLED", -31),
+    ("RPC_CLIENT_MEMPOOL_DISABLED", -33),
+    ("RPC_CLIENT_NODE_CAPACITY_REACHED", -34),
+    // Wallet errors
+    ("RPC_WALLET_ERROR", -4),
+    ("RPC_WALLET_INSUFFICIENT_FUNDS", -6),
+    ("RPC_WALLET_INVALID_LABEL_NAME", -11),
+    ("RPC_WALLET_KEYPOOL_RAN_OUT", -12),
+    ("RPC_WALLET_UNLOCK_NEEDED", -13),
+    ("RPC_WALLET_PASSPHRASE_INCORRECT", -14),
+    ("RPC_WALLET_WRONG_ENC_STATE", -15),
+    ("RPC_WALLET_ENCRYPTION_FAILED", -16),
+    ("RPC_WALLET_ALREADY_UNLOCKED", -17),
+    ("RPC_WALLET_NOT_FOUND", -18),
+    ("RPC_WALLET_NOT_SPECIFIED", -19),
+    ("RPC_WALLET_ALREADY_LOADED", -35),
+    ("RPC_WALLET_ALREADY_EXISTS", -36),
+];
+
+/// The canonical name of `code`, if Bitcoin Core defines it.
+pub fn canonical_name(code: i32) -> Option<&'static str> {
+    RPC_ERROR_CODES.iter().find(|(_, c)| *c == code).map(|(name, _)| *name)
+}
+
+/// The `BitcoinRpcError` variant for a canonical name, e.g. `VerifyAlreadyInUtxoSet`.
+pub fn variant_name(name: &str) -> String {
+    capitalize(&name.trim_start_matches("RPC_").to_lowercase())
+}
+
+/// The error codes a method description documents, in order of first mention.
+///
+/// Recognizes `RPC_*` tokens from [`RPC_ERROR_CODES`] (aliases resolve to their code) and
+/// numeric codes written as `error code -N`. Unknown tokens and codes are ignored, so
+/// callers fall back to the generic code and message for them.
+pub fn documented_error_codes(description: &str) -> Vec<i32> {
+    let words = description
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-'))
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>();
+
+    let mut codes = Vec::new();
+    for (i, word) in words.iter().enumerate() {
+        let code = if word.starts_with("RPC_") {
+            RPC_ERROR_CODES.iter().find(|(name, _)| name == word).map(|(_, code)| *code)
+        } else if i >= 2 && words[i - 1] == "code" && words[i - 2].eq_ignore_ascii_case("error") {
+            word.parse::<i32>().ok().filter(|code| canonical_name(*code).is_some())
+        } else {
+            None
+        };
+        if let Some(code) = code.filter(|code| !codes.contains(code)) {
+            codes.push(code);
+        }
+    }
+    codes
+}
+
+/// `///` lines naming the errors documented for `method`, for its wrapper's doc comment.
+pub fn error_docs(method: &BtcMethod) -> String {
+    let codes = documented_error_codes(&method.description);
+    if codes.is_empty() {
+        return String::new();
+    }
+    let mut doc = String::from("///\n/// # Errors\n/// Documented to fail with:\n");
+    for code in codes {
+        let name = canonical_name(code).expect("documented codes are known");
+        writeln!(
+            doc,
+            "/// - [`BitcoinRpcError::{0}`](crate::transport::BitcoinRpcError::{0}) ({code})",
+            variant_name(name)
+        )
+        .unwrap();
+    }
+    doc
+}
+
+/// Generates `rpc_error.rs`: the shared `BitcoinRpcError` enum of Bitcoin Core's error
+/// codes and the errors each method documents.
+pub struct RpcErrorGenerator;
+
+impl CodeGenerator for RpcErrorGenerator {
+    fn generate(&self, methods: &[BtcMethod]) -> Vec<(String, String)> {
+        let mut variants = String::new();
+        let mut from_code = String::new();
+        let mut to_code = String::new();
+        let mut names = String::new();
+        for (index, (name, code)) in RPC_ERROR_CODES.iter().enumerate() {
+            let aliases = RPC_ERROR_CODES[index + 1..]
+                .iter()
+                .filter(|(_, c)| c == code)
+                .map(|(alias, _)| format!("`{alias}`"))
+                .collect::<Vec<_>>();
+            // Aliases share the canonical variant
+            if canonical_name(*code) != Some(name) {
+                continue;
+            }
+            let variant = variant_name(name);
+            write!(variants, "    /// `{name}` ({code})").unwrap();
+            if !aliases.is_empty() {
+                write!(variants, ", also known as {}", aliases.join(", ")).unwrap();
+            }
+            writeln!(variants, "\n    {variant},").unwrap();
+            writeln!(from_code, "            {code} => BitcoinRpcError::{variant},").unwrap();
+            writeln!(to_code, "            BitcoinRpcError::{variant} => {code},").unwrap();
+            writeln!(names, "            BitcoinRpcError::{variant} => Some(\"{name}\"),").unwrap();
+        }
+
+        let mut documented = String::new();
+        for m in methods {
+            let codes = documented_error_codes(&m.description);
+            if codes.is_empty() {
+                continue;
+            }
+            let errors = codes
+                .iter()
+                .map(|code| {
+                    let name = canonical_name(*code).expect("documented codes are known");
+                    format!("BitcoinRpcError::{}", variant_name(name))
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            writeln!(documented, "    (\"{}\", &[{errors}]),", m.name).unwrap();
+        }
+
+        let code = format!(
+            r#"//! Bitcoin Core's RPC error codes as a typed enum.
+
+use super::TransportError;
+
+/// An error code Bitcoin Core returns in JSON-RPC error objects (`src/rpc/protocol.h`).
+///
+/// Codes this crate does not know are kept as [`Other`](BitcoinRpcError::Other), so a
+/// `match` is exhaustive without losing the raw code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BitcoinRpcError {{
+{variants}    /// A code not listed above.
+    Other(i32),
+}}
+
+impl BitcoinRpcError {{
+    /// The variant for a raw error code.
+    pub fn from_code(code: i32) -> Self {{
+        match code {{
+{from_code}            other => BitcoinRpcError::Other(other),
+        }}
+    }}
+
+    /// The raw error code.
+    pub fn code(self) -> i32 {{
+        match self {{
+{to_code}            BitcoinRpcError::Other(code) => code,
+        }}
+    }}
+
+    /// The `RPC_*` name Bitcoin Core uses for this code, if it has one.
+    pub fn name(self) -> Option<&'static str> {{
+        match self {{
+{names}            BitcoinRpcError::Other(_) => None,
+        }}
+    }}
+}}
+
+impl TransportError {{
+    /// The typed Bitcoin Core error, if this is a standard RPC error object.
+    pub fn bitcoin_rpc_error(&self) -> Option<BitcoinRpcError> {{
+        self.rpc_code().map(BitcoinRpcError::from_code)
+    }}
+}}
+
+/// The errors each method's documentation names, as `(method, errors)`.
+pub const DOCUMENTED_ERRORS: &[(&str, &[BitcoinRpcError])] = &[
+{documented}];
+
+/// The errors `method`'s documentation names (empty if it names none).
+pub fn documented_errors(method: &str) -> &'static [BitcoinRpcError] {{
+    DOCUMENTED_ERRORS.iter().find(|(m, _)| *m == method).map_or(&[], |(_, errors)| errors)
+}}
+"#
+        );
+        vec![("rpc_error".to_string(), code)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SENDRAWTRANSACTION: &str = "Submit a raw transaction (serialized, hex-encoded) to local node and network.\n\n\
+        A specific exception, RPC_TRANSACTION_ALREADY_IN_UTXO_SET, may throw if the transaction cannot be added to the mempool.\n\n\
+        Related RPCs: createrawtransaction, signrawtransactionwithkey";
+
+    #[test]
+    fn test_extracts_documented_error_tokens() {
+        assert_eq!(documented_error_codes(SENDRAWTRANSACTION), [-27]);
+        assert_eq!(canonical_name(-27), Some("RPC_VERIFY_ALREADY_IN_UTXO_SET"));
+        assert_eq!(variant_name("RPC_VERIFY_ALREADY_IN_UTXO_SET"), "VerifyAlreadyInUtxoSet");
+
+        assert_eq!(documented_error_codes("Fails with error code -8 or RPC_IN_WARMUP."), [-8, -28]);
+        // Unknown tokens and codes fall back to the generic code and message
+        assert!(documented_error_codes("RPC_NOT_A_CODE, error code -999, code -5").is_empty());
+    }
+
+    #[test]
+    fn test_generates_shared_error_enum() {
+        let method = BtcMethod {
+            name: "sendrawtransaction".into(),
+            description: SENDRAWTRANSACTION.into(),
+            examples: String::new(),
+            argument_names: vec![],
+            arguments: vec![],
+            results: vec![],
+        };
+        assert!(error_docs(&method).contains(
+            "[`BitcoinRpcError::VerifyAlreadyInUtxoSet`]\
+             (crate::transport::BitcoinRpcError::VerifyAlreadyInUtxoSet) (-27)"
+        ));
+
+        let files = RpcErrorGenerator.generate(&[method]);
+        let src = &files[0].1;
+        assert!(src.contains("    VerifyAlreadyInUtxoSet,\n"), "{src}");
+        assert!(src.contains("also known as `RPC_TRANSACTION_ALREADY_IN_UTXO_SET`"), "{src}");
+        assert!(src.contains("-27 => BitcoinRpcError::VerifyAlreadyInUtxoSet,"), "{src}");
+        assert!(!src.contains("TransactionAlreadyInUtxoSet"), "{src}");
+        assert!(
+            src.contains("(\"sendrawtransaction\", &[BitcoinRpcError::VerifyAlreadyInUtxoSet]),"),
+            "{src}"
+        );
+    }
+}
@@ -7,8 +7,8 @@ use bitcoin_rpc_types::BtcMethod;
 use super::utils::camel;
 use crate::generators::doc_comment;
 use crate::generators::test_node::versions::get_helpers_for_version;
-use crate::utils::{camel_to_snake_case, rust_type_for_argument};
-use crate::Version;
+use crate::utils::{camel_to_snake_case, rust_type_for_argument, sanitize_ident};
+use crate::{MethodCategories, Version};
 
 /// Generates a complete Rust client struct and implementation for a collection of Bitcoin RPC methods.
 ///
@@ -20,6 +20,7 @@ pub fn generate_combined_client(
     client_name: &str,
     methods: &[BtcMethod],
     version: &Version,
+    categories: &MethodCategories,
 ) -> std::io::Result<String> {
     let mut code = String::new();
 
@@ -31,12 +32,13 @@ pub fn generate_combined_client(
     emit_constructors(&mut code)?;
     emit_wallet_methods(&mut code)?;
     helpers.emit_block_mining_helpers(&mut code)?;
+    emit_mining_helpers(&mut code)?;
     helpers.emit_reset_chain(&mut code)?;
     emit_stop_node(&mut code)?;
     emit_node_manager_accessor(&mut code)?;
     emit_rpc_accessor(&mut code)?;
     emit_batch_method(&mut code)?;
-    emit_delegated_rpc_methods(&mut code, methods)?;
+    emit_delegated_rpc_methods(&mut code, methods, categories)?;
     helpers.emit_send_to_address_helpers(&mut code)?;
     writeln!(code, "}}\n").unwrap();
     emit_drop_impl(&mut code, client_name)?;
@@ -71,7 +73,7 @@ pub fn emit_imports(code: &mut String, version: &Version) -> std::io::Result<()>
         "use anyhow::Result;
 use std::sync::Arc;
 use crate::transport::core::{{TransportError, TransportExt}};
-use crate::transport::{{DefaultTransport, RpcClient, BatchBuilder}};
+use crate::transport::{{AddressType, BitcoinRpcError, DefaultTransport, Descriptor, EstimateMode, RpcClient, BatchBuilder}};
 use crate::responses::{version_lowercase}_responses::*;
 use serde_json::Value;
 use bitcoin_rpc_types::HashOrHeight;
@@ -185,39 +187,8 @@ pub fn emit_constructors(code: &mut String) -> std::io::Result<()> {
         // Create RPC client for batching support
         let rpc = RpcClient::from_transport(transport.clone());
         
-        // Wait for node to be ready for RPC
-        // Core initialization states that require waiting:
-        // -28: RPC in warmup
-        // -4:  RPC in warmup (alternative code)
-        let init_states = [
-            \"\\\"code\\\":-28\",
-            \"\\\"code\\\":-4\",
-        ];
-        
-        let max_retries = 30;
-        let mut retries = 0;
-        
-        loop {{
-            match transport.call::<serde_json::Value>(\"getblockchaininfo\", &[]).await {{
-                Ok(_) => break,
-                Err(TransportError::Rpc(e)) => {{
-                    // Check if the error matches any known initialization state
-                    let is_init_state = init_states.iter().any(|state| e.contains(state));
-                    if is_init_state && retries < max_retries {{
-                        tracing::debug!(\"Waiting for initialization: {{}} (attempt {{}}/{{}})\", e, retries + 1, max_retries);
-                        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
-                        retries += 1;
-                        continue;
-                    }}
-                    return Err(TransportError::Rpc(e));
-                }}
-                Err(e) => return Err(e),
-            }}
-        }}
-        
-        if retries > 0 {{
-            tracing::debug!(\"Node initialization completed after {{}} attempts\", retries);
-        }}
+        // Wait for node to be ready for RPC (retries while Core reports -28 or -4 during startup)
+        rpc.wait_until_ready(std::time::Duration::from_secs(30)).await?;
         
         Ok(Self {{
             transport,
@@ -272,7 +243,7 @@ pub fn emit_wallet_methods(code: &mut String) -> std::io::Result<()> {
              \n\
              match self.transport.call::<CreatewalletResponse>(\"createwallet\", &params).await {{\n\
                  Ok(_) => Ok(wallet_name),\n\
-                 Err(TransportError::Rpc(err)) if err.contains(\"\\\"code\\\":-4\") => {{\n\
+                 Err(e) if Self::is_existing_wallet_error(&e) => {{\n\
                      // Try loading instead\n\
                      params.clear();\n\
                      params.push(serde_json::to_value(wallet_name.clone())?);\n\
@@ -293,14 +264,88 @@ pub fn emit_wallet_methods(code: &mut String) -> std::io::Result<()> {
                  Err(e) => Err(e),\n\
              }}\n\
          }}\n\n\
+         /// Whether `e` reports that the wallet being created already exists or is loaded.\n\
+         ///\n\
+         /// Core reports an existing wallet database as a generic wallet error (`-4`), which\n\
+         /// only counts when its message says so: `-4` also covers e.g. a failed write.\n\
+         fn is_existing_wallet_error(e: &TransportError) -> bool {{\n\
+             match e.bitcoin_rpc_error() {{\n\
+                 Some(BitcoinRpcError::WalletAlreadyExists | BitcoinRpcError::WalletAlreadyLoaded) => true,\n\
+                 Some(BitcoinRpcError::WalletError) => e.to_string().contains(\"already exists\"),\n\
+                 _ => false,\n\
+             }}\n\
+         }}\n\n\
          /// Shortcut for `ensure_wallet_with_options(\"test_wallet\", WalletOptions::default().with_descriptors())`\n\
          pub async fn ensure_default_wallet(&mut self, name: impl Into<String>) -> Result<String, TransportError> {{\n\
              self.ensure_wallet_with_options(name, WalletOptions::default().with_descriptors()).await\n\
+         }}\n\n\
+         /// Loads the named wallet, creating it with default options if it does not exist yet.\n\
+         /// \"Already exists\" and \"already loaded\" errors are ignored. Returns the wallet name;\n\
+         /// use `self.rpc().wallet(&name)` to route calls to it when several wallets are loaded.\n\
+         pub async fn load_or_create_wallet(&self, name: impl Into<String>) -> Result<String, TransportError> {{\n\
+             let name = name.into();\n\
+             let params = [serde_json::to_value(&name)?];\n\
+             match self.transport.call::<Value>(\"createwallet\", &params).await {{\n\
+                 Ok(_) => return Ok(name),\n\
+                 Err(e) if Self::is_existing_wallet_error(&e) => tracing::debug!(\"Wallet {{}} exists, loading it: {{}}\", name, e),\n\
+                 Err(e) => return Err(e),\n\
+             }}\n\
+             match self.transport.call::<Value>(\"loadwallet\", &params).await {{\n\
+                 Ok(_) => Ok(name),\n\
+                 Err(e) if e.bitcoin_rpc_error() == Some(BitcoinRpcError::WalletAlreadyLoaded) => Ok(name),\n\
+                 Err(e) => Err(e),\n\
+             }}\n\
          }}\n"
     ).unwrap();
     Ok(())
 }
 
+/// Generates the version-independent mining helpers for the combined Bitcoin test client.
+///
+/// # Arguments
+/// * `code` - The string buffer to append the mining helpers to
+///
+/// # Returns
+/// * `std::io::Result<()>` - Success or failure of writing to the code buffer
+pub fn emit_mining_helpers(code: &mut String) -> std::io::Result<()> {
+    writeln!(
+        code,
+        "    /// Mines `num_blocks` blocks paying to `address` via `generatetoaddress`.\n\
+         /// Returns the hashes of the new blocks. No wallet is needed.\n\
+         pub async fn mine_blocks_to_address(\n\
+             &self,\n\
+             num_blocks: u64,\n\
+             address: &str,\n\
+         ) -> Result<Vec<bitcoin::BlockHash>, TransportError> {{\n\
+             let params = [serde_json::to_value(num_blocks)?, serde_json::to_value(address)?];\n\
+             self.transport.call(\"generatetoaddress\", &params).await\n\
+         }}\n\n\
+         /// Loads or creates `test_wallet` and mines 101 blocks to a new address in it, so the\n\
+         /// first coinbase has the 100 confirmations it needs to be spendable. Regtest only.\n\
+         ///\n\
+         /// Returns the funded address once `getbalance` reports a mature balance.\n\
+         pub async fn fund_wallet(&self) -> Result<bitcoin::Address, TransportError> {{\n\
+             // A coinbase output matures after 100 confirmations, i.e. 100 blocks on top of it\n\
+             const BLOCKS_TO_MATURITY: u64 = 101;\n\n\
+             self.load_or_create_wallet(\"test_wallet\").await?;\n\
+             let address = self.getnewaddress(String::new(), AddressType::Bech32m).await?.0;\n\
+             self.generatetoaddress(BLOCKS_TO_MATURITY, address.clone(), 1_000_000).await?;\n\n\
+             let balance = self.getbalance(None, 1, false, false).await?.0;\n\
+             if balance <= 0.0 {{\n\
+                 return Err(TransportError::Rpc(format!(\n\
+                     \"Wallet has no spendable balance after mining {{BLOCKS_TO_MATURITY}} blocks\"\n\
+                 )));\n\
+             }}\n\
+             address\n\
+                 .parse::<bitcoin::Address<bitcoin::address::NetworkUnchecked>>()\n\
+                 .and_then(|a| a.require_network(Network::Regtest))\n\
+                 .map_err(|e| TransportError::Rpc(format!(\"Invalid address {{address}}: {{e}}\")))\n\
+         }}\n"
+    )
+    .unwrap();
+    Ok(())
+}
+
 /// Generates the method for stopping the node for the combined Bitcoin test client.
 ///
 /// This function emits the method for stopping the node for the `{client_name}` struct.
@@ -394,12 +439,17 @@ pub fn emit_batch_method(code: &mut String) -> std::io::Result<()> {
 /// # Arguments
 /// * `code` - The string buffer to append the RPC methods to
 /// * `methods` - The methods to emit
+/// * `categories` - Gates each method behind the feature of its category
 ///
 /// # Returns
 /// * `std::io::Result<()>` - Success or failure of writing to the code buffer
-pub fn emit_delegated_rpc_methods(code: &mut String, methods: &[BtcMethod]) -> std::io::Result<()> {
+pub fn emit_delegated_rpc_methods(
+    code: &mut String,
+    methods: &[BtcMethod],
+    categories: &MethodCategories,
+) -> std::io::Result<()> {
     for m in methods {
-        let method_snake = camel_to_snake_case(&m.name);
+        let method_snake = sanitize_ident(&camel_to_snake_case(&m.name));
         let doc_comment = doc_comment::format_doc_comment(&m.description);
 
         // Get the specific return type for this method
@@ -416,11 +466,7 @@ pub fn emit_delegated_rpc_methods(code: &mut String, methods: &[BtcMethod]) -> s
                 .arguments
                 .iter()
                 .map(|arg| {
-                    let name = if arg.names[0] == "type" {
-                        "_type"
-                    } else {
-                        &camel_to_snake_case(&arg.names[0])
-                    };
+                    let name = sanitize_ident(&camel_to_snake_case(&arg.names[0]));
                     let ty = rust_type_for_argument(&arg.names[0], &arg.type_);
                     format!("{name}: {ty}")
                 })
@@ -431,11 +477,7 @@ pub fn emit_delegated_rpc_methods(code: &mut String, methods: &[BtcMethod]) -> s
                 .arguments
                 .iter()
                 .map(|arg| {
-                    let name = if arg.names[0] == "type" {
-                        "_type"
-                    } else {
-                        &camel_to_snake_case(&arg.names[0])
-                    };
+                    let name = sanitize_ident(&camel_to_snake_case(&arg.names[0]));
                     format!("        params.push(serde_json::to_value({name})?);")
                 })
                 .collect::<Vec<_>>()
@@ -456,8 +498,10 @@ pub fn emit_delegated_rpc_methods(code: &mut String, methods: &[BtcMethod]) -> s
 
         writeln!(
             code,
-            "{}\n{}    pub async fn {}(&self{}{}) -> Result<{}, TransportError> {{\n{}\n    }}\n",
+            "{}\n{}{}{}    pub async fn {}(&self{}{}) -> Result<{}, TransportError> {{\n{}\n    }}\n",
             doc_comment,
+            categories.cfg(&m.name, "    "),
+            doc_comment::deprecated_attribute(&m.description, "    "),
             clippy_allow,
             method_snake,
             if param_list.is_empty() { "" } else { ", " },
@@ -5,7 +5,8 @@ use std::fmt::Write as _;
 use bitcoin_rpc_types::BtcMethod;
 
 use crate::generators::doc_comment;
-use crate::utils::{camel_to_snake_case, capitalize, rust_type_for_argument};
+use crate::utils::{camel_to_snake_case, capitalize, rust_type_for_argument, sanitize_ident};
+use crate::{type_registry, MethodCategories};
 
 /// Generates Rust parameter structs for Bitcoin RPC methods that require arguments.
 ///
@@ -25,25 +26,33 @@ use crate::utils::{camel_to_snake_case, capitalize, rust_type_for_argument};
 /// # Arguments
 ///
 /// * `methods` - A slice of API methods from the Bitcoin RPC specification
+/// * `categories` - Gates each struct behind the feature of its method's category
 ///
 /// # Returns
 ///
 /// A `String` containing the complete Rust code for all parameter structs
-pub fn generate_params_code(methods: &[BtcMethod]) -> String {
-    let mut code = String::from(
-        "//! Parameter structs for RPC method calls\nuse serde::Serialize;\nuse bitcoin_rpc_types::HashOrHeight;\n\n",
-    );
+pub fn generate_params_code(methods: &[BtcMethod], categories: &MethodCategories) -> String {
+    let mut code =
+        String::from("//! Parameter structs for RPC method calls\nuse serde::Serialize;\n");
+    for (_, path) in type_registry::NAMED_ARGUMENT_IMPORTS {
+        writeln!(code, "use {path};").unwrap();
+    }
+    code.push('\n');
     for m in methods {
         if m.arguments.is_empty() {
             continue;
         }
         writeln!(code, "{}", doc_comment::format_doc_comment(&m.description)).unwrap();
+        code.push_str(&categories.cfg(&m.name, ""));
         writeln!(code, "#[derive(Debug, Serialize)]\npub struct {}Params {{", capitalize(&m.name))
             .unwrap();
         for p in &m.arguments {
-            let field =
-                if p.names[0] == "type" { "_type" } else { &camel_to_snake_case(&p.names[0]) };
+            let field = sanitize_ident(&camel_to_snake_case(&p.names[0]));
             let ty = rust_type_for_argument(&p.names[0], &p.type_);
+            // Unset arguments are left out rather than sent as `null`
+            if ty.starts_with("Option<") {
+                writeln!(code, "    #[serde(skip_serializing_if = \"Option::is_none\")]").unwrap();
+            }
             writeln!(code, "    pub {field}: {ty},").unwrap();
         }
         writeln!(code, "}}\n").unwrap();
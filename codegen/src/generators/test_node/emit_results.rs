@@ -2,17 +2,18 @@
 
 use std::fmt::Write as _;
 
-use bitcoin_rpc_conversions::TypeRegistry;
 use bitcoin_rpc_types::{BtcMethod, BtcResult};
 
 use super::utils::camel;
+use crate::{type_registry, MethodCategories};
 
 /// Generates Rust struct definitions for RPC method response types.
 ///
 /// This function creates transparent wrapper structs for methods that return a single result.
 /// Each generated struct is named `{MethodName}Response` and wraps the actual return type
-/// with serde deserialization support.
-pub fn generate_result_code(methods: &[BtcMethod]) -> String {
+/// with serde deserialization support. Each struct is gated behind the feature of its
+/// method's category in `categories`.
+pub fn generate_result_code(methods: &[BtcMethod], categories: &MethodCategories) -> String {
     let mut code =
         String::from("//! Result structs for RPC method returns\nuse serde::Deserialize;\n\n");
     for m in methods {
@@ -23,7 +24,8 @@ pub fn generate_result_code(methods: &[BtcMethod]) -> String {
         let ty = rust_type_for_result(r);
         writeln!(
             code,
-            "#[derive(Debug, Deserialize)]\n#[serde(transparent)]\npub struct {}Response(pub {});\n",
+            "{}#[derive(Debug, Deserialize)]\n#[serde(transparent)]\npub struct {}Response(pub {});\n",
+            categories.cfg(&m.name, ""),
             camel(&m.name),
             ty
         )
@@ -44,7 +46,7 @@ pub fn generate_result_code(methods: &[BtcMethod]) -> String {
 /// # Returns
 /// A `String` representing the Rust type for the result, possibly wrapped in `Option<>`.
 fn rust_type_for_result(result: &BtcResult) -> String {
-    let (base_ty, is_option) = TypeRegistry::map_result_type(result);
+    let (base_ty, is_option) = type_registry::map_result_type(result);
     if is_option {
         format!("Option<{base_ty}>")
     } else {
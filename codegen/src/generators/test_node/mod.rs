@@ -5,7 +5,7 @@
 
 use bitcoin_rpc_types::BtcMethod;
 
-use crate::{CodeGenerator, Version};
+use crate::{CodeGenerator, MethodCategories, Version};
 
 pub mod emit_combined_client;
 pub mod emit_params;
@@ -47,6 +47,7 @@ pub mod versions;
 /// while maintaining type safety and proper error handling throughout the test suite.
 pub struct TestNodeGenerator {
     version: Version,
+    categories: MethodCategories,
 }
 
 impl TestNodeGenerator {
@@ -55,18 +56,28 @@ impl TestNodeGenerator {
     /// The `version` string determines which RPC methods and structures are used when generating
     /// type-safe test clients and associated modules. This allows test code to stay in sync with
     /// version-specific behavior in Bitcoin Core.
-    pub fn new(version: Version) -> Self { Self { version } }
+    pub fn new(version: Version) -> Self {
+        Self { version, categories: MethodCategories::default() }
+    }
+
+    /// Put each RPC method of the client, and its params and result structs, behind the
+    /// feature of its category.
+    pub fn with_categories(mut self, categories: MethodCategories) -> Self {
+        self.categories = categories;
+        self
+    }
 }
 
 impl CodeGenerator for TestNodeGenerator {
     fn generate(&self, methods: &[BtcMethod]) -> Vec<(String, String)> {
-        let params_code = emit_params::generate_params_code(methods);
-        let result_code = emit_results::generate_result_code(methods);
+        let params_code = emit_params::generate_params_code(methods, &self.categories);
+        let result_code = emit_results::generate_result_code(methods, &self.categories);
 
         let client_code = emit_combined_client::generate_combined_client(
             "BitcoinTestClient",
             methods,
             &self.version,
+            &self.categories,
         )
         .unwrap();
 
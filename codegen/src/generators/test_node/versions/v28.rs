@@ -16,14 +16,14 @@ impl VersionedClientHelpers for V28Helpers {
          /// - address: The destination address\n\
          /// - amount: The amount to send\n\
          /// - conf_target: The confirmation target in blocks\n\
-         /// - estimate_mode: The fee estimate mode (\"economical\" or \"conservative\")\n\
+         /// - estimate_mode: The fee estimate mode\n\
          /// ```\n\
          pub async fn send_to_address_with_conf_target(\n\
          &self,\n\
          address: String,\n\
          amount: Amount,\n\
          conf_target: u64,\n\
-         estimate_mode: String,\n\
+         estimate_mode: EstimateMode,\n\
      ) -> Result<Value, TransportError> {{\n\
          Ok(serde_json::to_value(self.sendtoaddress(\n\
              address,\n\
@@ -54,7 +54,7 @@ impl VersionedClientHelpers for V28Helpers {
          false,\n\
          true,\n\
          0u64,\n\
-         \"unset\".to_string(),\n\
+         EstimateMode::Unset,\n\
          false,\n\
          fee_rate,\n\
          false,\n\
@@ -137,7 +137,7 @@ pub async fn mine_blocks(&mut self, num_blocks: u64, maxtries: u64) -> Result<(S
     let _wallet_name = self.ensure_default_wallet(\"test_wallet\").await?;
 
     tracing::debug!(\"Getting new address\");
-    let address = self.getnewaddress(\"\".to_string(), \"bech32m\".to_string()).await?;
+    let address = self.getnewaddress(\"\".to_string(), AddressType::Bech32m).await?;
     tracing::debug!(\"Generated address: {{:?}}\", address);
     tracing::debug!(\"Generating blocks\");
     let blocks = self.generatetoaddress(
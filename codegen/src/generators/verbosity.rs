@@ -0,0 +1,310 @@
+// codegen/src/generators/verbosity.rs
+
+use anyhow::{anyhow, Result};
+use bitcoin_rpc_types::{BtcMethod, BtcResult};
+
+/// One response form of a method whose result shape depends on a verbosity argument.
+///
+/// The overload is generated as its own wrapper (`getblock_verbose2`) with its own
+/// response type (`GetblockVerbose2Response`). The `fixed` arguments are left out of its
+/// signature and always sent with the given value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VerbosityOverload {
+    /// The RPC method, e.g. `getblock`.
+    pub method: &'static str,
+    /// Appended to the method name, e.g. `_verbose2`.
+    pub suffix: &'static str,
+    /// Arguments sent as a fixed JSON literal, as `(name, value)`.
+    ///
+    /// The form's result is the one whose condition (e.g. `for verbosity = 1`) gives every
+    /// fixed argument its value.
+    pub fixed: &'static [(&'static str, &'static str)],
+    /// Suffix of the overload whose fields this one extends, for results that start
+    /// with a `...` ("same output as verbosity = 1") entry.
+    pub extends: Option<&'static str>,
+}
+
+impl VerbosityOverload {
+    /// The overload's method name, e.g. `getblock_verbose2`.
+    pub fn name(&self) -> String { format!("{}{}", self.method, self.suffix) }
+}
+
+/// The verbosity overloads generated alongside their methods.
+///
+/// `getrawmempool` with `verbose = true` (a map keyed by txid) and `getblock` with
+/// `verbosity = 3` have no form the response generator can type, so they are only
+/// reachable through the untyped base method.
+pub const VERBOSITY_OVERLOADS: &[VerbosityOverload] = &[
+    VerbosityOverload {
+        method: "getblock",
+        suffix: "_hex",
+        fixed: &[("verbosity", "0")],
+        extends: None,
+    },
+    VerbosityOverload {
+        method: "getblock",
+        suffix: "_verbose",
+        fixed: &[("verbosity", "1")],
+        extends: None,
+    },
+    VerbosityOverload {
+        method: "getblock",
+        suffix: "_verbose2",
+        fixed: &[("verbosity", "2")],
+        extends: Some("_verbose"),
+    },
+    VerbosityOverload {
+        method: "getblockheader",
+        suffix: "_hex",
+        fixed: &[("verbose", "false")],
+        extends: None,
+    },
+    VerbosityOverload {
+        method: "getblockheader",
+        suffix: "_verbose",
+        fixed: &[("verbose", "true")],
+        extends: None,
+    },
+    VerbosityOverload {
+        method: "getrawtransaction",
+        suffix: "_hex",
+        fixed: &[("verbosity", "0")],
+        extends: None,
+    },
+    VerbosityOverload {
+        method: "getrawtransaction",
+        suffix: "_verbose",
+        fixed: &[("verbosity", "1")],
+        extends: None,
+    },
+    VerbosityOverload {
+        method: "getrawtransaction",
+        suffix: "_verbose2",
+        fixed: &[("verbosity", "2")],
+        extends: Some("_verbose"),
+    },
+    VerbosityOverload {
+        method: "getrawmempool",
+        suffix: "_sequence",
+        fixed: &[("verbose", "false"), ("mempool_sequence", "true")],
+        extends: None,
+    },
+];
+
+/// The overload generated under `name`, if it is one.
+pub fn overload(name: &str) -> Option<&'static VerbosityOverload> {
+    VERBOSITY_OVERLOADS.iter().find(|o| o.name() == name)
+}
+
+/// The RPC method a generated method calls: the base method for an overload, else `name`.
+pub fn rpc_name(name: &str) -> &str { overload(name).map_or(name, |o| o.method) }
+
+/// The JSON literal `argument` is fixed to in the generated method `name`, if any.
+pub fn fixed_value(name: &str, argument: &str) -> Option<&'static str> {
+    overload(name)?.fixed.iter().find(|(arg, _)| *arg == argument).map(|(_, value)| *value)
+}
+
+/// `methods` with the overloads of each method inserted right after it.
+///
+/// Overloads whose fixed arguments are missing from the schema (e.g. in an older Core
+/// version) are skipped. Fails if not exactly one result of a method has a condition
+/// matching the fixed arguments of an overload.
+pub fn with_overloads(methods: &[BtcMethod]) -> Result<Vec<BtcMethod>> {
+    let mut out = Vec::with_capacity(methods.len());
+    for method in methods {
+        out.push(method.clone());
+        for o in VERBOSITY_OVERLOADS.iter().filter(|o| o.method == method.name) {
+            out.extend(overload_method(method, o)?);
+        }
+    }
+    Ok(out)
+}
+
+/// The method generated for overload `o` of `base`, or `None` if `base` lacks one of
+/// the fixed arguments.
+fn overload_method(base: &BtcMethod, o: &VerbosityOverload) -> Result<Option<BtcMethod>> {
+    let mut arguments = base.arguments.clone();
+    for (name, _) in o.fixed {
+        match arguments.iter_mut().find(|a| a.names.first().is_some_and(|n| n == name)) {
+            Some(argument) => argument.required = true,
+            None => return Ok(None),
+        }
+    }
+
+    let mut result = resolve_result(base, o)?;
+    result.condition.clear();
+
+    let fixed =
+        o.fixed.iter().map(|(name, value)| format!("`{name} = {value}`")).collect::<Vec<_>>();
+    Ok(Some(BtcMethod {
+        name: o.name(),
+        description: format!(
+            "Calls `{}` with {}, returning only that response form.\n\n{}",
+            o.method,
+            fixed.join(" and "),
+            base.description
+        ),
+        arguments,
+        results: vec![result],
+        ..base.clone()
+    }))
+}
+
+/// The result of `base` that overload `o` returns, with the fields of the result it
+/// extends merged in.
+fn resolve_result(base: &BtcMethod, o: &VerbosityOverload) -> Result<BtcResult> {
+    let result = select_result(base, o)?;
+    let Some(suffix) = o.extends else {
+        return Ok(result.clone());
+    };
+    let parent = VERBOSITY_OVERLOADS
+        .iter()
+        .find(|p| p.method == o.method && p.suffix == suffix)
+        .ok_or_else(|| anyhow!("`{}` extends unknown overload `{}{suffix}`", o.name(), o.method))?;
+    Ok(merge_fields(resolve_result(base, parent)?, result))
+}
+
+/// The one result of `base` whose condition gives each argument `o` fixes its value.
+fn select_result<'a>(base: &'a BtcMethod, o: &VerbosityOverload) -> Result<&'a BtcResult> {
+    let mut matching = base.results.iter().filter(|r| {
+        o.fixed
+            .iter()
+            .all(|(name, value)| condition_value(&r.condition, name).as_deref() == Some(*value))
+    });
+    match (matching.next(), matching.next()) {
+        (Some(result), None) => Ok(result),
+        (first, _) => Err(anyhow!(
+            "`{}`: {} results of `{}` have a condition matching {:?}",
+            o.name(),
+            if first.is_some() { "several" } else { "no" },
+            base.name,
+            o.fixed
+        )),
+    }
+}
+
+/// The value a result `condition` gives `argument`, e.g. `1` for `verbosity` in both
+/// `for verbosity = 1` and `if verbosity is set to 1`, or `None` if it does not mention it.
+fn condition_value(condition: &str, argument: &str) -> Option<String> {
+    let condition = condition.replace('=', " = ").to_lowercase();
+    let tokens = condition
+        .split_whitespace()
+        .map(|t| t.trim_matches(|c: char| !c.is_alphanumeric() && c != '_' && c != '='))
+        .collect::<Vec<_>>();
+    let start = tokens.iter().position(|t| *t == argument)?;
+    tokens[start..].windows(2).find(|w| w[0] == "=" || w[0] == "to").map(|w| w[1].to_string())
+}
+
+/// `parent`'s fields followed by the ones `child` adds; fields of `child` replace
+/// same-named fields of `parent`, and the `...` elision is dropped.
+fn merge_fields(mut parent: BtcResult, child: &BtcResult) -> BtcResult {
+    let added = child.inner.iter().filter(|f| f.type_ != "elision" && !f.key_name.is_empty());
+    for field in added {
+        match parent.inner.iter_mut().find(|f| f.key_name == field.key_name) {
+            Some(existing) => *existing = field.clone(),
+            None => parent.inner.push(field.clone()),
+        }
+    }
+    parent.inner.retain(|f| f.type_ != "elision");
+    BtcResult { inner: parent.inner, ..child.clone() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bundled_methods() -> Vec<BtcMethod> {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../bitcoin-core-api.json");
+        crate::load_api_methods_from_file(path).unwrap()
+    }
+
+    #[test]
+    fn test_overloads_follow_their_method() {
+        let methods = with_overloads(&bundled_methods()).unwrap();
+        let names = methods
+            .iter()
+            .map(|m| m.name.as_str())
+            .skip_while(|name| *name != "getblock")
+            .take(4)
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["getblock", "getblock_hex", "getblock_verbose", "getblock_verbose2"]);
+        for o in VERBOSITY_OVERLOADS {
+            assert!(methods.iter().any(|m| m.name == o.name()), "{} missing", o.name());
+        }
+    }
+
+    #[test]
+    fn test_overload_fixes_arguments_and_selects_one_result() {
+        let methods = with_overloads(&bundled_methods()).unwrap();
+        let hex = methods.iter().find(|m| m.name == "getblock_hex").unwrap();
+
+        assert_eq!(hex.results.len(), 1);
+        assert_eq!(hex.results[0].type_, "hex");
+        assert!(hex.results[0].condition.is_empty());
+        assert!(hex.arguments.iter().all(|a| a.required));
+        assert!(hex.description.starts_with("Calls `getblock` with `verbosity = 0`"));
+
+        assert_eq!(rpc_name("getblock_hex"), "getblock");
+        assert_eq!(rpc_name("getblock"), "getblock");
+        assert_eq!(fixed_value("getblock_hex", "verbosity"), Some("0"));
+        assert_eq!(fixed_value("getblock_hex", "blockhash"), None);
+        assert_eq!(fixed_value("getblock", "verbosity"), None);
+    }
+
+    #[test]
+    fn test_extending_overload_merges_parent_fields() {
+        let methods = with_overloads(&bundled_methods()).unwrap();
+        let verbose = methods.iter().find(|m| m.name == "getblock_verbose").unwrap();
+        let verbose2 = methods.iter().find(|m| m.name == "getblock_verbose2").unwrap();
+        let keys = |m: &BtcMethod| {
+            m.results[0].inner.iter().map(|f| f.key_name.clone()).collect::<Vec<_>>()
+        };
+
+        // Same fields as verbosity 1, in the same order, with `tx` replaced by the
+        // verbosity 2 form
+        assert_eq!(keys(verbose2), keys(verbose));
+        let tx = |m: &BtcMethod| {
+            m.results[0].inner.iter().find(|f| f.key_name == "tx").map(|f| f.description.clone())
+        };
+        assert_ne!(tx(verbose2), tx(verbose));
+        assert!(verbose2.results[0].inner.iter().all(|f| f.type_ != "elision"));
+
+        // Fields only verbosity 2 has are appended
+        let raw2 = methods.iter().find(|m| m.name == "getrawtransaction_verbose2").unwrap();
+        assert_eq!(keys(raw2).last().unwrap(), "fee");
+    }
+    #[test]
+    fn test_results_are_selected_by_condition() {
+        assert_eq!(condition_value("for verbosity = 1", "verbosity").as_deref(), Some("1"));
+        assert_eq!(condition_value("for verbose=false", "verbose").as_deref(), Some("false"));
+        assert_eq!(
+            condition_value("if verbosity is not set or set to 0", "verbosity").as_deref(),
+            Some("0")
+        );
+        let sequence = "for verbose = false and mempool_sequence = true";
+        assert_eq!(condition_value(sequence, "mempool_sequence").as_deref(), Some("true"));
+        assert_eq!(condition_value("for verbose = true", "verbosity"), None);
+
+        // `getblockheader` lists the `verbose = true` form first
+        let methods = with_overloads(&bundled_methods()).unwrap();
+        let header_hex = methods.iter().find(|m| m.name == "getblockheader_hex").unwrap();
+        assert_eq!(header_hex.results[0].type_, "hex");
+        let sequence = methods.iter().find(|m| m.name == "getrawmempool_sequence").unwrap();
+        assert_eq!(sequence.results[0].type_, "object");
+    }
+
+    #[test]
+    fn test_unmatched_conditions_fail_generation() {
+        let getblock = bundled_methods().into_iter().find(|m| m.name == "getblock").unwrap();
+
+        let mut missing = getblock.clone();
+        missing.results.retain(|r| !r.condition.contains("= 2"));
+        let err = with_overloads(&[missing]).unwrap_err();
+        assert!(err.to_string().contains("no results of `getblock`"), "{err}");
+
+        let mut ambiguous = getblock;
+        ambiguous.results[3].condition = "for verbosity = 0".to_string();
+        let err = with_overloads(&[ambiguous]).unwrap_err();
+        assert!(err.to_string().contains("several results of `getblock`"), "{err}");
+    }
+}
@@ -11,33 +11,173 @@ pub mod generators;
 pub mod versioning;
 
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::OnceLock;
 
 use anyhow::Result;
-use bitcoin_rpc_types::BtcMethod;
+use bitcoin_rpc_types::{BtcArgument, BtcMethod};
+use serde::Deserialize;
 use serde_json::Value;
 
-use crate::generators::{doc_comment, response_type};
+use crate::generators::option_object::OptionObject;
+use crate::generators::{doc_comment, response_type, verbosity};
 use crate::versioning::Version;
 
+/// Errors returned while loading API methods from a schema file.
+#[derive(Debug, thiserror::Error)]
+pub enum ParseApiError {
+    /// The schema file could not be read.
+    #[error("failed to read API schema: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The schema file is not valid JSON.
+    #[error("invalid JSON in API schema: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// The schema has no top-level `methods` field.
+    #[error("Missing 'methods' field in JSON")]
+    MissingMethods,
+
+    /// The `methods` field is not an object keyed by method name.
+    #[error("'methods' field must be an object keyed by method name")]
+    MethodsNotObject,
+
+    /// A single method entry could not be parsed.
+    #[error("failed to parse method '{name}': {source}")]
+    BadMethod {
+        /// Name of the offending method.
+        name: String,
+        /// Underlying deserialization error.
+        #[source]
+        source: serde_json::Error,
+    },
+}
+
 /// Load API methods from a JSON file using the new schema system
-pub fn load_api_methods_from_file<P: AsRef<Path>>(path: P) -> Result<Vec<BtcMethod>> {
-    let raw = std::fs::read_to_string(&path)?;
-    let v: Value = serde_json::from_str(&raw)?;
+pub fn load_api_methods_from_file<P: AsRef<Path>>(
+    path: P,
+) -> std::result::Result<Vec<BtcMethod>, ParseApiError> {
+    load_api_methods_from_str(&std::fs::read_to_string(&path)?)
+}
 
-    let methods_value =
-        v.get("methods").ok_or_else(|| anyhow::anyhow!("Missing 'methods' field in JSON"))?;
+/// Load API methods from a JSON schema held in memory, e.g. one embedded with `include_str!`.
+pub fn load_api_methods_from_str(json: &str) -> std::result::Result<Vec<BtcMethod>, ParseApiError> {
+    let v: Value = serde_json::from_str(json)?;
 
-    let methods_map: std::collections::HashMap<String, BtcMethod> =
-        serde_json::from_value(methods_value.clone())?;
+    let methods_value = v.get("methods").ok_or(ParseApiError::MissingMethods)?;
+    let methods_obj = methods_value.as_object().ok_or(ParseApiError::MethodsNotObject)?;
+
+    let mut methods = methods_obj
+        .iter()
+        .map(|(name, method)| {
+            BtcMethod::deserialize(method)
+                .map_err(|source| ParseApiError::BadMethod { name: name.clone(), source })
+        })
+        .collect::<std::result::Result<Vec<_>, _>>()?;
 
     // Sort methods by name to ensure consistent ordering across runs
-    let mut methods: Vec<BtcMethod> = methods_map.into_values().collect();
     methods.sort_by(|a, b| a.name.cmp(&b.name));
+    SchemaNormalizer::normalize_methods(&mut methods);
     Ok(methods)
 }
 
+/// Load the `object` arguments with documented sub-fields from a JSON schema file.
+///
+/// The nested `inner` schema of an argument is not part of `BtcArgument`, so it is read
+/// here alongside [`load_api_methods_from_file`]. Objects are ordered by method, then by
+/// argument position.
+pub fn load_option_objects_from_file<P: AsRef<Path>>(
+    path: P,
+) -> std::result::Result<Vec<OptionObject>, ParseApiError> {
+    load_option_objects_from_str(&std::fs::read_to_string(&path)?)
+}
+
+/// Load the documented `object` arguments from a JSON schema held in memory (see
+/// [`load_option_objects_from_file`]).
+pub fn load_option_objects_from_str(
+    json: &str,
+) -> std::result::Result<Vec<OptionObject>, ParseApiError> {
+    let v: Value = serde_json::from_str(json)?;
+
+    let methods_value = v.get("methods").ok_or(ParseApiError::MissingMethods)?;
+    let methods_obj = methods_value.as_object().ok_or(ParseApiError::MethodsNotObject)?;
+
+    let mut objects = Vec::new();
+    let mut unknown_types = std::collections::BTreeSet::new();
+    for (name, method) in methods_obj {
+        let arguments = method.get("arguments").and_then(Value::as_array);
+        for argument in arguments.into_iter().flatten() {
+            let (Some("object"), Some(inner)) = (
+                argument.get("type").and_then(Value::as_str),
+                argument.get("inner").and_then(Value::as_array),
+            ) else {
+                continue;
+            };
+            let Some(arg_name) = argument.pointer("/names/0").and_then(Value::as_str) else {
+                continue;
+            };
+            let mut fields = inner
+                .iter()
+                .map(BtcArgument::deserialize)
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .map_err(|source| ParseApiError::BadMethod { name: name.clone(), source })?;
+            SchemaNormalizer::normalize_arguments(&mut fields, &mut unknown_types);
+            if !fields.is_empty() {
+                objects.push(OptionObject {
+                    method: name.clone(),
+                    argument: arg_name.to_string(),
+                    fields,
+                });
+            }
+        }
+    }
+
+    schema_normalizer::warn_unknown(unknown_types);
+
+    // Stable sort keeps the schema's argument order within a method
+    objects.sort_by(|a, b| a.method.cmp(&b.method));
+    Ok(objects)
+}
+
+/// Load the category of every method from a JSON schema held in memory.
+///
+/// Methods without a `category` field are categorized by name with
+/// [`categories::category_of`].
+pub fn load_method_categories_from_str(
+    json: &str,
+) -> std::result::Result<MethodCategories, ParseApiError> {
+    let v: Value = serde_json::from_str(json)?;
+
+    let methods_value = v.get("methods").ok_or(ParseApiError::MissingMethods)?;
+    let methods_obj = methods_value.as_object().ok_or(ParseApiError::MethodsNotObject)?;
+
+    Ok(methods_obj
+        .iter()
+        .map(|(name, method)| {
+            let category = method
+                .get("category")
+                .and_then(Value::as_str)
+                .unwrap_or_else(|| categories::category_of(name));
+            (name.clone(), category.to_string())
+        })
+        .collect())
+}
+
+/// Sub-crate: **`categories`**
+///
+/// Groups methods into Core's RPC categories, each gated behind a feature of the
+/// generated crate.
+pub mod categories;
+pub use categories::MethodCategories;
+
+/// Sub-crate: **`components`**
+///
+/// Assigns each method to the Bitcoin Core component (node, wallet, ...) serving it, so
+/// the generated transport can route calls per component.
+pub mod components;
+pub use components::RpcComponent;
+
 /// Sub-crate: **`namespace_scaffolder`**
 ///
 /// Writes `mod.rs` scaffolding for generated modules.
@@ -53,6 +193,18 @@ pub fn load_api_methods_from_file<P: AsRef<Path>>(path: P) -> Result<Vec<BtcMeth
 /// ```
 pub mod namespace_scaffolder;
 
+/// Sub-crate: **`schema_normalizer`**
+///
+/// Rewrites schema type strings into one canonical vocabulary when a schema is loaded.
+pub mod schema_normalizer;
+pub use schema_normalizer::SchemaNormalizer;
+
+/// Sub-crate: **`schema_validator`**
+///
+/// Checks a parsed schema against the invariants the generators rely on.
+pub mod schema_validator;
+pub use schema_validator::{SchemaValidator, ValidateError};
+
 /// Sub-crate: **`transport_core_generator`**
 ///
 /// Generates the core transport types: Transport trait, TransportError enum,
@@ -60,6 +212,12 @@ pub mod namespace_scaffolder;
 pub mod transport_core_generator;
 pub use transport_core_generator::TransportCoreGenerator;
 
+/// Sub-crate: **`type_registry`**
+///
+/// Maps RPC types to Rust types: the built-in rules from `bitcoin_rpc_conversions`,
+/// extensible with user rules.
+pub mod type_registry;
+
 /// Sub-crate: **`utils`**
 ///
 /// Utility functions for code generation.
@@ -79,24 +237,60 @@ pub trait CodeGenerator {
     fn validate(&self, _methods: &[BtcMethod]) -> Result<()> { Ok(()) }
 }
 
-#[allow(unused)]
-fn format_with_rustfmt(path: &Path) {
-    if let Ok(status) = Command::new("rustfmt").arg("--edition=2021").arg(path).status() {
-        if !status.success() {
-            eprintln!("[warn] rustfmt failed on {path:?}");
+/// Set this environment variable to `1` or `true` to skip formatting generated files with
+/// `rustfmt`; any other value (e.g. `0` or an empty one) leaves formatting on.
+pub const NO_RUSTFMT_ENV: &str = "BITCOIN_RPC_CODEGEN_NO_RUSTFMT";
+
+/// Whether `value` of [`NO_RUSTFMT_ENV`] asks to skip formatting
+fn rustfmt_disabled_by(value: &str) -> bool {
+    let value = value.trim();
+    value == "1" || value.eq_ignore_ascii_case("true")
+}
+
+/// Whether generated files should be run through `rustfmt`.
+///
+/// Availability is probed once per process; if `rustfmt` is missing, formatting is
+/// skipped with a single log line instead of a warning per file.
+fn rustfmt_enabled() -> bool {
+    static AVAILABLE: OnceLock<bool> = OnceLock::new();
+    if std::env::var(NO_RUSTFMT_ENV).is_ok_and(|value| rustfmt_disabled_by(&value)) {
+        return false;
+    }
+    *AVAILABLE.get_or_init(|| {
+        let available = Command::new("rustfmt")
+            .arg("--version")
+            .output()
+            .is_ok_and(|output| output.status.success());
+        if !available {
+            tracing::info!("rustfmt not found; generated files will not be formatted");
         }
-    } else {
-        eprintln!("[warn] rustfmt not found or failed to run for {path:?}");
+        available
+    })
+}
+
+/// Format `paths` with a single `rustfmt` invocation.
+fn format_with_rustfmt(paths: &[PathBuf]) {
+    if paths.is_empty() || !rustfmt_enabled() {
+        return;
+    }
+    match Command::new("rustfmt").arg("--edition=2021").args(paths).status() {
+        Ok(status) if status.success() => {}
+        Ok(status) => tracing::warn!("rustfmt exited with {status} on {} files", paths.len()),
+        Err(e) => tracing::warn!("failed to run rustfmt: {e}"),
     }
 }
 
 /// Persist a list of generated source files to disk under the given output directory,
 /// creating any necessary subdirectories and appending `.rs` if missing.
+///
+/// The written files are formatted with one `rustfmt` run, unless `rustfmt` is missing or
+/// [`NO_RUSTFMT_ENV`] is set to `1` or `true`.
 pub fn write_generated<P: AsRef<Path>>(
     out_dir: P,
     files: &[(String, String)],
 ) -> std::io::Result<()> {
     fs::create_dir_all(&out_dir)?;
+    let mut paths = Vec::with_capacity(files.len());
     for (name, src) in files {
         let path = if name.ends_with(".rs") {
             out_dir.as_ref().join(name)
@@ -107,8 +301,9 @@ pub fn write_generated<P: AsRef<Path>>(
             fs::create_dir_all(parent)?;
         }
         fs::write(&path, src.as_bytes())?;
-        format_with_rustfmt(&path);
+        paths.push(path);
     }
+    format_with_rustfmt(&paths);
     Ok(())
 }
 
@@ -116,20 +311,51 @@ pub fn write_generated<P: AsRef<Path>>(
 ///
 /// `TransportCodeGenerator` implements the `CodeGenerator` trait to produce, for each
 /// `BtcMethod`, a self-contained Rust source file containing:
-/// 1. An `async fn` that accepts a `&dyn TransportTrait` and JSON-serializable parameters.
+/// 1. An `async fn` that accepts a `&dyn TransportTrait` and typed parameters
+///    (`Option<T>` for optional arguments).
 /// 2. Logic to serialize those parameters into a `Vec<serde_json::Value>`.
 /// 3. A call to `transport.send_request(method_name, &params).await`.
 /// 4. Deserialization of the raw response into a typed `Response` struct (or raw `Value`).
+/// 5. For methods with many arguments, a `<Method>Builder` that takes the required
+///    arguments up front and sets the optional ones by name.
 pub struct TransportCodeGenerator {
     version: Version,
+    option_objects: Vec<OptionObject>,
+    builder_threshold: usize,
 }
 
+/// Methods with more arguments than this get a builder by default
+/// (see [`TransportCodeGenerator::with_builder_threshold`]).
+pub const DEFAULT_BUILDER_THRESHOLD: usize = 5;
+
 impl TransportCodeGenerator {
     /// Create a new TransportCodeGenerator with the specified Bitcoin Core version
-    pub fn new(version: Version) -> Self { Self { version } }
+    pub fn new(version: Version) -> Self {
+        Self { version, option_objects: Vec::new(), builder_threshold: DEFAULT_BUILDER_THRESHOLD }
+    }
+
+    /// Document the sub-fields of these object arguments on the wrappers taking them
+    /// (see [`load_option_objects_from_file`]).
+    pub fn with_option_objects(mut self, objects: Vec<OptionObject>) -> Self {
+        self.option_objects = objects;
+        self
+    }
+
+    /// Emit a builder next to the wrappers of methods taking more than `threshold`
+    /// arguments, at least one of them optional, e.g.
+    /// `SendtoaddressBuilder::new(address, amount).comment("x".into()).send(&transport)`.
+    pub fn with_builder_threshold(mut self, threshold: usize) -> Self {
+        self.builder_threshold = threshold;
+        self
+    }
 
     /// Generate conditional imports based on what is actually needed
-    fn generate_imports(has_parameters: bool, has_structured_response: bool) -> String {
+    fn generate_imports(
+        has_parameters: bool,
+        has_structured_response: bool,
+        argument_imports: &[&str],
+        response_helpers: &[&str],
+    ) -> String {
         let mut imports = vec![];
         imports.push("use serde_json::Value;".to_string());
 
@@ -137,60 +363,204 @@ impl TransportCodeGenerator {
             imports.push("use serde_json::json;".to_string());
         }
 
+        for path in argument_imports {
+            imports.push(format!("use {path};"));
+        }
+
         if has_structured_response {
             imports.push("use serde::{Deserialize, Serialize};".to_string());
         }
 
+        if !response_helpers.is_empty() {
+            imports.push(format!("use crate::responses::{{{}}};", response_helpers.join(", ")));
+        }
+
         imports.push("use crate::transport::{TransportTrait, TransportError};".to_string());
 
         imports.join("\n")
     }
+
+    /// Statements that build the positional `params` vector for a wrapper body.
+    ///
+    /// Optional arguments after the last required one are only sent up to the
+    /// last one that is `Some`, so bitcoind sees the shortest valid positional
+    /// list. Optional arguments before a required one keep their slot as `null`.
+    ///
+    /// Arguments an overload of `method` fixes (see [`generators::verbosity`]) are sent
+    /// as their literal value.
+    fn params_statements(method: &str, args: &[BtcArgument]) -> String {
+        let ident = |a: &BtcArgument| {
+            if let Some(value) = verbosity::fixed_value(method, &a.names[0]) {
+                value.to_string()
+            } else if a.required {
+                let (ty, _) = type_registry::map_argument_type(a);
+                type_registry::parameter_value(ty, &utils::sanitize_ident(&a.names[0]))
+            } else {
+                utils::sanitize_ident(&a.names[0])
+            }
+        };
+
+        let split = args.iter().rposition(|a| a.required).map_or(0, |i| i + 1);
+        let (leading, trailing) = args.split_at(split);
+
+        let leading = if leading.is_empty() {
+            "Vec::<Value>::new()".to_string()
+        } else {
+            let elems = leading
+                .iter()
+                .map(|a| format!("json!({})", ident(a)))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("vec![{elems}]")
+        };
+
+        if trailing.is_empty() {
+            return format!("    let params = {leading};");
+        }
+
+        let optional = trailing
+            .iter()
+            .map(|a| format!("{}.map(|v| json!(v))", ident(a)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!(
+            "    let mut params = {leading};\n    \
+             let optional = [{optional}];\n    \
+             let present = optional.iter().rposition(Option::is_some).map_or(0, |i| i + 1);\n    \
+             params.extend(optional.into_iter().take(present).map(|v| v.unwrap_or(Value::Null)));"
+        )
+    }
+
+    /// A `<Method>Builder` calling the wrapper `fn_name` with the non-fixed `args`.
+    ///
+    /// Required arguments go to `new`; each optional one gets a setter and is `None`
+    /// until set, so the wrapper trims the unset ones from the end of `params`. Setters
+    /// of deprecated arguments are `#[deprecated]`, and `send` allows calling a
+    /// `deprecated` wrapper.
+    fn builder_struct(
+        rpc: &str,
+        fn_name: &str,
+        struct_name: &str,
+        args: &[&BtcArgument],
+        ok_ty: &str,
+        deprecated: bool,
+    ) -> String {
+        use utils::sanitize_ident;
+
+        let ident = |a: &BtcArgument| sanitize_ident(&a.names[0]);
+        let (required, optional): (Vec<&BtcArgument>, Vec<_>) =
+            args.iter().copied().partition(|a| a.required);
+
+        let fields = args
+            .iter()
+            .map(|a| format!("    {}: {},\n", ident(a), Self::argument_type(a)))
+            .collect::<String>();
+        let new_args = required
+            .iter()
+            .map(|a| format!("{}: {}", ident(a), Self::argument_type(a)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let new_fields = args
+            .iter()
+            .map(|a| if a.required { ident(a) } else { format!("{}: None", ident(a)) })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let setters = optional
+            .iter()
+            .map(|a| {
+                let name = ident(a);
+                let (ty, _) = type_registry::map_argument_type(a);
+                format!(
+                    "    /// Set the optional `{}` argument.\n\
+                     {}    pub fn {name}(mut self, {name}: {ty}) -> Self {{\n        \
+                     self.{name} = Some({name});\n        \
+                     self\n    }}\n\n",
+                    a.names[0],
+                    doc_comment::deprecated_attribute(&a.description, "    "),
+                )
+            })
+            .collect::<String>();
+        let call_args = args.iter().map(|a| format!(", self.{}", ident(a))).collect::<String>();
+        let clippy_allow =
+            if required.len() > 7 { "    #[allow(clippy::too_many_arguments)]\n" } else { "" };
+        let deprecated_allow = if deprecated { "    #[allow(deprecated)]\n" } else { "" };
+
+        format!(
+            r#"
+/// Builder for a `{rpc}` call: pass the required arguments to [`{struct_name}::new`] and
+/// set the optional ones by name before [`{struct_name}::send`].
+#[derive(Debug, Clone)]
+pub struct {struct_name} {{
+{fields}}}
+
+impl {struct_name} {{
+    /// Start a `{rpc}` call with its required arguments.
+{clippy_allow}    pub fn new({new_args}) -> Self {{
+        Self {{ {new_fields} }}
+    }}
+
+{setters}    /// Send the call through `transport`, see [`{fn_name}`].
+{deprecated_allow}    pub async fn send(self, transport: &dyn TransportTrait) -> Result<{ok_ty}, TransportError> {{
+        {fn_name}(transport{call_args}).await
+    }}
+}}
+"#
+        )
+    }
+
+    /// Rust type for an argument in a generated wrapper signature.
+    ///
+    /// Uses the active `TypeRegistry` mapping, so only arguments it cannot categorize
+    /// stay as `serde_json::Value`. Optional arguments are wrapped in `Option`.
+    fn argument_type(arg: &BtcArgument) -> String {
+        let (base_ty, _) = type_registry::map_argument_type(arg);
+        if arg.required {
+            base_ty.to_string()
+        } else {
+            format!("Option<{base_ty}>")
+        }
+    }
+
+    /// Rust type for an argument's parameter in a wrapper signature: like
+    /// [`Self::argument_type`], but required arguments of the
+    /// [`type_registry::INTO_ARGUMENT_TYPES`] take anything converting into them.
+    fn parameter_type(arg: &BtcArgument) -> String {
+        match type_registry::map_argument_type(arg).0 {
+            base_ty if arg.required => type_registry::parameter_type(base_ty),
+            _ => Self::argument_type(arg),
+        }
+    }
 }
 
 impl CodeGenerator for TransportCodeGenerator {
     fn generate(&self, methods: &[BtcMethod]) -> Vec<(String, String)> {
-        use utils::capitalize;
+        use utils::{capitalize, sanitize_ident};
 
         methods
             .iter()
             .map(|m| {
+                let rpc = verbosity::rpc_name(&m.name);
+                let is_fixed =
+                    |a: &BtcArgument| verbosity::fixed_value(&m.name, &a.names[0]).is_some();
+
                 /* ---------- fn signature ---------- */
                 let fn_args = std::iter::once("transport: &dyn TransportTrait".into())
-                    .chain(m.arguments.iter().map(|a| {
-                        let name = if a.names[0] == "type" {
-                            format!("r#{}", a.names[0])
-                        } else {
-                            a.names[0].clone()
-                        };
-                        format!("{name}: serde_json::Value")
+                    .chain(m.arguments.iter().filter(|a| !is_fixed(a)).map(|a| {
+                        format!("{}: {}", sanitize_ident(&a.names[0]), Self::parameter_type(a))
                     }))
                     .collect::<Vec<_>>()
                     .join(", ");
 
                 /* ---------- params vec ---------- */
-                let params_vec = if m.arguments.is_empty() {
-                    "Vec::<Value>::new()".into()
-                } else {
-                    let elems = m
-                        .arguments
-                        .iter()
-                        .map(|a| {
-                            let name = if a.names[0] == "type" {
-                                format!("r#{}", a.names[0])
-                            } else {
-                                a.names[0].clone()
-                            };
-                            format!("json!({name})")
-                        })
-                        .collect::<Vec<_>>()
-                        .join(", ");
-                    format!("vec![{elems}]")
-                };
+                let params_vec = Self::params_statements(&m.name, &m.arguments);
 
                 /* ---------- docs + types ---------- */
-                let docs_md = doc_comment::generate_example_docs(m, &self.version.as_doc_version())
-                    .trim_end()
-                    .to_string();
+                let header = doc_comment::generated_file_header(&self.version.as_doc_version());
+                let mut description = doc_comment::format_description(&m.description);
+                if !description.is_empty() {
+                    description.push_str("///\n");
+                }
+                let examples = doc_comment::generate_example_docs(m);
                 let response_struct =
                     response_type::build_return_type(m).unwrap_or_default().unwrap_or_default();
                 let ok_ty = if response_struct.is_empty() {
@@ -202,7 +572,36 @@ impl CodeGenerator for TransportCodeGenerator {
                 /* ---------- source file ---------- */
                 let has_parameters = !m.arguments.is_empty();
                 let has_structured_response = !response_struct.is_empty();
-                let imports = Self::generate_imports(has_parameters, has_structured_response);
+                let argument_imports = type_registry::named_argument_imports(
+                    m.arguments.iter().map(|a| type_registry::map_argument_type(a).0),
+                );
+                let imports = Self::generate_imports(
+                    has_parameters,
+                    has_structured_response,
+                    &argument_imports,
+                    &[
+                        response_type::AMOUNT_FROM_BTC_FLOAT,
+                        response_type::OPTION_AMOUNT_FROM_BTC_FLOAT,
+                        response_type::SIGNED_AMOUNT_FROM_BTC_FLOAT,
+                        response_type::OPTION_SIGNED_AMOUNT_FROM_BTC_FLOAT,
+                    ]
+                    .into_iter()
+                    .filter(|helper| response_struct.contains(&format!("\"{helper}\"")))
+                    .chain(
+                        response_struct
+                            .contains(response_type::CONSENSUS_DECODE_ERROR)
+                            .then_some(response_type::CONSENSUS_DECODE_ERROR),
+                    )
+                    .collect::<Vec<_>>(),
+                );
+
+                let option_docs = self
+                    .option_objects
+                    .iter()
+                    .filter(|o| o.method == rpc)
+                    .map(OptionObject::field_docs)
+                    .collect::<String>();
+                let error_docs = generators::rpc_error::error_docs(m);
 
                 // Add clippy allow for too many arguments if needed
                 let clippy_allow = if m.arguments.len() > 7 {
@@ -211,33 +610,45 @@ impl CodeGenerator for TransportCodeGenerator {
                     ""
                 };
 
+                let deprecated = doc_comment::deprecated_attribute(&m.description, "");
+
+                let fn_name = sanitize_ident(&m.name);
+                let args = m.arguments.iter().filter(|a| !is_fixed(a)).collect::<Vec<_>>();
+                let builder =
+                    if args.len() > self.builder_threshold && args.iter().any(|a| !a.required) {
+                        let struct_name = format!("{}Builder", capitalize(&m.name));
+                        let deprecated = !deprecated.is_empty();
+                        Self::builder_struct(rpc, &fn_name, &struct_name, &args, &ok_ty, deprecated)
+                    } else {
+                        String::new()
+                    };
+
                 let src = format!(
-                    r#"{docs}
+                    r#"{header}
 #[allow(unused_imports)]
 {imports}
 {resp_struct}
 
-/// Calls the `{rpc}` RPC method.
+{description}/// Calls the `{rpc}` RPC method.
 ///
 /// Generated transport wrapper for JSON-RPC.
-{clippy_allow}pub async fn {fn_name}({fn_args}) -> Result<{ok_ty}, TransportError> {{
-    let params = {params_vec};
+{option_docs}{error_docs}///
+{examples}
+{deprecated}{clippy_allow}pub async fn {fn_name}({fn_args}) -> Result<{ok_ty}, TransportError> {{
+{params_vec}
     let raw = transport.send_request("{rpc}", &params).await?;
     {handler}
 }}
-"#,
-                    docs = docs_md,
+{builder}"#,
                     imports = imports,
                     resp_struct = response_struct,
-                    rpc = m.name,
-                    fn_name = &m.name,
                     fn_args = fn_args,
                     ok_ty = ok_ty,
                     params_vec = params_vec,
                     handler = if response_struct.is_empty() {
                         "Ok(raw)".into()
                     } else {
-                        format!("Ok(serde_json::from_value::<{ok_ty}>(raw)?)")
+                        format!("crate::transport::decode_result::<{ok_ty}>(\"{}\", &raw)", m.name)
                     }
                 );
 
@@ -247,15 +658,545 @@ impl CodeGenerator for TransportCodeGenerator {
     }
 }
 
-// TODO(multiprocess): Introduce an `RpcComponent` abstraction to formally distinguish between
-// independently-addressable RPC components like `node`, `wallet`, `index`, and `gui`.
-//
-// This will support:
-// - routing method calls to different endpoints (e.g., node.sock vs wallet.sock)
-// - preventing runtime errors by associating methods with their component
-// - future `CombinedClient` that multiplexes requests across components
-//
-// This abstraction will become essential as Bitcoin Core moves toward
-// separate processes with their own RPC servers.
-//
-// Start by creating a `components.rs` module defining `RpcComponent` and a registry of methods.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_schema(contents: &str) -> tempfile::NamedTempFile {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        std::io::Write::write_all(&mut file, contents.as_bytes()).unwrap();
+        file
+    }
+
+    const GETBLOCKCOUNT: &str = r#""getblockcount": {"name": "getblockcount", "description": "", "category": "blockchain", "examples": "", "argument_names": [], "arguments": [], "results": []}"#;
+
+    #[test]
+    fn test_load_api_methods_reports_failing_method() {
+        let schema = format!(r#"{{"methods": {{{GETBLOCKCOUNT}}}}}"#);
+        let valid = write_schema(&schema);
+        let methods = load_api_methods_from_file(valid.path()).unwrap();
+        assert_eq!(methods.len(), 1);
+        assert_eq!(methods[0].name, "getblockcount");
+        assert_eq!(load_api_methods_from_str(&schema).unwrap()[0].name, "getblockcount");
+
+        let file = write_schema(&format!(
+            r#"{{"methods": {{{GETBLOCKCOUNT}, "zzbroken": "not an object"}}}}"#
+        ));
+
+        match load_api_methods_from_file(file.path()).unwrap_err() {
+            ParseApiError::BadMethod { name, .. } => assert_eq!(name, "zzbroken"),
+            other => panic!("expected BadMethod, got {other:?}"),
+        }
+    }
+
+    fn argument(name: &str, required: bool) -> String {
+        format!(
+            r#"{{"names": ["{name}"], "description": "", "type": "string", "required": {required}}}"#
+        )
+    }
+
+    #[test]
+    fn test_load_api_methods_preserves_argument_order() {
+        // Declaration order deliberately differs from both alphabetical order and
+        // required-first order, so any sort or map round-trip shows up here.
+        let args = [("zeta", true), ("alpha", false), ("mid", true), ("beta", false)]
+            .iter()
+            .map(|(name, required)| argument(name, *required))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mixed = format!(
+            r#""mixed": {{"name": "mixed", "description": "", "category": "util", "examples": "", "argument_names": [], "arguments": [{args}], "results": []}}"#
+        );
+        let expected = ["zeta", "alpha", "mid", "beta"];
+
+        // The same methods listed in either key order must load identically.
+        for schema in [
+            format!(r#"{{"methods": {{{mixed}, {GETBLOCKCOUNT}}}}}"#),
+            format!(r#"{{"methods": {{{GETBLOCKCOUNT}, {mixed}}}}}"#),
+        ] {
+            let file = write_schema(&schema);
+            let methods = load_api_methods_from_file(file.path()).unwrap();
+            let names = methods.iter().map(|m| m.name.as_str()).collect::<Vec<_>>();
+            assert_eq!(names, ["getblockcount", "mixed"]);
+
+            let arg_names =
+                methods[1].arguments.iter().map(|a| a.names[0].as_str()).collect::<Vec<_>>();
+            assert_eq!(arg_names, expected);
+
+            let files = TransportCodeGenerator::new(Version::from_string("v30").unwrap())
+                .generate(&methods[1..]);
+            let src = &files[0].1;
+            assert!(src.contains("vec![json!(zeta), json!(alpha), json!(mid)]"), "{src}");
+            assert!(src.contains("let optional = [beta.map(|v| json!(v))];"), "{src}");
+        }
+    }
+
+    #[test]
+    fn test_nested_results_survive_load_and_generate() {
+        let script = r#"{"type": "object", "key_name": "scriptPubKey", "description": "", "optional": false, "inner": [
+            {"type": "string", "key_name": "asm", "description": "", "optional": false},
+            {"type": "string", "key_name": "address", "description": "", "optional": true}
+        ]}"#;
+        let file = write_schema(&format!(
+            r#"{{"methods": {{"gettxout": {{"name": "gettxout", "description": "", "category": "blockchain", "examples": "", "argument_names": [], "arguments": [], "results": [
+                {{"type": "object", "description": "", "inner": [{{"type": "number", "key_name": "confirmations", "description": "", "optional": false}}, {script}]}}
+            ]}}}}}}"#
+        ));
+        let methods = load_api_methods_from_file(file.path()).unwrap();
+        let script = &methods[0].results[0].inner[1];
+        assert_eq!(script.key_name, "scriptPubKey");
+        assert_eq!(script.inner.len(), 2);
+        assert!(!script.inner[1].required());
+
+        let files = generators::ResponseTypeCodeGenerator::new("v30").generate(&methods);
+        let src = &files[0].1;
+        assert!(src.contains("pub script_pubkey: GettxoutResponseScriptPubKey,"), "{src}");
+        assert!(src.contains("pub struct GettxoutResponseScriptPubKey {"), "{src}");
+        assert!(src.contains("pub address: Option<String>,"), "{src}");
+    }
+
+    #[test]
+    fn test_transport_wrappers_name_the_method_failing_to_decode() {
+        let file = write_schema(
+            r#"{"methods": {"getblock": {"name": "getblock", "description": "", "category": "blockchain", "examples": "", "argument_names": [], "arguments": [], "results": [
+                {"type": "object", "description": "", "inner": [{"type": "number", "key_name": "nTx", "description": "", "optional": false}]}
+            ]}}}"#,
+        );
+        let methods = load_api_methods_from_file(file.path()).unwrap();
+
+        let files =
+            TransportCodeGenerator::new(Version::from_string("v30").unwrap()).generate(&methods);
+        let src = &files[0].1;
+        assert!(
+            src.contains("crate::transport::decode_result::<GetblockResponse>(\"getblock\", &raw)"),
+            "{src}"
+        );
+
+        let core = &TransportCoreGenerator.generate(&methods)[0].1;
+        assert!(core.contains("ResponseDecode { method: String, message: String }"), "{core}");
+        // Nested mismatches name the path of the field
+        assert!(core.contains("serde_path_to_error::deserialize(result)"), "{core}");
+        assert!(core.contains("decode_result(method, &result)"), "{core}");
+    }
+
+    #[test]
+    fn test_no_rustfmt_env_needs_a_true_value() {
+        for value in ["1", "true", "TRUE", " 1\n"] {
+            assert!(rustfmt_disabled_by(value), "{value:?}");
+        }
+        for value in ["", "0", "false", "no"] {
+            assert!(!rustfmt_disabled_by(value), "{value:?}");
+        }
+    }
+
+    #[test]
+    fn test_default_transport_timeout_keeps_the_client() {
+        let core = &TransportCoreGenerator.generate(&[])[0].1;
+
+        assert!(!core.contains("reqwest::Client::builder()"), "{core}");
+        assert!(core.contains("self.timeout = Some(timeout);"), "{core}");
+        // Per-method timeouts win over the transport-wide one, which batches use too
+        assert!(core.contains(".copied()\n            .or(self.timeout);"), "{core}");
+        assert!(core.contains("let timeout = self.timeout;"), "{core}");
+    }
+
+    #[test]
+    fn test_default_transport_retries_only_connection_errors() {
+        let core = &TransportCoreGenerator.generate(&[])[0].1;
+
+        assert!(
+            core.contains("Err(TransportError::Connect(e)) if retries < self.max_retries"),
+            "{core}"
+        );
+        assert!(!core.contains("Err(TransportError::Http(e)) if retries"), "{core}");
+        assert!(core.contains("if err.is_connect() {"), "{core}");
+    }
+
+    #[test]
+    fn test_default_transport_reports_non_json_bodies() {
+        let core = &TransportCoreGenerator.generate(&[])[0].1;
+
+        assert!(
+            core.contains(
+                "return Err(TransportError::NonJsonResponse { status, body_snippet: text[..end].trim().to_string() });"
+            ),
+            "{core}"
+        );
+        // Every single-request path and the batch path read the body through `read_json`
+        assert_eq!(core.matches("let json: Value = read_json(response).await?;").count(), 3);
+        assert!(core.contains("            read_json(response).await\n"), "{core}");
+        assert!(!core.contains("serde_json::from_str(&text)"), "{core}");
+    }
+
+    #[test]
+    fn test_default_transport_reports_rejected_credentials() {
+        let core = &TransportCoreGenerator.generate(&[])[0].1;
+
+        assert!(core.contains("Unauthorized { status: u16 },"), "{core}");
+        // Checked before the empty body bitcoind sends would fail to parse
+        let check = core
+            .find("if matches!(status, 401 | 403) {\n        return Err(TransportError::Unauthorized { status });")
+            .unwrap();
+        assert!(check < core.find("let text = response.text()").unwrap(), "{core}");
+    }
+
+    #[test]
+    fn test_transport_wrappers_use_typed_arguments() {
+        let getblockhash = r#"{"methods": {"getblockhash": {"name": "getblockhash", "description": "", "category": "blockchain", "examples": "", "argument_names": ["height"], "arguments": [{"names": ["height"], "description": "", "type": "number", "required": true}], "results": []}}}"#;
+        let file = write_schema(getblockhash);
+        let methods = load_api_methods_from_file(file.path()).unwrap();
+
+        let files =
+            TransportCodeGenerator::new(Version::from_string("v30").unwrap()).generate(&methods);
+        let src = &files[0].1;
+        assert!(
+            src.contains("pub async fn getblockhash(transport: &dyn TransportTrait, height: u64)"),
+            "{src}"
+        );
+    }
+
+    #[test]
+    fn test_hash_or_height_arguments_take_impl_into() {
+        let getblockstats = r#"{"methods": {"getblockstats": {"name": "getblockstats", "description": "", "category": "blockchain", "examples": "", "argument_names": ["hash_or_height"], "arguments": [{"names": ["hash_or_height"], "description": "", "type": "number", "required": true}], "results": []}}}"#;
+        let file = write_schema(getblockstats);
+        let methods = load_api_methods_from_file(file.path()).unwrap();
+
+        let files =
+            TransportCodeGenerator::new(Version::from_string("v30").unwrap()).generate(&methods);
+        let src = &files[0].1;
+        assert!(src.contains("use bitcoin_rpc_types::HashOrHeight;"), "{src}");
+        assert!(src.contains("hash_or_height: impl Into<HashOrHeight>)"), "{src}");
+        assert!(src.contains("vec![json!(Into::<HashOrHeight>::into(hash_or_height))]"), "{src}");
+
+        let files = generators::ClientTraitGenerator::new("v30").generate(&methods);
+        let (_, client) = files.iter().find(|(name, _)| name == "client.rs").unwrap();
+        assert!(client.contains("_hash_or_height: impl Into<HashOrHeight>)"), "{client}");
+        assert!(
+            client.contains("serde_json::json!(Into::<HashOrHeight>::into(_hash_or_height)),"),
+            "{client}"
+        );
+    }
+
+    #[test]
+    fn test_address_type_and_estimate_mode_arguments_take_enums() {
+        let schema = r#"{"methods": {"getnewaddress": {"name": "getnewaddress", "description": "", "category": "wallet", "examples": "", "argument_names": ["label", "address_type"], "arguments": [{"names": ["label"], "description": "", "type": "string", "required": false}, {"names": ["address_type"], "description": "", "type": "string", "required": false}], "results": []}, "estimatesmartfee": {"name": "estimatesmartfee", "description": "", "category": "util", "examples": "", "argument_names": ["conf_target", "estimate_mode"], "arguments": [{"names": ["conf_target"], "description": "", "type": "number", "required": true}, {"names": ["estimate_mode"], "description": "", "type": "string", "required": false}], "results": []}}}"#;
+        let file = write_schema(schema);
+        let methods = load_api_methods_from_file(file.path()).unwrap();
+
+        let files =
+            TransportCodeGenerator::new(Version::from_string("v30").unwrap()).generate(&methods);
+        let src = |name: &str| &files.iter().find(|(n, _)| n == name).unwrap().1;
+        let getnewaddress = src("getnewaddress");
+        assert!(getnewaddress.contains("use crate::transport::AddressType;"), "{getnewaddress}");
+        assert!(getnewaddress.contains("address_type: Option<AddressType>)"), "{getnewaddress}");
+        assert!(!getnewaddress.contains("HashOrHeight"), "{getnewaddress}");
+        let estimatesmartfee = src("estimatesmartfee");
+        assert!(
+            estimatesmartfee.contains("use crate::transport::EstimateMode;"),
+            "{estimatesmartfee}"
+        );
+        assert!(
+            estimatesmartfee.contains("estimate_mode: Option<EstimateMode>)"),
+            "{estimatesmartfee}"
+        );
+
+        let files = generators::ClientTraitGenerator::new("v30").generate(&methods);
+        let (_, client) = files.iter().find(|(name, _)| name == "client.rs").unwrap();
+        assert!(client.contains("use crate::transport::AddressType;"), "{client}");
+        assert!(client.contains("_address_type: Option<AddressType>)"), "{client}");
+        assert!(client.contains("_estimate_mode: Option<EstimateMode>)"), "{client}");
+    }
+
+    #[test]
+    fn test_descriptor_arguments_take_descriptor() {
+        let getdescriptorinfo = r#"{"methods": {"getdescriptorinfo": {"name": "getdescriptorinfo", "description": "", "category": "util", "examples": "", "argument_names": ["descriptor"], "arguments": [{"names": ["descriptor"], "description": "", "type": "string", "required": true}], "results": []}}}"#;
+        let file = write_schema(getdescriptorinfo);
+        let methods = load_api_methods_from_file(file.path()).unwrap();
+
+        let files =
+            TransportCodeGenerator::new(Version::from_string("v30").unwrap()).generate(&methods);
+        let src = &files[0].1;
+        assert!(src.contains("use crate::transport::Descriptor;"), "{src}");
+        assert!(src.contains("descriptor: Descriptor)"), "{src}");
+        assert!(src.contains("vec![json!(descriptor)]"), "{src}");
+    }
+
+    #[test]
+    fn test_generated_doc_comments_are_well_formed() {
+        let getblockhash = r#"{"methods": {"getblockhash": {"name": "getblockhash", "description": "Returns hash of block in best-block-chain at height provided.\n", "category": "blockchain", "examples": "> bitcoin-cli getblockhash 1000\n", "argument_names": ["height"], "arguments": [{"names": ["height"], "description": "The height index", "type": "number", "required": true}], "results": []}}}"#;
+        let file = write_schema(getblockhash);
+        let methods = load_api_methods_from_file(file.path()).unwrap();
+
+        let transport =
+            TransportCodeGenerator::new(Version::from_string("v30").unwrap()).generate(&methods);
+        let client = generators::ClientTraitGenerator::new("v30").generate(&methods);
+        for (name, src) in transport.iter().chain(client.iter()) {
+            assert!(!src.contains("/// ///"), "{name}: {src}");
+            assert!(!src.contains("bitcoin-cli"), "{name}: {src}");
+        }
+
+        let src = &transport[0].1;
+        assert!(src.contains("/// ```rust,ignore\n"), "{src}");
+        assert!(!src.contains("/// ```rust\n"), "{src}");
+        assert!(src.contains("/// let result = client.getblockhash(0).await?;"), "{src}");
+        assert!(src.contains("/// let result = getblockhash(&transport, 0).await?;"), "{src}");
+        // The examples document the wrapper itself, not the `use` items above it
+        let fn_docs = &src[src.find("/// Returns hash of block").unwrap()..];
+        assert!(fn_docs.contains("/// # Examples"), "{src}");
+        assert!(src.starts_with("//! This file is auto-generated."), "{src}");
+    }
+
+    #[test]
+    fn test_examples_declare_arguments_without_a_placeholder() {
+        let getblockstats = r#"{"methods": {"getblockstats": {"name": "getblockstats", "description": "", "category": "blockchain", "examples": "", "argument_names": ["hash_or_height"], "arguments": [{"names": ["hash_or_height"], "description": "", "type": "number", "required": true}], "results": []}}}"#;
+        let file = write_schema(getblockstats);
+        let methods = load_api_methods_from_file(file.path()).unwrap();
+
+        let src = doc_comment::generate_example_docs(&methods[0]);
+        assert!(!src.contains("todo!()"), "{src}");
+        assert_eq!(
+            src.matches("/// # let hash_or_height: HashOrHeight = unimplemented!();\n").count(),
+            2,
+            "{src}"
+        );
+        assert!(
+            src.contains("/// let result = client.getblockstats(hash_or_height).await?;"),
+            "{src}"
+        );
+        assert!(
+            src.contains("/// let result = getblockstats(&transport, hash_or_height).await?;"),
+            "{src}"
+        );
+    }
+
+    #[test]
+    fn test_existing_wallet_errors_need_a_specific_code_or_message() {
+        let mut code = String::new();
+        generators::test_node::emit_combined_client::emit_wallet_methods(&mut code).unwrap();
+
+        assert!(!code.contains("rpc_code() == Some(-4)"), "{code}");
+        assert!(!code.contains("BitcoinRpcError::WalletError |"), "{code}");
+        assert_eq!(
+            code.matches("Err(e) if Self::is_existing_wallet_error(&e)").count(),
+            2,
+            "{code}"
+        );
+        assert!(code.contains("e.to_string().contains(\"already exists\")"), "{code}");
+    }
+
+    #[test]
+    fn test_option_object_fields_are_documented() {
+        let listunspent = r#"{"methods": {"listunspent": {"name": "listunspent", "description": "", "category": "wallet", "examples": "", "argument_names": ["minconf", "query_options"], "arguments": [
+            {"names": ["minconf"], "description": "", "type": "number", "required": false},
+            {"names": ["query_options"], "description": "", "type": "object", "required": false, "inner": [
+                {"names": ["maximumCount"], "description": "Maximum number of UTXOs", "type": "number", "required": false}
+            ]}
+        ], "results": []}}}"#;
+        let file = write_schema(listunspent);
+        let methods = load_api_methods_from_file(file.path()).unwrap();
+        let objects = load_option_objects_from_file(file.path()).unwrap();
+        assert_eq!(objects.len(), 1);
+        assert_eq!(
+            (objects[0].method.as_str(), objects[0].argument.as_str()),
+            ("listunspent", "query_options")
+        );
+        assert_eq!(objects[0].fields[0].names, ["maximumCount"]);
+
+        let files = TransportCodeGenerator::new(Version::from_string("v30").unwrap())
+            .with_option_objects(objects)
+            .generate(&methods);
+        let src = &files[0].1;
+        assert!(
+            src.contains("/// - `maximumCount` (number, optional): Maximum number of UTXOs\n"),
+            "{src}"
+        );
+        assert!(src.contains("[`ListunspentQueryOptions`]"), "{src}");
+    }
+
+    #[test]
+    fn test_transport_wrappers_trim_trailing_optional_arguments() {
+        let args = [("wallet_name", true), ("disable_private_keys", false), ("blank", false)]
+            .iter()
+            .map(|(name, required)| argument(name, *required))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let file = write_schema(&format!(
+            r#"{{"methods": {{"createwallet": {{"name": "createwallet", "description": "", "category": "wallet", "examples": "", "argument_names": [], "arguments": [{args}], "results": []}}}}}}"#
+        ));
+        let methods = load_api_methods_from_file(file.path()).unwrap();
+
+        let files =
+            TransportCodeGenerator::new(Version::from_string("v30").unwrap()).generate(&methods);
+        let src = &files[0].1;
+        assert!(
+            src.contains("disable_private_keys: Option<String>, blank: Option<String>"),
+            "{src}"
+        );
+        // `createwallet("w", None, None)` keeps only the required prefix: `["w"]`.
+        assert!(src.contains("let mut params = vec![json!(wallet_name)];"), "{src}");
+        assert!(
+            src.contains(
+                "let optional = [disable_private_keys.map(|v| json!(v)), blank.map(|v| json!(v))];"
+            ),
+            "{src}"
+        );
+        assert!(src.contains("optional.iter().rposition(Option::is_some)"), "{src}");
+    }
+
+    #[test]
+    fn test_verbosity_overloads_fix_their_arguments() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../bitcoin-core-api.json");
+        let methods = load_api_methods_from_file(path).unwrap();
+        let getblock = generators::verbosity::with_overloads(
+            &methods.into_iter().filter(|m| m.name == "getblock").collect::<Vec<_>>(),
+        )
+        .unwrap();
+
+        let files =
+            TransportCodeGenerator::new(Version::from_string("v30").unwrap()).generate(&getblock);
+        let names = files.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, ["getblock", "getblock_hex", "getblock_verbose", "getblock_verbose2"]);
+
+        let src = &files[3].1;
+        assert!(
+            src.contains(
+                "pub async fn getblock_verbose2(transport: &dyn TransportTrait, blockhash: bitcoin::BlockHash) -> Result<GetblockVerbose2Response, TransportError>"
+            ),
+            "{src}"
+        );
+        assert!(src.contains("let params = vec![json!(blockhash), json!(2)];"), "{src}");
+        assert!(src.contains("transport.send_request(\"getblock\", &params)"), "{src}");
+        assert!(src.contains("/// Calls the `getblock` RPC method."), "{src}");
+    }
+
+    #[test]
+    fn test_keyword_names_are_escaped_in_every_generator() {
+        let file = write_schema(
+            r#"{"methods": {"match": {"name": "match", "description": "", "category": "util", "examples": "", "argument_names": ["type", "fn"], "arguments": [
+                {"names": ["type"], "description": "", "type": "string", "required": true},
+                {"names": ["fn"], "description": "", "type": "string", "required": false}
+            ], "results": [{"type": "string", "description": ""}]}}}"#,
+        );
+        let methods = load_api_methods_from_file(file.path()).unwrap();
+
+        let version = Version::from_string("v30").unwrap();
+        let src = &TransportCodeGenerator::new(version.clone()).generate(&methods)[0].1;
+        assert!(
+            src.contains("pub async fn r#match(transport: &dyn TransportTrait, r#type: String, r#fn: Option<String>)"),
+            "{src}"
+        );
+        assert!(src.contains("vec![json!(r#type)]"), "{src}");
+        assert!(src.contains("transport.send_request(\"match\", &params)"), "{src}");
+
+        let client = &generators::ClientTraitGenerator::new("v30").generate(&methods)[0].1;
+        assert!(client.contains("async fn r#match(&self, _type: String, _fn: Option<String>)"));
+
+        let batch = &generators::BatchBuilderGenerator::default().generate(&methods)[0].1;
+        assert!(batch.contains("pub fn r#match(mut self, r#type: Value, r#fn: Value) -> Self"));
+        assert!(batch.contains("self.calls.push((\"match\", vec![json!(r#type), json!(r#fn)]));"));
+        assert!(batch.contains("\"match\" => results.r#match = Some("), "{batch}");
+    }
+
+    #[test]
+    fn test_builders_for_methods_above_threshold() {
+        let args = [("wallet_name", true), ("disable_private_keys", false), ("blank", false)]
+            .iter()
+            .map(|(name, required)| argument(name, *required))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let file = write_schema(&format!(
+            r#"{{"methods": {{"createwallet": {{"name": "createwallet", "description": "", "category": "wallet", "examples": "", "argument_names": [], "arguments": [{args}], "results": []}}}}}}"#
+        ));
+        let methods = load_api_methods_from_file(file.path()).unwrap();
+        let version = Version::from_string("v30").unwrap();
+
+        // Three arguments are below the default threshold
+        let src = &TransportCodeGenerator::new(version.clone()).generate(&methods)[0].1;
+        assert!(!src.contains("CreatewalletBuilder"), "{src}");
+
+        let src =
+            &TransportCodeGenerator::new(version).with_builder_threshold(2).generate(&methods)[0].1;
+        assert!(src.contains("pub struct CreatewalletBuilder {"), "{src}");
+        assert!(src.contains("pub fn new(wallet_name: String) -> Self {"), "{src}");
+        assert!(
+            src.contains("Self { wallet_name, disable_private_keys: None, blank: None }"),
+            "{src}"
+        );
+        assert!(src.contains("pub fn blank(mut self, blank: String) -> Self {"), "{src}");
+        assert!(
+            src.contains(
+                "createwallet(transport, self.wallet_name, self.disable_private_keys, self.blank).await"
+            ),
+            "{src}"
+        );
+    }
+
+    #[test]
+    fn test_deprecated_methods_get_deprecated_attribute() {
+        let file = write_schema(
+            r#"{"methods": {"getunconfirmedbalance": {"name": "getunconfirmedbalance", "description": "DEPRECATED\nIdentical to getbalances().mine.untrusted_pending\n", "category": "wallet", "examples": "", "argument_names": [], "arguments": [], "results": []}, "getbalances": {"name": "getbalances", "description": "Returns an object with all balances in BTC.\n", "category": "wallet", "examples": "", "argument_names": [], "arguments": [], "results": []}}}"#,
+        );
+        let methods = load_api_methods_from_file(file.path()).unwrap();
+        let files =
+            TransportCodeGenerator::new(Version::from_string("v29").unwrap()).generate(&methods);
+        let src = |name: &str| &files.iter().find(|(n, _)| n == name).unwrap().1;
+
+        let attribute = "#[deprecated(note = \"deprecated by Bitcoin Core; use \
+                         `getbalances().mine.untrusted_pending` instead\")]\n\
+                         pub async fn getunconfirmedbalance(";
+        assert!(
+            src("getunconfirmedbalance").contains(attribute),
+            "{}",
+            src("getunconfirmedbalance")
+        );
+        assert!(!src("getbalances").contains("#[deprecated"));
+
+        assert_eq!(
+            doc_comment::deprecation_note("(DEPRECATED) Set the transaction fee rate"),
+            Some("deprecated by Bitcoin Core".to_string())
+        );
+        assert_eq!(doc_comment::deprecation_note("Use getbalances instead"), None);
+    }
+
+    #[test]
+    fn test_load_api_methods_structural_errors() {
+        let missing = write_schema(r#"{"version": "v30.0.0"}"#);
+        assert!(matches!(
+            load_api_methods_from_file(missing.path()),
+            Err(ParseApiError::MissingMethods)
+        ));
+
+        let not_object = write_schema(r#"{"methods": []}"#);
+        assert!(matches!(
+            load_api_methods_from_file(not_object.path()),
+            Err(ParseApiError::MethodsNotObject)
+        ));
+
+        let invalid = write_schema("{ not json");
+        assert!(matches!(load_api_methods_from_file(invalid.path()), Err(ParseApiError::Json(_))));
+
+        assert!(matches!(
+            load_api_methods_from_file("/nonexistent/api.json"),
+            Err(ParseApiError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn test_write_generated_formats_files_in_one_pass() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = vec![
+            ("alpha".to_string(), "pub fn alpha( )->u8{1}".to_string()),
+            ("nested/beta.rs".to_string(), "pub fn beta( )->u8{2}".to_string()),
+        ];
+        write_generated(dir.path(), &files).unwrap();
+
+        let alpha = fs::read_to_string(dir.path().join("alpha.rs")).unwrap();
+        let beta = fs::read_to_string(dir.path().join("nested/beta.rs")).unwrap();
+        if rustfmt_enabled() {
+            assert_eq!(alpha, "pub fn alpha() -> u8 {\n    1\n}\n");
+            assert_eq!(beta, "pub fn beta() -> u8 {\n    2\n}\n");
+        } else {
+            assert_eq!(alpha, files[0].1);
+        }
+    }
+}
@@ -14,7 +14,7 @@
 //! can simply do:
 //!
 //! ```rust, ignore
-//! use generated::responses::*;  // re‑exports v28_types, v29_types, latest_types…
+//! use generated::responses::*;  // the newest version's types; older ones stay in v28_responses…
 //! ```
 //!
 //! Concretely it writes:
@@ -57,11 +57,20 @@ impl ModuleGenerator {
     /// 1. `responses/<version>_responses/mod.rs`
     /// 2. top‑level re‑export files (`responses/mod.rs`)
     pub fn generate_all(&self) -> io::Result<()> {
-        self.generate_responses_mod_rs()?;
-        self.generate_top_level_responses_mod()?;
+        for (path, contents) in self.render_all()? {
+            let path = self.out_dir.join(path);
+            fs::create_dir_all(path.parent().unwrap())?;
+            fs::write(&path, contents)?;
+        }
         Ok(())
     }
 
+    /// The files [`generate_all`](Self::generate_all) writes, as `(path relative to the
+    /// output directory, contents)`, without touching the disk.
+    pub fn render_all(&self) -> io::Result<Vec<(PathBuf, String)>> {
+        Ok(vec![(Path::new("responses").join("mod.rs"), self.render_top_level_responses_mod()?)])
+    }
+
     /// Writes _one_ `mod.rs` that lives in  
     /// `…/responses/mod.rs` and declares `pub mod v28_responses; pub mod v29_responses; …`.
     pub fn generate_responses_mod_rs(&self) -> io::Result<()> {
@@ -73,9 +82,10 @@ impl ModuleGenerator {
         )
     }
 
-    /// Creates the top‑level responses module file that declares `pub mod` for each version
-    /// and then `pub use` every version so downstream crates can do `use generated::responses::*`.
-    fn generate_top_level_responses_mod(&self) -> io::Result<()> {
+    /// Renders the top‑level responses module file that declares `pub mod` for each version
+    /// and then `pub use`s the newest so downstream crates can do `use generated::responses::*`;
+    /// older versions stay reachable as `responses::v28_responses::*`.
+    fn render_top_level_responses_mod(&self) -> io::Result<String> {
         use std::fmt::Write;
 
         let mut types_mod_rs = String::new();
@@ -90,15 +100,13 @@ impl ModuleGenerator {
         }
         writeln!(types_mod_rs).map_err(io::Error::other)?;
 
-        for version in &self.versions {
-            writeln!(types_mod_rs, "pub use self::{}_responses::*;", version.as_module_name())
+        // Versions share type names, so only the newest is re-exported unqualified
+        if let Some(newest) = self.versions.iter().max() {
+            writeln!(types_mod_rs, "pub use self::{}_responses::*;", newest.as_module_name())
                 .map_err(io::Error::other)?;
         }
 
-        let types_mod_path = self.out_dir.join("responses").join("mod.rs");
-        fs::create_dir_all(types_mod_path.parent().unwrap())?;
-        fs::write(&types_mod_path, types_mod_rs)?;
-        Ok(())
+        Ok(types_mod_rs)
     }
 }
 
@@ -121,3 +129,21 @@ fn generate_versioned_mod_rs(
     fs::write(&mod_path, mod_rs_content)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_the_newest_responses_are_reexported() {
+        let versions = vec![Version::new(30, 0), Version::new(29, 1)];
+        let files = ModuleGenerator::new(versions, PathBuf::new()).render_all().unwrap();
+
+        assert_eq!(files.len(), 1);
+        let (path, mod_rs) = &files[0];
+        assert_eq!(path, &Path::new("responses").join("mod.rs"));
+        assert!(mod_rs.contains("pub mod v30_responses;\npub mod v29_1_responses;\n"), "{mod_rs}");
+        assert!(mod_rs.contains("pub use self::v30_responses::*;"), "{mod_rs}");
+        assert!(!mod_rs.contains("pub use self::v29_1_responses"), "{mod_rs}");
+    }
+}
@@ -0,0 +1,198 @@
+//! Canonical spelling of schema type strings.
+//!
+//! Schemas from different sources spell the same type differently: Core's help text says
+//! `numeric` and `json object`, hand-written schemas use `bool` or `Amount`. The loaders
+//! rewrite every argument and result type into [`CANONICAL_TYPES`] once, right after
+//! parsing, so generators and the [`type_registry`](crate::type_registry) only ever match
+//! the canonical names.
+
+use std::collections::BTreeSet;
+
+use bitcoin_rpc_types::{BtcArgument, BtcMethod, BtcResult};
+
+/// The type vocabulary generators see, as used by Bitcoin Core's own schema dump.
+pub const CANONICAL_TYPES: &[&str] = &[
+    "amount",
+    "any",
+    "array",
+    "boolean",
+    "elision",
+    "hex",
+    "none",
+    "number",
+    "object",
+    "range",
+    "string",
+    "timestamp",
+];
+
+/// The canonical type for a raw schema type string, or `None` if it is not recognized.
+///
+/// Matching ignores case and surrounding whitespace.
+pub fn canonical_type(raw: &str) -> Option<&'static str> {
+    let raw = raw.trim().to_ascii_lowercase();
+    if let Some(canonical) = CANONICAL_TYPES.iter().find(|t| **t == raw) {
+        return Some(canonical);
+    }
+    let canonical = match raw.as_str() {
+        "str_amount" | "numeric or string" | "string or numeric" => "amount",
+        "bool" => "boolean",
+        "..." => "elision",
+        "str_hex" | "hex string" | "hexstring" => "hex",
+        "null" | "json null" => "none",
+        "num" | "numeric" | "int" | "integer" => "number",
+        "obj" | "json object" | "obj_dyn" | "obj_named" | "obj_user_keys" => "object",
+        "arr" | "json array" | "arr_fixed" => "array",
+        "numeric or array" => "range",
+        "str" => "string",
+        "time" => "timestamp",
+        _ => return None,
+    };
+    Some(canonical)
+}
+
+/// Rewrites schema type strings into [`CANONICAL_TYPES`].
+pub struct SchemaNormalizer;
+
+impl SchemaNormalizer {
+    /// Canonicalize every argument and result type of `methods`.
+    ///
+    /// Unrecognized types are left as they are and logged once each as a warning.
+    ///
+    /// # Returns
+    ///
+    /// Returns the unrecognized type strings, sorted
+    pub fn normalize_methods(methods: &mut [BtcMethod]) -> Vec<String> {
+        let mut unknown = BTreeSet::new();
+        for method in methods.iter_mut() {
+            Self::normalize_arguments(&mut method.arguments, &mut unknown);
+            for result in &mut method.results {
+                normalize_result(result, &mut unknown);
+            }
+        }
+        warn_unknown(unknown)
+    }
+
+    /// Canonicalize the types of `arguments`, e.g. the fields of an option object.
+    ///
+    /// Unrecognized types are left and collected into `unknown`.
+    pub fn normalize_arguments(arguments: &mut [BtcArgument], unknown: &mut BTreeSet<String>) {
+        for argument in arguments {
+            normalize_type(&mut argument.type_, unknown);
+        }
+    }
+}
+
+/// Log each unrecognized type once and return them in order.
+pub(crate) fn warn_unknown(unknown: BTreeSet<String>) -> Vec<String> {
+    for type_ in &unknown {
+        tracing::warn!("Unrecognized schema type {type_:?}; generating it as-is");
+    }
+    unknown.into_iter().collect()
+}
+
+fn normalize_type(type_: &mut String, unknown: &mut BTreeSet<String>) {
+    match canonical_type(type_) {
+        Some(canonical) =>
+            if type_ != canonical {
+                *type_ = canonical.to_string();
+            },
+        None => {
+            unknown.insert(type_.clone());
+        }
+    }
+}
+
+fn normalize_result(result: &mut BtcResult, unknown: &mut BTreeSet<String>) {
+    normalize_type(&mut result.type_, unknown);
+    for inner in &mut result.inner {
+        normalize_result(inner, unknown);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_aliases_map_to_canonical_types() {
+        for (raw, canonical) in [
+            ("Amount", "amount"),
+            ("numeric or string", "amount"),
+            ("bool", "boolean"),
+            ("Boolean", "boolean"),
+            ("STR_HEX", "hex"),
+            ("hex", "hex"),
+            ("numeric", "number"),
+            (" number ", "number"),
+            ("json object", "object"),
+            ("json array", "array"),
+            ("json null", "none"),
+            ("time", "timestamp"),
+        ] {
+            assert_eq!(canonical_type(raw), Some(canonical), "{raw}");
+        }
+        assert_eq!(canonical_type("satoshis"), None);
+        // Every canonical type is its own canonical form
+        for type_ in CANONICAL_TYPES {
+            assert_eq!(canonical_type(type_), Some(*type_));
+        }
+    }
+
+    #[test]
+    fn test_normalize_methods_rewrites_nested_types() {
+        let field = |type_: &str, inner: Vec<BtcResult>| BtcResult {
+            type_: type_.into(),
+            inner,
+            ..Default::default()
+        };
+        let mut methods = vec![BtcMethod {
+            name: "getblock".into(),
+            description: String::new(),
+            examples: String::new(),
+            argument_names: vec![],
+            arguments: vec![BtcArgument {
+                names: vec!["verbose".into()],
+                description: String::new(),
+                oneline_description: String::new(),
+                also_positional: false,
+                type_str: None,
+                required: false,
+                hidden: false,
+                type_: "bool".into(),
+            }],
+            results: vec![field(
+                "json object",
+                vec![
+                    field("Amount", vec![]),
+                    field("satoshis", vec![]),
+                    field("json array", vec![field("STR_HEX", vec![])]),
+                ],
+            )],
+        }];
+
+        assert_eq!(SchemaNormalizer::normalize_methods(&mut methods), ["satoshis"]);
+        let method = &methods[0];
+        assert_eq!(method.arguments[0].type_, "boolean");
+        let object = &method.results[0];
+        assert_eq!(object.type_, "object");
+        let types = object.inner.iter().map(|r| r.type_.as_str()).collect::<Vec<_>>();
+        assert_eq!(types, ["amount", "satoshis", "array"]);
+        assert_eq!(object.inner[2].inner[0].type_, "hex");
+    }
+
+    #[test]
+    fn test_normalized_output_is_stable() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../bitcoin-core-api.json");
+        let raw = std::fs::read_to_string(path).unwrap();
+        let methods = crate::load_api_methods_from_str(&raw).unwrap();
+
+        // The bundled schema is already canonical, and normalizing again changes nothing
+        let mut again = methods.clone();
+        assert!(SchemaNormalizer::normalize_methods(&mut again).is_empty());
+        assert_eq!(
+            serde_json::to_string(&again).unwrap(),
+            serde_json::to_string(&methods).unwrap()
+        );
+    }
+}
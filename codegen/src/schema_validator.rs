@@ -0,0 +1,267 @@
+//! Structural checks on a parsed schema, run before any code is generated.
+//!
+//! Generators assume every argument is named, names are unique within a method and result
+//! trees are shaped like the JSON they describe. [`SchemaValidator`] checks those
+//! invariants up front and reports every violation at once, so a bad schema fails with a
+//! list of problems instead of a panic halfway through generation.
+
+use std::collections::HashSet;
+
+use bitcoin_rpc_types::{BtcMethod, BtcResult};
+
+/// A schema invariant a method violates.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ValidateError {
+    /// An argument has no name.
+    #[error("{method}: argument {index} has no name")]
+    UnnamedArgument {
+        /// The method taking the argument.
+        method: String,
+        /// The argument's position.
+        index: usize,
+    },
+
+    /// Two arguments (or aliases) of one method share a name.
+    #[error("{method}: argument name '{name}' is used more than once")]
+    DuplicateArgument {
+        /// The method taking the arguments.
+        method: String,
+        /// The repeated name.
+        name: String,
+    },
+
+    /// A field named `amount` (or `*_amount`) is not typed as a number.
+    #[error("{method}: amount field '{field}' has non-numeric type '{type_}'")]
+    NonNumericAmount {
+        /// The method the field belongs to.
+        method: String,
+        /// The argument name or result path.
+        field: String,
+        /// The field's schema type.
+        type_: String,
+    },
+
+    /// A result that is neither an object nor an array has nested fields.
+    #[error("{method}: result '{path}' of type '{type_}' cannot have nested fields")]
+    ScalarWithInner {
+        /// The method returning the result.
+        method: String,
+        /// Path to the result, e.g. `results[1].tx`.
+        path: String,
+        /// The result's schema type.
+        type_: String,
+    },
+
+    /// An object result has two fields with the same key.
+    #[error("{method}: object '{path}' has more than one field named '{key}'")]
+    DuplicateField {
+        /// The method returning the object.
+        method: String,
+        /// Path to the object.
+        path: String,
+        /// The repeated key.
+        key: String,
+    },
+}
+
+/// Checks parsed methods against the invariants the generators rely on.
+pub struct SchemaValidator;
+
+impl SchemaValidator {
+    /// Validate `methods`, collecting every violation rather than stopping at the first.
+    pub fn validate_methods(methods: &[BtcMethod]) -> Result<(), Vec<ValidateError>> {
+        let mut errors = Vec::new();
+        for method in methods {
+            validate_arguments(method, &mut errors);
+            for (index, result) in method.results.iter().enumerate() {
+                validate_result(&method.name, result, &format!("results[{index}]"), &mut errors);
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Whether a field name denotes an amount, e.g. `amount` or `total_amount`.
+fn is_amount_name(name: &str) -> bool { name == "amount" || name.ends_with("_amount") }
+
+/// Whether an amount field's type is numeric.
+fn is_numeric(type_: &str) -> bool { matches!(type_, "amount" | "number") }
+
+fn validate_arguments(method: &BtcMethod, errors: &mut Vec<ValidateError>) {
+    let mut seen = HashSet::new();
+    for (index, argument) in method.arguments.iter().enumerate() {
+        if argument.names.first().is_none_or(|name| name.is_empty()) {
+            errors.push(ValidateError::UnnamedArgument { method: method.name.clone(), index });
+            continue;
+        }
+        for name in &argument.names {
+            if !seen.insert(name.as_str()) {
+                errors.push(ValidateError::DuplicateArgument {
+                    method: method.name.clone(),
+                    name: name.clone(),
+                });
+            }
+        }
+        if is_amount_name(&argument.names[0]) && !is_numeric(&argument.type_) {
+            errors.push(ValidateError::NonNumericAmount {
+                method: method.name.clone(),
+                field: argument.names[0].clone(),
+                type_: argument.type_.clone(),
+            });
+        }
+    }
+}
+
+fn validate_result(method: &str, result: &BtcResult, path: &str, errors: &mut Vec<ValidateError>) {
+    if is_amount_name(&result.key_name) && !is_numeric(&result.type_) {
+        errors.push(ValidateError::NonNumericAmount {
+            method: method.to_string(),
+            field: path.to_string(),
+            type_: result.type_.clone(),
+        });
+    }
+    if result.inner.is_empty() {
+        return;
+    }
+    if !matches!(result.type_.as_str(), "object" | "array") {
+        errors.push(ValidateError::ScalarWithInner {
+            method: method.to_string(),
+            path: path.to_string(),
+            type_: result.type_.clone(),
+        });
+    }
+
+    let mut keys = HashSet::new();
+    for (index, field) in result.inner.iter().enumerate() {
+        if result.type_ == "object" && !field.key_name.is_empty() && !keys.insert(&field.key_name) {
+            errors.push(ValidateError::DuplicateField {
+                method: method.to_string(),
+                path: path.to_string(),
+                key: field.key_name.clone(),
+            });
+        }
+        let child = if field.key_name.is_empty() {
+            format!("{path}[{index}]")
+        } else {
+            format!("{path}.{}", field.key_name)
+        };
+        validate_result(method, field, &child, errors);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin_rpc_types::BtcArgument;
+
+    use super::*;
+
+    fn argument(names: &[&str], type_: &str) -> BtcArgument {
+        BtcArgument {
+            names: names.iter().map(|n| n.to_string()).collect(),
+            description: String::new(),
+            oneline_description: String::new(),
+            also_positional: false,
+            type_str: None,
+            required: false,
+            hidden: false,
+            type_: type_.into(),
+        }
+    }
+
+    fn field(key_name: &str, type_: &str, inner: Vec<BtcResult>) -> BtcResult {
+        BtcResult { type_: type_.into(), key_name: key_name.into(), inner, ..Default::default() }
+    }
+
+    fn method(arguments: Vec<BtcArgument>, results: Vec<BtcResult>) -> BtcMethod {
+        BtcMethod {
+            name: "sendtoaddress".into(),
+            description: String::new(),
+            examples: String::new(),
+            argument_names: vec![],
+            arguments,
+            results,
+        }
+    }
+
+    #[test]
+    fn test_accepts_well_formed_methods() {
+        let valid = method(
+            vec![argument(&["address"], "string"), argument(&["amount"], "amount")],
+            vec![field(
+                "",
+                "object",
+                vec![
+                    field("txid", "hex", vec![]),
+                    field("tx", "array", vec![field("", "hex", vec![])]),
+                ],
+            )],
+        );
+        assert_eq!(SchemaValidator::validate_methods(&[valid]), Ok(()));
+    }
+
+    #[test]
+    fn test_reports_every_violation() {
+        let invalid = method(
+            vec![
+                argument(&[], "string"),
+                argument(&["address", "addr"], "string"),
+                argument(&["addr"], "string"),
+                argument(&["amount"], "string"),
+            ],
+            vec![field(
+                "",
+                "object",
+                vec![
+                    field("fee", "amount", vec![]),
+                    field("fee", "amount", vec![]),
+                    field("total_amount", "string", vec![]),
+                    field("txid", "hex", vec![field("vout", "number", vec![])]),
+                ],
+            )],
+        );
+        let errors = SchemaValidator::validate_methods(&[invalid]).unwrap_err();
+        let method = || "sendtoaddress".to_string();
+        assert_eq!(
+            errors,
+            [
+                ValidateError::UnnamedArgument { method: method(), index: 0 },
+                ValidateError::DuplicateArgument { method: method(), name: "addr".into() },
+                ValidateError::NonNumericAmount {
+                    method: method(),
+                    field: "amount".into(),
+                    type_: "string".into()
+                },
+                ValidateError::DuplicateField {
+                    method: method(),
+                    path: "results[0]".into(),
+                    key: "fee".into()
+                },
+                ValidateError::NonNumericAmount {
+                    method: method(),
+                    field: "results[0].total_amount".into(),
+                    type_: "string".into()
+                },
+                ValidateError::ScalarWithInner {
+                    method: method(),
+                    path: "results[0].txid".into(),
+                    type_: "hex".into()
+                },
+            ]
+        );
+        assert_eq!(
+            errors[5].to_string(),
+            "sendtoaddress: result 'results[0].txid' of type 'hex' cannot have nested fields"
+        );
+    }
+
+    #[test]
+    fn test_bundled_schema_is_valid() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../bitcoin-core-api.json");
+        let methods = crate::load_api_methods_from_file(path).unwrap();
+        assert_eq!(SchemaValidator::validate_methods(&methods), Ok(()));
+    }
+}
@@ -20,6 +20,7 @@ impl CodeGenerator for TransportCoreGenerator {
         emit_default_transport_struct(&mut code);
         emit_default_transport_impl(&mut code);
         emit_transport_impl(&mut code);
+        emit_wallet_name_encoder(&mut code);
 
         vec![("core.rs".to_string(), code)]
     }
@@ -42,17 +43,106 @@ fn emit_error_enum(code: &mut String) {
         "#[derive(Debug, Error, serde::Serialize, serde::Deserialize)]\n\
          pub enum TransportError {{\n\
              #[error(\"HTTP error: {{0}}\")] Http(String),\n\
+             /// The node could not be reached, so the request was never sent.\n\
+             #[error(\"Connection error: {{0}}\")] Connect(String),\n\
              #[error(\"JSON error: {{0}}\")] Json(String),\n\
              #[error(\"RPC error: {{0}}\")] Rpc(String),\n\
+             #[error(\"RPC error {{code}}: {{message}}\")] RpcCode {{ code: i32, message: String, data: Option<Value> }},\n\
+             #[error(\"Incompatible node: client generated for Bitcoin Core {{expected}}, node runs {{actual}}\")] IncompatibleVersion {{ expected: String, actual: String }},\n\
+             /// The result of `method` did not match its typed response; `message` is the serde\n\
+             /// error, naming e.g. the missing field.\n\
+             #[error(\"{{method}}: {{message}}\")] ResponseDecode {{ method: String, message: String }},\n\
+             /// The response answered a different request id than the one sent (`actual` is\n\
+             /// `null` if it had none).\n\
+             #[error(\"Response id {{actual}} does not match request id {{expected}}\")] IdMismatch {{ expected: u64, actual: Value }},\n\
+             /// The server answered with a body that is not JSON at all, e.g. a proxy's HTML\n\
+             /// error page; `body_snippet` is the start of it.\n\
+             #[error(\"Non-JSON response (HTTP {{status}}): {{body_snippet}}\")] NonJsonResponse {{ status: u16, body_snippet: String }},\n\
+             /// The node rejected the credentials with HTTP 401 or 403.\n\
+             #[error(\"Unauthorized (HTTP {{status}}): check rpcuser/rpcpassword or the cookie file\")] Unauthorized {{ status: u16 }},\n\
+         }}\n\
+         \n\
+         impl TransportError {{\n\
+             /// The JSON-RPC error code, if this error is a standard RPC error object.\n\
+             pub fn rpc_code(&self) -> Option<i32> {{\n\
+                 match self {{\n\
+                     TransportError::RpcCode {{ code, .. }} => Some(*code),\n\
+                     _ => None,\n\
+                 }}\n\
+             }}\n\
+         \n\
+             /// A [`TransportError::ResponseDecode`] for the result of `method` failing to decode.\n\
+             pub fn response_decode(method: &str, error: impl std::fmt::Display) -> Self {{\n\
+                 TransportError::ResponseDecode {{ method: method.to_string(), message: error.to_string() }}\n\
+             }}\n\
+         }}\n\
+         \n\
+         /// Deserialize the `result` of `method` into `R`, naming the field that failed to\n\
+         /// decode (e.g. `vout[1].value`) in the [`TransportError::ResponseDecode`] message.\n\
+         pub fn decode_result<R: serde::de::DeserializeOwned>(method: &str, result: &Value) -> Result<R, TransportError> {{\n\
+             serde_path_to_error::deserialize(result).map_err(|e| TransportError::response_decode(method, e))\n\
+         }}\n\
+         \n\
+         /// Convert a JSON-RPC `error` value into the most specific `TransportError`.\n\
+         pub(crate) fn rpc_error(error: &Value) -> TransportError {{\n\
+             let code = error.get(\"code\").and_then(Value::as_i64).and_then(|c| i32::try_from(c).ok());\n\
+             let message = error.get(\"message\").and_then(Value::as_str);\n\
+             match (code, message) {{\n\
+                 (Some(code), Some(message)) => TransportError::RpcCode {{\n\
+                     code,\n\
+                     message: message.to_string(),\n\
+                     data: error.get(\"data\").cloned(),\n\
+                 }},\n\
+                 _ => TransportError::Rpc(error.to_string()),\n\
+             }}\n\
+         }}\n\
+         \n\
+         /// How much of a non-JSON body [`TransportError::NonJsonResponse`] keeps.\n\
+         const BODY_SNIPPET_LEN: usize = 256;\n\
+         \n\
+         /// Read the body of `response` and parse it as JSON.\n\
+         ///\n\
+         /// HTTP 401/403 fails with [`TransportError::Unauthorized`] before the body is read, as\n\
+         /// bitcoind answers bad credentials with an empty one.\n\
+         ///\n\
+         /// A body that is not JSON at all (e.g. a proxy's HTML `502` page or bitcoind's\n\
+         /// plain-text `Work queue depth exceeded`) fails with [`TransportError::NonJsonResponse`],\n\
+         /// while JSON of the wrong shape for `T` is a [`TransportError::Json`] error.\n\
+         async fn read_json<T: serde::de::DeserializeOwned>(response: reqwest::Response) -> Result<T, TransportError> {{\n\
+             let status = response.status().as_u16();\n\
+             if matches!(status, 401 | 403) {{\n\
+                 return Err(TransportError::Unauthorized {{ status }});\n\
+             }}\n\
+             let text = response.text().await.map_err(|e| TransportError::Http(e.to_string()))?;\n\
+             tracing::trace!(\"Response body: {{}}\", text);\n\
+             let Ok(json) = serde_json::from_str::<Value>(&text) else {{\n\
+                 let mut end = text.len().min(BODY_SNIPPET_LEN);\n\
+                 while !text.is_char_boundary(end) {{\n\
+                     end -= 1;\n\
+                 }}\n\
+                 return Err(TransportError::NonJsonResponse {{ status, body_snippet: text[..end].trim().to_string() }});\n\
+             }};\n\
+             serde_json::from_value(json).map_err(|e| TransportError::Json(e.to_string()))\n\
          }}\n"
     )
     .unwrap();
 }
 
 fn emit_error_impls(code: &mut String) {
-    for (from, variant) in
-        &[("reqwest::Error", "Http"), ("serde_json::Error", "Json"), ("anyhow::Error", "Rpc")]
-    {
+    writeln!(
+        code,
+        "impl From<reqwest::Error> for TransportError {{\n\
+             fn from(err: reqwest::Error) -> Self {{\n\
+                 if err.is_connect() {{\n\
+                     TransportError::Connect(err.to_string())\n\
+                 }} else {{\n\
+                     TransportError::Http(err.to_string())\n\
+                 }}\n\
+             }}\n\
+         }}\n"
+    )
+    .unwrap();
+    for (from, variant) in &[("serde_json::Error", "Json"), ("anyhow::Error", "Rpc")] {
         writeln!(
             code,
             "impl From<{from}> for TransportError {{\n\
@@ -81,6 +171,55 @@ fn emit_transport_trait(code: &mut String) {
     ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<Vec<Value>, TransportError>> + Send + 'a>>;
     
     fn url(&self) -> &str;
+
+    /// Return a transport that routes requests to the `/wallet/<name>` endpoint.
+    ///
+    /// Transports that cannot route per wallet return `None`.
+    fn for_wallet(&self, _wallet_name: &str) -> Option<std::sync::Arc<dyn TransportTrait>> {{
+        None
+    }}
+
+    /// Send a JSON-RPC request with `params` passed **by name** (a JSON object).
+    ///
+    /// The default implementation sends a single-element batch; transports that
+    /// route requests (e.g. per wallet) should override it.
+    fn send_request_named<'a>(
+        &'a self,
+        method: &'a str,
+        params: &'a serde_json::Map<String, Value>,
+    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<Value, TransportError>> + Send + 'a>> {{
+        Box::pin(async move {{
+            let request = serde_json::json!({{
+                \"jsonrpc\": \"2.0\", \"id\": \"1\", \"method\": method, \"params\": params
+            }});
+            let mut responses = self.send_batch(std::slice::from_ref(&request)).await?;
+            let json = responses.pop().ok_or_else(|| TransportError::Rpc(\"Empty batch response\".to_string()))?;
+            if let Some(error) = json.get(\"error\").filter(|e| !e.is_null()) {{
+                return Err(rpc_error(error));
+            }}
+            json.get(\"result\").cloned().ok_or_else(|| TransportError::Rpc(\"No result field\".to_string()))
+        }})
+    }}
+
+    /// Send a request that may legitimately block for up to `max_wait`, such as a
+    /// `getblocktemplate` long-poll, returning `Ok(None)` if it has not answered by then.
+    ///
+    /// The default implementation bounds `send_request` by `max_wait`, so a shorter
+    /// timeout inside the transport still applies; transports with a global request
+    /// timeout should override it.
+    fn send_request_long_poll<'a>(
+        &'a self,
+        method: &'a str,
+        params: &'a [Value],
+        max_wait: std::time::Duration,
+    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<Option<Value>, TransportError>> + Send + 'a>> {{
+        Box::pin(async move {{
+            match tokio::time::timeout(max_wait, self.send_request(method, params)).await {{
+                Ok(result) => result.map(Some),
+                Err(_elapsed) => Ok(None),
+            }}
+        }})
+    }}
 }}"
     )
     .unwrap();
@@ -103,7 +242,7 @@ fn emit_transport_ext_impl(code: &mut String) {
              fn call<'a, T2: serde::de::DeserializeOwned>(&'a self, method: &'a str, params: &'a [Value]) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<T2, TransportError>> + Send + 'a>> {{\n\
                  Box::pin(async move {{\n\
                      let result = self.send_request(method, params).await?;\n\
-                     Ok(serde_json::from_value(result)?)\n\
+                     decode_result(method, &result)\n\
                  }})\n\
              }}\n\
          }}\n"
@@ -120,6 +259,26 @@ fn emit_default_transport_struct(code: &mut String) {
              url: String,\n\
              auth: Option<(String, String)>,\n\
              wallet_name: Option<String>,\n\
+             max_retries: u32,\n\
+             timeout: Option<std::time::Duration>,\n\
+             method_timeouts: std::collections::HashMap<String, std::time::Duration>,\n\
+         }}\n\
+         \n\
+         /// Request timeouts a new [`DefaultTransport`] gives RPCs that routinely run for\n\
+         /// minutes, as `(method, seconds)`. Change them with `with_method_timeout`.\n\
+         pub const DEFAULT_METHOD_TIMEOUTS: &[(&str, u64)] = &[\n\
+             (\"dumptxoutset\", 3600),\n\
+             (\"gettxoutsetinfo\", 1800),\n\
+             (\"loadtxoutset\", 3600),\n\
+             (\"rescanblockchain\", 3600),\n\
+             (\"scantxoutset\", 1800),\n\
+         ];\n\
+         \n\
+         fn default_method_timeouts() -> std::collections::HashMap<String, std::time::Duration> {{\n\
+             DEFAULT_METHOD_TIMEOUTS\n\
+                 .iter()\n\
+                 .map(|(method, secs)| (method.to_string(), std::time::Duration::from_secs(*secs)))\n\
+                 .collect()\n\
          }}\n"
     )
     .unwrap();
@@ -135,14 +294,171 @@ fn emit_default_transport_impl(code: &mut String) {
                      url: url.into(),\n\
                      auth,\n\
                      wallet_name: None,\n\
+                     max_retries: 0,\n\
+                     timeout: None,\n\
+                     method_timeouts: default_method_timeouts(),\n\
                  }}\n\
              }}\n\
              \n\
+             /// Create a transport that sends through an existing `reqwest::Client`, sharing its\n\
+             /// connection pool and HTTP settings with the rest of the application.\n\
+             ///\n\
+             /// No credentials are added to requests: configure authentication on `client`\n\
+             /// (e.g. a default `Authorization` header). The client is kept as given: `with_timeout`\n\
+             /// applies to each request instead.\n\
+             pub fn from_client(client: reqwest::Client, url: impl Into<String>) -> Self {{\n\
+                 Self {{\n\
+                     client,\n\
+                     url: url.into(),\n\
+                     auth: None,\n\
+                     wallet_name: None,\n\
+                     max_retries: 0,\n\
+                     timeout: None,\n\
+                     method_timeouts: default_method_timeouts(),\n\
+                 }}\n\
+             }}\n\
+             \n\
+             /// Create a transport from a [`Config`](crate::config::Config), applying its\n\
+             /// `timeout_secs` and `max_retries` when set.\n\
+             pub fn from_config(config: &crate::config::Config) -> Self {{\n\
+                 let mut transport = Self::new(\n\
+                     config.rpc_url.clone(),\n\
+                     Some((config.rpc_user.clone(), config.rpc_password.clone())),\n\
+                 );\n\
+                 if let Some(secs) = config.timeout_secs {{\n\
+                     transport = transport.with_timeout(std::time::Duration::from_secs(secs));\n\
+                 }}\n\
+                 if let Some(retries) = config.max_retries {{\n\
+                     transport = transport.with_max_retries(retries);\n\
+                 }}\n\
+                 transport\n\
+             }}\n\
+             \n\
+             /// Fail requests that take longer than `timeout` with `TransportError::Http`.\n\
+             ///\n\
+             /// Set on each request, so the client's own settings (e.g. one passed to\n\
+             /// `from_client`) are kept. Methods with their own timeout (see\n\
+             /// `with_method_timeout`) keep it.\n\
+             pub fn with_timeout(mut self, timeout: std::time::Duration) -> Self {{\n\
+                 self.timeout = Some(timeout);\n\
+                 self\n\
+             }}\n\
+             \n\
+             /// Retry requests failing with `TransportError::Connect` up to `max_retries` times,\n\
+             /// with exponential backoff starting at 100ms.\n\
+             ///\n\
+             /// Only connection failures are retried: a request that timed out or lost its\n\
+             /// response may already have run on the node, and sending e.g. `sendtoaddress`\n\
+             /// again would pay twice. RPC error objects returned by the node are never retried.\n\
+             pub fn with_max_retries(mut self, max_retries: u32) -> Self {{\n\
+                 self.max_retries = max_retries;\n\
+                 self\n\
+             }}\n\
+             \n\
+             /// Give requests for `method` a timeout of `timeout`, in place of the client's.\n\
+             ///\n\
+             /// New transports already carry the [`DEFAULT_METHOD_TIMEOUTS`] for known-slow RPCs.\n\
+             pub fn with_method_timeout(mut self, method: impl Into<String>, timeout: std::time::Duration) -> Self {{\n\
+                 self.method_timeouts.insert(method.into(), timeout);\n\
+                 self\n\
+             }}\n\
+             \n\
              pub fn with_wallet(mut self, wallet_name: impl Into<String>) -> Self {{\n\
                  self.wallet_name = Some(wallet_name.into());\n\
                  self\n\
-             }}\n\
-         }}\n"
+             }}\n"
+    )
+    .unwrap();
+    writeln!(
+        code,
+        "
+    /// POST a prepared JSON-RPC request, retrying connection failures up to `max_retries` times.
+    ///
+    /// Each attempt uses the timeout set for the request's method, else the `with_timeout` one.
+    async fn dispatch(&self, request: &Value) -> Result<Value, TransportError> {{
+        let timeout = request[\"method\"]
+            .as_str()
+            .and_then(|method| self.method_timeouts.get(method))
+            .copied()
+            .or(self.timeout);
+        let mut delay = std::time::Duration::from_millis(100);
+        let mut retries = 0;
+        loop {{
+            match self.dispatch_once(request, timeout).await {{
+                Err(TransportError::Connect(e)) if retries < self.max_retries => {{
+                    retries += 1;
+                    tracing::debug!(\"Retrying request ({{}}/{{}}) after connection error: {{}}\", retries, self.max_retries, e);
+                    tokio::time::sleep(delay).await;
+                    delay *= 2;
+                }}
+                result => return result,
+            }}
+        }}
+    }}
+
+    /// POST `request` to `url` with this transport's credentials.
+    ///
+    /// `timeout` overrides the client's own request timeout, if it has one.
+    fn post(&self, url: &str, request: &Value, timeout: Option<std::time::Duration>) -> reqwest::RequestBuilder {{
+        let mut req = self.client.post(url).json(request);
+        if let Some((username, password)) = &self.auth {{
+            req = req.basic_auth(username, Some(password));
+        }}
+        if let Some(timeout) = timeout {{
+            req = req.timeout(timeout);
+        }}
+        req
+    }}
+
+    /// POST a prepared JSON-RPC request, preferring the wallet endpoint if one is configured.
+    async fn dispatch_once(&self, request: &Value, timeout: Option<std::time::Duration>) -> Result<Value, TransportError> {{
+        tracing::debug!(\"Sending request to {{}}\", self.url);
+
+        // If a wallet is configured, prefer wallet endpoint; fallback to base URL on -32601 (method not found)
+        if let Some(wallet) = &self.wallet_name {{
+            let wallet_url = format!(\"{{}}/wallet/{{}}\", self.url.trim_end_matches('/'), encode_wallet_name(wallet));
+
+            // Try wallet endpoint first
+            let response = match self.post(&wallet_url, request, timeout).send().await {{
+                Ok(resp) => {{ tracing::debug!(\"Response status: {{}}\", resp.status()); resp }}
+                Err(e) => return Err(e.into()),
+            }};
+
+            let json: Value = read_json(response).await?;
+
+            if let Some(error) = json.get(\"error\") {{
+                // Fallback only for -32601 (Method not found)
+                if rpc_error(error).rpc_code() == Some(-32601) {{
+                    let response = match self.post(&self.url, request, timeout).send().await {{
+                        Ok(resp) => {{ tracing::debug!(\"Base response status: {{}}\", resp.status()); resp }}
+                        Err(e) => return Err(e.into()),
+                    }};
+                    let json: Value = read_json(response).await?;
+                    if let Some(error) = json.get(\"error\") {{
+                        return Err(rpc_error(error));
+                    }}
+                    return json.get(\"result\").cloned().ok_or_else(|| TransportError::Rpc(\"No result field\".to_string()));
+                }} else {{
+                    return Err(rpc_error(error));
+                }}
+            }}
+
+            return json.get(\"result\").cloned().ok_or_else(|| TransportError::Rpc(\"No result field\".to_string()));
+        }}
+
+        // No wallet configured → base URL
+        let response = match self.post(&self.url, request, timeout).send().await {{
+            Ok(resp) => {{ tracing::debug!(\"Response status: {{}}\", resp.status()); resp }},
+            Err(e) => return Err(e.into()),
+        }};
+        let json: Value = read_json(response).await?;
+        if let Some(error) = json.get(\"error\") {{
+            return Err(rpc_error(error));
+        }}
+        json.get(\"result\").cloned().ok_or_else(|| TransportError::Rpc(\"No result field\".to_string()))
+    }}
+}}
+"
     )
     .unwrap();
 }
@@ -152,76 +468,48 @@ fn emit_transport_impl(code: &mut String) {
         code,
         "impl TransportTrait for DefaultTransport {{
     fn send_request<'a>(&'a self, method: &'a str, params: &'a [Value]) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<Value, TransportError>> + Send + 'a>> {{
-        let client = self.client.clone();
-        let url = self.url.clone();
-        let auth = self.auth.clone();
-        let wallet_name = self.wallet_name.clone();
         Box::pin(async move {{
             let request = serde_json::json!({{
                 \"jsonrpc\": \"2.0\", \"id\": \"1\", \"method\": method, \"params\": params
             }});
-            eprintln!(\"[debug] Sending request to {{}}\", url);
-
-            // If a wallet is configured, prefer wallet endpoint; fallback to base URL on -32601 (method not found)
-            if let Some(wallet) = &wallet_name {{
-                let wallet_url = format!(\"{{}}/wallet/{{}}\", url.trim_end_matches('/'), wallet);
-
-                // Try wallet endpoint first
-                let mut req = client.post(&wallet_url).json(&request);
-                if let Some((username, password)) = &auth {{
-                    req = req.basic_auth(username, Some(password));
-                }}
-                let response = match req.send().await {{
-                    Ok(resp) => {{ eprintln!(\"[debug] Response status: {{}}\", resp.status()); resp }}
-                    Err(e) => return Err(TransportError::Http(e.to_string())),
-                }};
-
-                let text = response.text().await.map_err(|e| TransportError::Http(e.to_string()))?;
-                eprintln!(\"[debug] Response body: {{}}\", text);
-                let json: Value = serde_json::from_str(&text).map_err(|e| TransportError::Json(e.to_string()))?;
+            self.dispatch(&request).await
+        }})
+    }}
 
-                if let Some(error) = json.get(\"error\") {{
-                    // Fallback only for -32601 (Method not found)
-                    if error.get(\"code\").and_then(|c| c.as_i64()) == Some(-32601) {{
-                        let mut req = client.post(&url).json(&request);
-                        if let Some((username, password)) = &auth {{
-                            req = req.basic_auth(username, Some(password));
-                        }}
-                        let response = match req.send().await {{
-                            Ok(resp) => {{ eprintln!(\"[debug] Base response status: {{}}\", resp.status()); resp }}
-                            Err(e) => return Err(TransportError::Http(e.to_string())),
-                        }};
-                        let text = response.text().await.map_err(|e| TransportError::Http(e.to_string()))?;
-                        eprintln!(\"[debug] Base response body: {{}}\", text);
-                        let json: Value = serde_json::from_str(&text).map_err(|e| TransportError::Json(e.to_string()))?;
-                        if let Some(error) = json.get(\"error\") {{
-                            return Err(TransportError::Rpc(error.to_string()));
-                        }}
-                        return json.get(\"result\").cloned().ok_or_else(|| TransportError::Rpc(\"No result field\".to_string()));
-                    }} else {{
-                        return Err(TransportError::Rpc(error.to_string()));
-                    }}
-                }}
+    fn send_request_named<'a>(
+        &'a self,
+        method: &'a str,
+        params: &'a serde_json::Map<String, Value>,
+    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<Value, TransportError>> + Send + 'a>> {{
+        Box::pin(async move {{
+            let request = serde_json::json!({{
+                \"jsonrpc\": \"2.0\", \"id\": \"1\", \"method\": method, \"params\": params
+            }});
+            self.dispatch(&request).await
+        }})
+    }}
 
-                return json.get(\"result\").cloned().ok_or_else(|| TransportError::Rpc(\"No result field\".to_string()));
-            }}
+    fn for_wallet(&self, wallet_name: &str) -> Option<std::sync::Arc<dyn TransportTrait>> {{
+        Some(std::sync::Arc::new(self.clone().with_wallet(wallet_name)))
+    }}
 
-            // No wallet configured → base URL
-            let mut req = client.post(&url).json(&request);
-            if let Some((username, password)) = &auth {{
-                req = req.basic_auth(username, Some(password));
-            }}
-            let response = match req.send().await {{
-                Ok(resp) => {{ eprintln!(\"[debug] Response status: {{}}\", resp.status()); resp }},
-                Err(e) => return Err(TransportError::Http(e.to_string())),
-            }};
-            let text = response.text().await.map_err(|e| TransportError::Http(e.to_string()))?;
-            eprintln!(\"[debug] Response body: {{}}\", text);
-            let json: Value = serde_json::from_str(&text).map_err(|e| TransportError::Json(e.to_string()))?;
-            if let Some(error) = json.get(\"error\") {{
-                return Err(TransportError::Rpc(error.to_string()));
+    /// Long-polls are sent once, without retries, and outlive the `with_timeout` limit.
+    fn send_request_long_poll<'a>(
+        &'a self,
+        method: &'a str,
+        params: &'a [Value],
+        max_wait: std::time::Duration,
+    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<Option<Value>, TransportError>> + Send + 'a>> {{
+        Box::pin(async move {{
+            let request = serde_json::json!({{
+                \"jsonrpc\": \"2.0\", \"id\": \"1\", \"method\": method, \"params\": params
+            }});
+            // The HTTP timeout is a backstop: `max_wait` elapsing is reported as `None`
+            let http_timeout = max_wait + std::time::Duration::from_secs(5);
+            match tokio::time::timeout(max_wait, self.dispatch_once(&request, Some(http_timeout))).await {{
+                Ok(result) => result.map(Some),
+                Err(_elapsed) => Ok(None),
             }}
-            json.get(\"result\").cloned().ok_or_else(|| TransportError::Rpc(\"No result field\".to_string()))
         }})
     }}
     
@@ -232,20 +520,22 @@ fn emit_transport_impl(code: &mut String) {
         let client = self.client.clone();
         let url = self.url.clone();
         let auth = self.auth.clone();
+        let timeout = self.timeout;
         Box::pin(async move {{
-            eprintln!(\"[debug] Sending batch request to {{}}: {{:?}}\", url, bodies);
+            tracing::debug!(\"Sending batch of {{}} requests to {{}}\", bodies.len(), url);
+            tracing::trace!(\"Batch request bodies: {{:?}}\", bodies);
             let mut req = client.post(&url).json(bodies);
             if let Some((username, password)) = &auth {{
                 req = req.basic_auth(username, Some(password));
             }}
+            if let Some(timeout) = timeout {{
+                req = req.timeout(timeout);
+            }}
             let response = match req.send().await {{
-                Ok(resp) => {{ eprintln!(\"[debug] Batch response status: {{}}\", resp.status()); resp }},
-                Err(e) => return Err(TransportError::Http(e.to_string())),
+                Ok(resp) => {{ tracing::debug!(\"Batch response status: {{}}\", resp.status()); resp }},
+                Err(e) => return Err(e.into()),
             }};
-            let text = response.text().await.map_err(|e| TransportError::Http(e.to_string()))?;
-            eprintln!(\"[debug] Batch response body: {{}}\", text);
-            let v: Vec<Value> = serde_json::from_str(&text).map_err(|e| TransportError::Json(e.to_string()))?;
-            Ok(v)
+            read_json(response).await
         }})
     }}
     
@@ -256,3 +546,22 @@ fn emit_transport_impl(code: &mut String) {
     )
     .unwrap();
 }
+
+fn emit_wallet_name_encoder(code: &mut String) {
+    writeln!(
+        code,
+        "
+/// Percent-encode a wallet name for use as a single URL path segment.
+fn encode_wallet_name(name: &str) -> String {{
+    let mut encoded = String::with_capacity(name.len());
+    for byte in name.bytes() {{
+        match byte {{
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!(\"%{{byte:02X}}\")),
+        }}
+    }}
+    encoded
+}}"
+    )
+    .unwrap();
+}
@@ -0,0 +1,382 @@
+//! Extensible front for `bitcoin_rpc_conversions::TypeRegistry`.
+//!
+//! The built-in category rules live in the conversions crate as a fixed table. This module
+//! lets callers layer their own rules on top, and every generator maps types through the
+//! registry [`install`](TypeRegistry::install)ed for the process (the built-ins by default),
+//! so argument types, response fields and test-node helpers always agree.
+
+use std::borrow::Cow;
+use std::sync::OnceLock;
+
+pub use bitcoin_rpc_conversions::BitcoinRpcType;
+use bitcoin_rpc_conversions::TypeRegistry as BuiltinRegistry;
+use bitcoin_rpc_types::{BtcArgument, BtcResult};
+
+/// A user-supplied category rule, matched the same way as the built-in ones.
+#[derive(Debug, Clone)]
+struct TypeRule {
+    rpc_type: String,
+    pattern: Option<String>,
+    category: BitcoinRpcType,
+    exact: bool,
+}
+
+/// Maps RPC types to Rust types using the built-in rules plus optional user rules.
+///
+/// User rules take precedence over the built-ins:
+/// - among user pattern rules the longest matching pattern wins, as in the built-in table;
+/// - a matching user pattern rule beats any built-in rule;
+/// - otherwise a user catch-all (`pattern: None`) for the RPC type applies, replacing all
+///   built-in rules for that type;
+/// - otherwise the built-in rules decide.
+#[derive(Debug, Clone, Default)]
+pub struct TypeRegistry {
+    rules: Vec<TypeRule>,
+}
+
+/// Builder for a [`TypeRegistry`] with user rules.
+#[derive(Debug, Default)]
+pub struct TypeRegistryBuilder {
+    rules: Vec<TypeRule>,
+}
+
+impl TypeRegistryBuilder {
+    /// Add a rule mapping fields of `rpc_type` (or `"*"` for any type) whose normalized name
+    /// contains (or, with `exact`, equals) `pattern` to `category`.
+    ///
+    /// A `None` pattern makes the rule the catch-all for `rpc_type`.
+    pub fn rule(
+        mut self,
+        rpc_type: &str,
+        pattern: Option<&str>,
+        category: BitcoinRpcType,
+        exact: bool,
+    ) -> Self {
+        self.rules.push(TypeRule {
+            rpc_type: rpc_type.to_string(),
+            pattern: pattern.map(str::to_string),
+            category,
+            exact,
+        });
+        self
+    }
+
+    /// Finish building the registry.
+    pub fn build(self) -> TypeRegistry { TypeRegistry { rules: self.rules } }
+}
+
+/// Rules this crate adds on top of the built-in table, as `(rpc_type, pattern, category)`.
+///
+/// Unnamed array elements are categorized by their description, e.g. the
+/// `"The transaction id"` elements of `getrawmempool`.
+const EXTRA_RULES: &[(&str, &str, BitcoinRpcType)] =
+    &[("hex", "transactionid", BitcoinRpcType::BitcoinTxid)];
+
+/// Arguments typed by their exact (normalized) name rather than by a category, as
+/// `(name, Rust type)`, for types the categories have no variant for.
+///
+/// `hash_or_height` takes either a block hash or a height, so no single category fits it;
+/// `address_type` and `estimate_mode` are strings limited to the few values of their enums;
+/// a `descriptor` string is checked for descriptor syntax before it is sent.
+///
+/// Only top-level arguments are typed. The descriptors `importdescriptors` takes in the
+/// `desc` field of its `requests`, and `scantxoutset` in its `scanobjects`, are elements
+/// of untyped `object`/`array` arguments and stay plain strings.
+const NAMED_ARGUMENT_TYPES: &[(&str, &str)] = &[
+    ("hashorheight", "HashOrHeight"),
+    ("addresstype", "AddressType"),
+    ("estimatemode", "EstimateMode"),
+    ("descriptor", "Descriptor"),
+];
+
+/// Where generated code imports each of the [`NAMED_ARGUMENT_TYPES`] from, as
+/// `(Rust type, path)`.
+pub const NAMED_ARGUMENT_IMPORTS: &[(&str, &str)] = &[
+    ("HashOrHeight", "bitcoin_rpc_types::HashOrHeight"),
+    ("AddressType", "crate::transport::AddressType"),
+    ("EstimateMode", "crate::transport::EstimateMode"),
+    ("Descriptor", "crate::transport::Descriptor"),
+];
+
+/// Argument types that generated methods take as `impl Into<..>` when required, so
+/// callers can pass anything converting into them (e.g. a height or a block hash).
+pub const INTO_ARGUMENT_TYPES: &[&str] = &["HashOrHeight"];
+
+static ACTIVE: OnceLock<TypeRegistry> = OnceLock::new();
+
+impl TypeRegistry {
+    /// A registry with only the built-in rules.
+    pub fn new() -> Self { Self::default() }
+
+    /// Start building a registry with user rules.
+    pub fn builder() -> TypeRegistryBuilder { TypeRegistryBuilder::default() }
+
+    /// Make this registry the one used by all generators in this process.
+    ///
+    /// Must be called before generating; fails (returning the registry) if a registry was
+    /// already installed or the default was already used.
+    pub fn install(self) -> Result<(), TypeRegistry> { ACTIVE.set(self) }
+
+    /// The registry used by the generators.
+    pub fn active() -> &'static TypeRegistry { ACTIVE.get_or_init(TypeRegistry::new) }
+
+    /// Categorize an RPC type based on its JSON schema type and field name.
+    pub fn categorize(&self, rpc_type: &str, field: &str) -> BitcoinRpcType {
+        let rpc_type = canonical_rpc_type(rpc_type);
+        self.user_category(rpc_type, field)
+            .unwrap_or_else(|| BuiltinRegistry::categorize(rpc_type, field))
+    }
+
+    /// Maps an argument to its Rust type and whether it is optional.
+    pub fn map_argument_type(&self, arg: &BtcArgument) -> (&'static str, bool) {
+        let arg = match canonical_rpc_type(&arg.type_) {
+            canonical if canonical != arg.type_ =>
+                Cow::Owned(BtcArgument { type_: canonical.to_string(), ..arg.clone() }),
+            _ => Cow::Borrowed(arg),
+        };
+        if let Some(category) = self.user_category(&arg.type_, &arg.names[0]) {
+            return (category.to_rust_type(), category.is_optional_by_default() || !arg.required);
+        }
+        let name = normalize(&arg.names[0]);
+        match NAMED_ARGUMENT_TYPES.iter().find(|(pattern, _)| *pattern == name) {
+            Some((_, ty)) => (ty, !arg.required),
+            None => BuiltinRegistry::map_argument_type(&arg),
+        }
+    }
+
+    /// Maps a result field to its Rust type and whether it is optional.
+    pub fn map_result_type(&self, result: &BtcResult) -> (&'static str, bool) {
+        let result = match canonical_rpc_type(&result.type_) {
+            canonical if canonical != result.type_ =>
+                Cow::Owned(BtcResult { type_: canonical.to_string(), ..result.clone() }),
+            _ => Cow::Borrowed(result),
+        };
+        // Use description as fallback when key_name is empty, like the built-in mapping
+        let name = if result.key_name.is_empty() { &result.description } else { &result.key_name };
+        match self.user_category(&result.type_, name) {
+            Some(category) =>
+                (category.to_rust_type(), category.is_optional_by_default() || !result.required()),
+            None => BuiltinRegistry::map_result_type(&result),
+        }
+    }
+
+    /// The category chosen by a user rule or [`EXTRA_RULES`], or `None` to defer to the
+    /// built-ins.
+    fn user_category(&self, rpc_type: &str, field: &str) -> Option<BitcoinRpcType> {
+        self.user_rule_category(rpc_type, field).or_else(|| {
+            let field_norm = normalize(field);
+            EXTRA_RULES
+                .iter()
+                .find(|(ty, pattern, _)| *ty == rpc_type && field_norm.contains(pattern))
+                .map(|(_, _, category)| *category)
+        })
+    }
+
+    /// The category chosen by a user rule.
+    fn user_rule_category(&self, rpc_type: &str, field: &str) -> Option<BitcoinRpcType> {
+        let applies = |rule: &&TypeRule| rule.rpc_type == "*" || rule.rpc_type == rpc_type;
+
+        let field_norm = normalize(field);
+        let best_pattern = self
+            .rules
+            .iter()
+            .filter(applies)
+            .filter_map(|rule| {
+                let pat_norm = normalize(rule.pattern.as_deref()?);
+                let matches = if rule.exact {
+                    field_norm == pat_norm
+                } else {
+                    field_norm.contains(&pat_norm)
+                };
+                matches.then_some((pat_norm.len(), rule.category))
+            })
+            // Longest pattern wins; the first rule wins ties
+            .rev()
+            .max_by_key(|(len, _)| *len);
+        if let Some((_, category)) = best_pattern {
+            return Some(category);
+        }
+
+        self.rules
+            .iter()
+            .filter(applies)
+            .find(|rule| rule.pattern.is_none())
+            .map(|rule| rule.category)
+    }
+}
+
+/// [`TypeRegistry::map_argument_type`] on the [active](TypeRegistry::active) registry.
+pub fn map_argument_type(arg: &BtcArgument) -> (&'static str, bool) {
+    TypeRegistry::active().map_argument_type(arg)
+}
+
+/// The import paths of the [`NAMED_ARGUMENT_TYPES`] among `types`, in
+/// [`NAMED_ARGUMENT_IMPORTS`] order.
+pub fn named_argument_imports<'a>(types: impl IntoIterator<Item = &'a str>) -> Vec<&'static str> {
+    let types = types.into_iter().collect::<Vec<_>>();
+    NAMED_ARGUMENT_IMPORTS
+        .iter()
+        .filter(|(ty, _)| types.contains(ty))
+        .map(|(_, path)| *path)
+        .collect()
+}
+
+/// The type of a generated method's parameter for a required argument of Rust type `ty`:
+/// `impl Into<ty>` for the [`INTO_ARGUMENT_TYPES`], else `ty` itself.
+pub fn parameter_type(ty: &str) -> String {
+    if INTO_ARGUMENT_TYPES.contains(&ty) {
+        format!("impl Into<{ty}>")
+    } else {
+        ty.to_string()
+    }
+}
+
+/// The expression converting the parameter `name` declared by [`parameter_type`] into `ty`.
+pub fn parameter_value(ty: &str, name: &str) -> String {
+    if INTO_ARGUMENT_TYPES.contains(&ty) {
+        format!("Into::<{ty}>::into({name})")
+    } else {
+        name.to_string()
+    }
+}
+
+/// [`TypeRegistry::map_result_type`] on the [active](TypeRegistry::active) registry.
+pub fn map_result_type(result: &BtcResult) -> (&'static str, bool) {
+    TypeRegistry::active().map_result_type(result)
+}
+
+/// The RPC type whose rules apply to `rpc_type`.
+///
+/// Bitcoin Core documents some fields as `timestamp` and others with the same name
+/// (`time`, `mediantime`, `locktime`, ...) as `number`. Both are JSON numbers, so a
+/// `timestamp` field is mapped exactly like a `number` field of the same name, giving
+/// each field name a single Rust type wherever it appears.
+fn canonical_rpc_type(rpc_type: &str) -> &str {
+    match rpc_type {
+        "timestamp" => "number",
+        other => other,
+    }
+}
+
+/// Normalizes names by lowercasing and stripping `_`, `-`, and spaces.
+fn normalize(name: &str) -> String {
+    name.chars().filter(|c| !matches!(c, '_' | '-' | ' ')).flat_map(|c| c.to_lowercase()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_rule_overrides_number_catch_all() {
+        let builtin = TypeRegistry::new();
+        assert_eq!(builtin.categorize("number", "weight"), BitcoinRpcType::LargeInteger);
+
+        let registry = TypeRegistry::builder()
+            .rule("number", Some("weight"), BitcoinRpcType::SmallInteger, false)
+            .build();
+        assert_eq!(registry.categorize("number", "weight"), BitcoinRpcType::SmallInteger);
+        assert_eq!(registry.categorize("number", "block_weight"), BitcoinRpcType::SmallInteger);
+        // Unrelated fields keep the built-in mapping
+        assert_eq!(registry.categorize("number", "size"), builtin.categorize("number", "size"));
+    }
+
+    #[test]
+    fn test_timestamp_fields_map_like_number_fields() {
+        let registry = TypeRegistry::new();
+        for field in ["time", "mediantime", "blocktime", "locktime", "timereceived"] {
+            let number =
+                BtcResult { type_: "number".into(), key_name: field.into(), ..Default::default() };
+            let timestamp = BtcResult { type_: "timestamp".into(), ..number.clone() };
+            assert_eq!(
+                registry.map_result_type(&timestamp),
+                registry.map_result_type(&number),
+                "{field}"
+            );
+
+            let argument = BtcArgument {
+                names: vec![field.into()],
+                description: String::new(),
+                oneline_description: String::new(),
+                also_positional: false,
+                type_str: None,
+                required: true,
+                hidden: false,
+                type_: "timestamp".into(),
+            };
+            assert_eq!(registry.map_argument_type(&argument), registry.map_result_type(&number));
+        }
+        assert_eq!(
+            registry.map_result_type(&BtcResult {
+                type_: "timestamp".into(),
+                key_name: "time".into(),
+                ..Default::default()
+            }),
+            ("u64", false)
+        );
+    }
+
+    #[test]
+    fn test_hash_or_height_arguments_take_either() {
+        let registry = TypeRegistry::new();
+        let argument = |required| BtcArgument {
+            names: vec!["hash_or_height".into()],
+            description: String::new(),
+            oneline_description: String::new(),
+            also_positional: false,
+            type_str: None,
+            required,
+            hidden: false,
+            type_: "number".into(),
+        };
+        assert_eq!(registry.map_argument_type(&argument(true)), ("HashOrHeight", false));
+        assert_eq!(registry.map_argument_type(&argument(false)), ("HashOrHeight", true));
+
+        assert_eq!(parameter_type("HashOrHeight"), "impl Into<HashOrHeight>");
+        assert_eq!(parameter_value("HashOrHeight", "x"), "Into::<HashOrHeight>::into(x)");
+        assert_eq!(parameter_type("u64"), "u64");
+        assert_eq!(parameter_value("u64", "x"), "x");
+    }
+
+    #[test]
+    fn test_named_string_arguments_are_typed() {
+        let argument = |name: &str| BtcArgument {
+            names: vec![name.into()],
+            description: String::new(),
+            oneline_description: String::new(),
+            also_positional: false,
+            type_str: None,
+            required: false,
+            hidden: false,
+            type_: "string".into(),
+        };
+        let registry = TypeRegistry::new();
+        assert_eq!(registry.map_argument_type(&argument("address_type")), ("AddressType", true));
+        assert_eq!(registry.map_argument_type(&argument("estimate_mode")), ("EstimateMode", true));
+        assert_eq!(registry.map_argument_type(&argument("descriptor")), ("Descriptor", true));
+        // Only the exact name: a `descriptors` array keeps its category
+        assert_ne!(registry.map_argument_type(&argument("descriptors")).0, "Descriptor");
+        assert_eq!(parameter_type("AddressType"), "AddressType");
+        assert_eq!(
+            named_argument_imports(["String", "AddressType", "HashOrHeight"]),
+            ["bitcoin_rpc_types::HashOrHeight", "crate::transport::AddressType"]
+        );
+    }
+
+    #[test]
+    fn test_user_rules_merge_with_builtins() {
+        let registry = TypeRegistry::builder()
+            .rule("number", None, BitcoinRpcType::Float, false)
+            .rule("number", Some("fee"), BitcoinRpcType::LargeInteger, false)
+            .rule("number", Some("feerate"), BitcoinRpcType::Float, true)
+            .build();
+
+        // The user catch-all replaces the built-in rules for `number` ...
+        assert_eq!(registry.categorize("number", "height"), BitcoinRpcType::Float);
+        // ... but not for other RPC types
+        assert_eq!(registry.categorize("string", "txid"), BitcoinRpcType::BitcoinTxid);
+        // Longest user pattern wins
+        assert_eq!(registry.categorize("number", "fee_rate"), BitcoinRpcType::Float);
+        assert_eq!(registry.categorize("number", "basefee"), BitcoinRpcType::LargeInteger);
+    }
+}
@@ -1,8 +1,9 @@
 // codegen/src/utils.rs
 
-use bitcoin_rpc_conversions::TypeRegistry;
 use bitcoin_rpc_types::BtcArgument;
 
+use crate::type_registry;
+
 /// Converts a camelCase string to snake_case
 pub fn camel_to_snake_case(s: &str) -> String {
     // This requires special handling because otherwise, it would be converted to "script_pub_key".
@@ -37,6 +38,36 @@ pub fn capitalize(s: &str) -> String {
         .collect::<String>()
 }
 
+/// Rust keywords (strict and reserved, 2021 edition) that cannot be used as plain identifiers
+const RUST_KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl",
+    "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "Self", "static", "struct", "super", "trait", "true", "try", "type",
+    "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// Keywords that cannot be raw identifiers either, so they get a trailing `_` instead
+const NON_RAW_KEYWORDS: &[&str] = &["crate", "self", "Self", "super"];
+
+/// Turns a schema name (a method, argument or field) into a valid Rust identifier
+///
+/// Keywords become raw identifiers (`type` → `r#type`), except those Rust does not allow
+/// as raw identifiers, which get a trailing underscore (`self` → `self_`). Names starting
+/// with a digit get a leading underscore (`10th_percentile` → `_10th_percentile`). Any
+/// case conversion is up to the caller and happens before this.
+pub fn sanitize_ident(name: &str) -> String {
+    if NON_RAW_KEYWORDS.contains(&name) {
+        format!("{name}_")
+    } else if RUST_KEYWORDS.contains(&name) {
+        format!("r#{name}")
+    } else if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{name}")
+    } else {
+        name.to_string()
+    }
+}
+
 /// Determines the appropriate Rust type for a given API argument.
 ///
 /// This function takes the parameter name and its API type as input,
@@ -51,7 +82,7 @@ pub fn capitalize(s: &str) -> String {
 /// # Returns
 /// A `String` representing the Rust type for the argument, possibly wrapped in `Option<>`.
 pub fn rust_type_for_argument(param_name: &str, api_ty: &str) -> String {
-    let (base_ty, is_option) = TypeRegistry::map_argument_type(&BtcArgument {
+    let (base_ty, is_option) = type_registry::map_argument_type(&BtcArgument {
         type_: api_ty.to_string(),
         names: vec![param_name.to_string()],
         type_str: None,
@@ -136,3 +167,21 @@ pub fn reorder_arguments_for_rust_signature(
 
     (reordered_args, mapping)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_ident() {
+        assert_eq!(sanitize_ident("type"), "r#type");
+        assert_eq!(sanitize_ident("fn"), "r#fn");
+        assert_eq!(sanitize_ident("match"), "r#match");
+        assert_eq!(sanitize_ident("self"), "self_");
+        assert_eq!(sanitize_ident("10th_percentile_feerate"), "_10th_percentile_feerate");
+        assert_eq!(sanitize_ident("getblock"), "getblock");
+        // Only whole names are keywords
+        assert_eq!(sanitize_ident("typed"), "typed");
+        assert_eq!(sanitize_ident("_type"), "_type");
+    }
+}
@@ -7,31 +7,55 @@ use thiserror::Error;
 pub const BUILD_VERSION: u32 = 0;
 
 /// Bitcoin Core version representation
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+///
+/// Versions are ordered by `(major, minor, patch)`, so generators can gate methods and
+/// fields with [`supports`](Version::supports) and [`in_range`](Version::in_range).
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Version {
     major: u32,
     minor: u32,
+    patch: u32,
 
     version_string: String,
 }
 
 impl Version {
     /// Construct a new Version from `major.minor`.
-    pub fn new(major: u32, minor: u32) -> Self {
-        let version_string =
-            if minor == 0 { format!("v{}", major) } else { format!("v{}.{}", major, minor) };
-        Self { major, minor, version_string }
+    pub fn new(major: u32, minor: u32) -> Self { Self::with_patch(major, minor, 0) }
+
+    /// Construct a new Version from `major.minor.patch`.
+    pub fn with_patch(major: u32, minor: u32, patch: u32) -> Self {
+        let version_string = format!("v{}", Self::join(major, minor, patch, "."));
+        Self { major, minor, patch, version_string }
     }
 
-    /// Parse strings like "v29.1" or "29.1" into a Version.
+    /// The non-zero-trailing components joined by `sep`, e.g. `29`, `29.1` or `29.0.1`.
+    fn join(major: u32, minor: u32, patch: u32, sep: &str) -> String {
+        match (minor, patch) {
+            (0, 0) => format!("{major}"),
+            (_, 0) => format!("{major}{sep}{minor}"),
+            _ => format!("{major}{sep}{minor}{sep}{patch}"),
+        }
+    }
+
+    /// Parse strings like "v29.1", "29.10" or "v27.1.0" into a Version.
+    ///
+    /// A zero patch is dropped, so "v27.1.0" and "v27.1" are the same version.
     pub fn from_string(s: &str) -> Result<Self, VersionError> {
         let s = s.trim_start_matches('v');
         let parts: Vec<&str> = s.split('.').collect();
 
-        if parts.is_empty() || parts.len() > 2 {
+        if parts.is_empty() || parts.len() > 3 {
             return Err(VersionError::ParseError(format!("Invalid version format: '{}'", s)));
         }
 
+        let patch = match parts.get(2) {
+            Some(patch) => patch
+                .parse::<u32>()
+                .map_err(|_| VersionError::ParseError(format!("Invalid patch '{}'", patch)))?,
+            None => 0,
+        };
+
         let major = parts[0]
             .parse::<u32>()
             .map_err(|_| VersionError::ParseError(format!("Invalid major '{}'", parts[0])))?;
@@ -44,44 +68,47 @@ impl Version {
             0
         };
 
-        Ok(Self::new(major, minor))
+        Ok(Self::with_patch(major, minor, patch))
     }
 
     /// Return the major component.
     pub fn major(&self) -> u32 { self.major }
     /// Return the minor component.
     pub fn minor(&self) -> u32 { self.minor }
+    /// Return the patch component.
+    pub fn patch(&self) -> u32 { self.patch }
 
-    /// Return the pretty string form (e.g., "v29.1").
+    /// Return the pretty string form (e.g., "v29.1" or "v29.0.1").
     pub fn as_str(&self) -> &str { &self.version_string }
-    /// Return the module name form (e.g., "v29_1").
-    pub fn as_module_name(&self) -> String {
-        if self.minor == 0 {
-            format!("v{}", self.major)
-        } else {
-            format!("v{}_{}", self.major, self.minor)
-        }
-    }
-    /// Return the documentation label (e.g., "29.1").
-    pub fn as_doc_version(&self) -> String {
-        if self.minor == 0 {
-            format!("{}", self.major)
-        } else {
-            format!("{}.{}", self.major, self.minor)
-        }
-    }
-    /// Return a numeric-ish form without the leading 'v' (e.g., "29_1").
-    pub fn as_number(&self) -> String {
-        if self.minor == 0 {
-            format!("{}", self.major)
-        } else {
-            format!("{}_{}", self.major, self.minor)
-        }
-    }
+    /// Return the module name form (e.g., "v29_1" or "v29_0_1").
+    pub fn as_module_name(&self) -> String { format!("v{}", self.as_number()) }
+    /// Return the documentation label (e.g., "29.1" or "29.0.1").
+    pub fn as_doc_version(&self) -> String { Self::join(self.major, self.minor, self.patch, ".") }
+    /// Return a numeric-ish form without the leading 'v' (e.g., "29_1" or "29_0_1").
+    pub fn as_number(&self) -> String { Self::join(self.major, self.minor, self.patch, "_") }
+
+    /// Whether this version is `min` or newer, i.e. supports what `min` introduced.
+    pub fn supports(&self, min: &Version) -> bool { self >= min }
+
+    /// Whether `lo <= self < hi`, e.g. for a feature added in `lo` and removed in `hi`.
+    pub fn in_range(&self, lo: &Version, hi: &Version) -> bool { lo <= self && self < hi }
 
     /// Compose crate version (e.g., "29.1.1") with midas build number
+    ///
+    /// Core's patch release takes the hundreds of the last component, so a crate for
+    /// v29.0.1 (`29.0.100`) is distinct from, and newer than, one for v29 (`29.0.0`).
     pub fn crate_version(&self) -> String {
-        format!("{}.{}.{}", self.major, self.minor, BUILD_VERSION)
+        format!("{}.{}.{}", self.major, self.minor, self.patch * 100 + BUILD_VERSION)
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> { Some(self.cmp(other)) }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (self.major, self.minor, self.patch).cmp(&(other.major, other.minor, other.patch))
     }
 }
 
@@ -92,3 +119,67 @@ pub enum VersionError {
     #[error("Failed to parse version: {0}")]
     ParseError(String),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_string_accepts_multi_digit_minor_and_patch() {
+        let v = Version::from_string("v29.10").unwrap();
+        assert_eq!((v.major(), v.minor()), (29, 10));
+        assert_eq!(v.as_module_name(), "v29_10");
+
+        let v = Version::from_string("27.1.0").unwrap();
+        assert_eq!((v.major(), v.minor()), (27, 1));
+        assert_eq!(v.as_str(), "v27.1");
+
+        let v = Version::from_string("v29.0.1").unwrap();
+        assert_eq!((v.major(), v.minor(), v.patch()), (29, 0, 1));
+        assert_eq!(v.as_str(), "v29.0.1");
+        assert_eq!(v.as_module_name(), "v29_0_1");
+        assert_eq!(v.as_doc_version(), "29.0.1");
+
+        let v = Version::from_string("v30").unwrap();
+        assert_eq!((v.major(), v.minor()), (30, 0));
+    }
+
+    #[test]
+    fn test_ordering_and_ranges() {
+        let v = |s| Version::from_string(s).unwrap();
+
+        assert!(v("v28") < v("v29"));
+        assert!(v("v29.1") > v("v29.0"));
+        assert!(v("v29.10") > v("v29.9"));
+        assert_eq!(v("v29.1.0").cmp(&v("v29.1")), std::cmp::Ordering::Equal);
+        assert!(v("v29.0.1") < v("v29.1"));
+        assert!(v("v29.0.1") > v("v29"));
+
+        assert!(v("v29.1").supports(&v("v25")));
+        assert!(v("v25").supports(&v("v25")));
+        assert!(!v("v24.2").supports(&v("v25")));
+
+        assert!(v("v26").in_range(&v("v25"), &v("v28")));
+        assert!(v("v25").in_range(&v("v25"), &v("v28")));
+        assert!(!v("v28").in_range(&v("v25"), &v("v28")));
+        assert!(!v("v24").in_range(&v("v25"), &v("v28")));
+    }
+
+    #[test]
+    fn test_crate_versions_distinguish_patch_releases() {
+        let v = |s| Version::from_string(s).unwrap().crate_version();
+
+        assert_eq!(v("v29"), format!("29.0.{BUILD_VERSION}"));
+        assert_eq!(v("v29.1"), format!("29.1.{BUILD_VERSION}"));
+        assert_eq!(v("v29.0.1"), format!("29.0.{}", 100 + BUILD_VERSION));
+        assert_ne!(v("v29.0.1"), v("v29"));
+    }
+
+    #[test]
+    fn test_from_string_rejects_malformed() {
+        assert!(Version::from_string("v29.x").is_err());
+        assert!(Version::from_string("v29.1.x").is_err());
+        assert!(Version::from_string("1.2.3.4").is_err());
+        assert!(Version::from_string("").is_err());
+    }
+}
@@ -0,0 +1,82 @@
+// Generated blocking client trait for Bitcoin Core {{VERSION}}
+
+use crate::transport::{TransportTrait, TransportError};
+use serde_json::Value;
+{{IMPORTS}}
+#[allow(unused_imports)]
+use super::client::*;
+
+/// A synchronous JSON-RPC transport.
+///
+/// Implement it directly for a blocking HTTP client, or wrap any async `TransportTrait`
+/// in [`BlockingTransport`].
+pub trait BlockingTransportTrait: Send + Sync {
+    /// Send a JSON-RPC request and wait for its raw `result`.
+    fn send_request_blocking(&self, method: &str, params: &[Value]) -> Result<Value, TransportError>;
+}
+
+/// Drives an async `TransportTrait` to completion on a private current-thread runtime.
+///
+/// Must not be used from within an async runtime: blocking on the private runtime from an
+/// async context panics. Use the async client there.
+#[derive(Debug)]
+pub struct BlockingTransport<T> {
+    inner: T,
+    runtime: tokio::runtime::Runtime,
+}
+
+impl<T: TransportTrait> BlockingTransport<T> {
+    /// Wrap `inner` with its own current-thread runtime.
+    pub fn new(inner: T) -> std::io::Result<Self> {
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
+        Ok(Self { inner, runtime })
+    }
+
+    /// The wrapped async transport.
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl<T: TransportTrait> BlockingTransportTrait for BlockingTransport<T> {
+    fn send_request_blocking(&self, method: &str, params: &[Value]) -> Result<Value, TransportError> {
+        self.runtime.block_on(self.inner.send_request(method, params))
+    }
+}
+
+#[doc = r#"A blocking mirror of `BitcoinClient{{VERSION_NODOTS}}` for Bitcoin Core {{VERSION}}
+
+Every method has the same typed signature as its async counterpart, without `async`.
+Every `BlockingTransportTrait` gets it through the blanket impl below."#]
+pub trait BlockingBitcoinClient{{VERSION_NODOTS}}: Send + Sync {
+    /// Call `method` with `params` followed by the `extra` positional arguments.
+    ///
+    /// The blocking counterpart of `BitcoinClient{{VERSION_NODOTS}}::call_extra`.
+    fn call_extra(&self, method: &str, params: &[Value], extra: &[Value]) -> Result<Value, TransportError>;
+
+{{TRAIT_METHOD_DECLS}}
+}
+
+impl<T: BlockingTransportTrait> BlockingBitcoinClient{{VERSION_NODOTS}} for T {
+    fn call_extra(&self, method: &str, params: &[Value], extra: &[Value]) -> Result<Value, TransportError> {
+        let params = params.iter().chain(extra).cloned().collect::<Vec<_>>();
+        self.send_request_blocking(method, &params)
+    }
+
+{{TRAIT_METHODS}}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::MockTransport;
+
+    fn assert_blocking_client<C: BlockingBitcoinClient{{VERSION_NODOTS}}>() {}
+
+    #[test]
+    fn blocking_client_builds_against_mock_transport() {
+        assert_blocking_client::<BlockingTransport<MockTransport>>();
+        let _: &dyn BlockingBitcoinClient{{VERSION_NODOTS}} =
+            &BlockingTransport::new(MockTransport::new()).unwrap();
+    }
+}
@@ -7,10 +7,26 @@ use serde::{de::DeserializeOwned, Deserialize, ser::SerializeSeq};
 
 {{PARAM_STRUCTS}}
 
-#[doc = r#"A versioned client trait for Bitcoin Core {{VERSION}}"#]
+#[doc = r#"A versioned client trait for Bitcoin Core {{VERSION}}
+
+The trait is object-safe, so it can be used as `&dyn BitcoinClient{{VERSION_NODOTS}}` and
+implemented by hand for mocks. Every `TransportTrait` gets it through the blanket impl below."#]
 #[async_trait]
-pub trait BitcoinClient{{VERSION_NODOTS}}: Send + Sync + TransportTrait + TransportExt + RpcDispatchExt {
-{{TRAIT_METHODS}}
+pub trait BitcoinClient{{VERSION_NODOTS}}: Send + Sync {
+    /// Call `method` with `params` followed by the `extra` positional arguments.
+    ///
+    /// An escape hatch for arguments this client does not model yet, e.g. one added in a
+    /// newer Core release. `params` are what the typed method would send; typed methods
+    /// send nothing for trailing unset arguments, so fill any gap before `extra` with
+    /// `Value::Null`. The raw result can be deserialized into the typed response.
+    async fn call_extra(
+        &self,
+        method: &str,
+        params: &[serde_json::Value],
+        extra: &[serde_json::Value],
+    ) -> Result<serde_json::Value, TransportError>;
+
+{{TRAIT_METHOD_DECLS}}
 }
 
 /// Helper to route calls to the node or wallet namespace automatically.
@@ -50,5 +66,15 @@ impl<T: TransportTrait + TransportExt + ?Sized> WalletTransportExt for T {}
 // Provide default implementation for any type that implements TransportTrait + TransportExt
 #[async_trait]
 impl<T: TransportTrait + TransportExt + Send + Sync> BitcoinClient{{VERSION_NODOTS}} for T {
+    async fn call_extra(
+        &self,
+        method: &str,
+        params: &[serde_json::Value],
+        extra: &[serde_json::Value],
+    ) -> Result<serde_json::Value, TransportError> {
+        let params = params.iter().chain(extra).cloned().collect::<Vec<_>>();
+        self.send_request(method, &params).await
+    }
+
 {{TRAIT_METHODS}}
 }
\ No newline at end of file
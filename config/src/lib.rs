@@ -18,8 +18,23 @@ pub enum ConfigError {
     Serialize(#[from] toml::ser::Error),
     #[error("Config file not found at: {0}")]
     NotFound(PathBuf),
+    #[error("Invalid value {value:?} for environment variable {var}: {reason}")]
+    InvalidEnv { var: &'static str, value: String, reason: String },
+    #[error("Invalid config value for {0}: {1}")]
+    Invalid(&'static str, String),
 }
 
+/// Environment variable overriding [`BitcoinConfig::host`]
+pub const ENV_RPC_HOST: &str = "BITCOIN_RPC_HOST";
+/// Environment variable overriding [`BitcoinConfig::port`]
+pub const ENV_RPC_PORT: &str = "BITCOIN_RPC_PORT";
+/// Environment variable overriding [`BitcoinConfig::username`]
+pub const ENV_RPC_USER: &str = "BITCOIN_RPC_USER";
+/// Environment variable overriding [`BitcoinConfig::password`]
+pub const ENV_RPC_PASSWORD: &str = "BITCOIN_RPC_PASSWORD";
+/// Environment variable overriding [`BitcoinConfig::network`]
+pub const ENV_RPC_NETWORK: &str = "BITCOIN_RPC_NETWORK";
+
 /// Main configuration structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -44,6 +59,12 @@ pub struct BitcoinConfig {
     pub password: String,
     /// Bitcoin network to use
     pub network: Option<Network>,
+    /// Per-request timeout in seconds (optional)
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    /// Number of retries for failed HTTP requests (optional)
+    #[serde(default)]
+    pub max_retries: Option<u32>,
 }
 
 /// Logging configuration
@@ -67,11 +88,112 @@ pub struct CodegenConfig {
 impl Config {
     /// Load configuration from a TOML file at `path`
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, ConfigError> {
-        let contents = std::fs::read_to_string(path.as_ref())?;
-        let config = toml::from_str(&contents)?;
+        let config = Self::parse_file(path)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Load configuration from a TOML file at `path`, then apply environment overrides
+    ///
+    /// Precedence is env > file > default; see [`Config::merge_env`]. The merged config
+    /// is validated, so the file may leave a value for the environment to fill in.
+    pub fn from_file_with_env<P: AsRef<Path>>(path: P) -> Result<Self, ConfigError> {
+        Self::from_file_with_env_internal(path, |var| std::env::var(var).ok())
+    }
+
+    /// Internal function for testing - allows injection of environment values
+    fn from_file_with_env_internal<P: AsRef<Path>>(
+        path: P,
+        lookup: impl Fn(&str) -> Option<String>,
+    ) -> Result<Self, ConfigError> {
+        let mut config = Self::parse_file(path)?;
+        config.merge_env_internal(lookup)?;
+        config.validate()?;
         Ok(config)
     }
 
+    /// Read and parse the TOML file at `path`, without validating it
+    fn parse_file<P: AsRef<Path>>(path: P) -> Result<Self, ConfigError> {
+        let contents = std::fs::read_to_string(path.as_ref())?;
+        Ok(toml::from_str(&contents)?)
+    }
+
+    /// Check the RPC connection settings for values that could only fail at connect time
+    ///
+    /// Rejects port 0, an empty host, and a host that carries a scheme (`http://...`)
+    /// or a path, since the RPC URL is built from `host` and `port`.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let bitcoin = &self.bitcoin;
+        if bitcoin.port == 0 {
+            return Err(ConfigError::Invalid("bitcoin.port", "port must not be 0".to_string()));
+        }
+        if bitcoin.host.trim().is_empty() {
+            return Err(ConfigError::Invalid("bitcoin.host", "host must not be empty".to_string()));
+        }
+        if bitcoin.host.contains("://") {
+            return Err(ConfigError::Invalid(
+                "bitcoin.host",
+                format!("host {:?} must not include a scheme", bitcoin.host),
+            ));
+        }
+        if bitcoin.host.contains('/') {
+            return Err(ConfigError::Invalid(
+                "bitcoin.host",
+                format!("host {:?} must not include a path", bitcoin.host),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Overlay the `BITCOIN_RPC_*` environment variables that are set onto this config
+    ///
+    /// Reads `BITCOIN_RPC_HOST`, `BITCOIN_RPC_PORT`, `BITCOIN_RPC_USER`,
+    /// `BITCOIN_RPC_PASSWORD` and `BITCOIN_RPC_NETWORK`. Unset variables leave the
+    /// current value untouched. A port that is not a `u16` or an unknown network
+    /// (`bitcoin`/`main`, `testnet`/`test`, `testnet4`, `signet`, `regtest`) is an error.
+    pub fn merge_env(&mut self) -> Result<(), ConfigError> {
+        self.merge_env_internal(|var| std::env::var(var).ok())
+    }
+
+    /// Internal function for testing - allows injection of environment values
+    fn merge_env_internal(
+        &mut self,
+        lookup: impl Fn(&str) -> Option<String>,
+    ) -> Result<(), ConfigError> {
+        // Apply to a copy so an invalid variable leaves the config unchanged
+        let mut bitcoin = self.bitcoin.clone();
+        if let Some(host) = lookup(ENV_RPC_HOST) {
+            bitcoin.host = host;
+        }
+        if let Some(port) = lookup(ENV_RPC_PORT) {
+            bitcoin.port =
+                port.parse().map_err(|e: std::num::ParseIntError| ConfigError::InvalidEnv {
+                    var: ENV_RPC_PORT,
+                    value: port.clone(),
+                    reason: e.to_string(),
+                })?;
+        }
+        if let Some(username) = lookup(ENV_RPC_USER) {
+            bitcoin.username = username;
+        }
+        if let Some(password) = lookup(ENV_RPC_PASSWORD) {
+            bitcoin.password = password;
+        }
+        if let Some(network) = lookup(ENV_RPC_NETWORK) {
+            let parsed = network
+                .parse::<Network>()
+                .or_else(|_| Network::from_core_arg(&network))
+                .map_err(|e| ConfigError::InvalidEnv {
+                var: ENV_RPC_NETWORK,
+                value: network.clone(),
+                reason: e.to_string(),
+            })?;
+            bitcoin.network = Some(parsed);
+        }
+        self.bitcoin = bitcoin;
+        Ok(())
+    }
+
     /// Save this configuration as a pretty-printed TOML file at `path`
     pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), ConfigError> {
         let contents = toml::to_string_pretty(self)?;
@@ -89,6 +211,10 @@ impl Config {
     }
 
     /// Get the default output directory for generated code
+    ///
+    /// Uses `OUT_DIR` if set, otherwise the current directory. The result is
+    /// canonicalized (symlinks resolved, e.g. `/tmp` → `/private/tmp` on macOS) when the
+    /// directory exists; a directory that does not exist yet is returned as given.
     pub fn default_output_dir() -> PathBuf {
         Self::default_output_dir_internal(
             std::env::var("OUT_DIR").ok(),
@@ -101,18 +227,11 @@ impl Config {
         out_dir: Option<String>,
         current_dir: Option<PathBuf>,
     ) -> PathBuf {
-        // First try to get OUT_DIR environment variable
-        if let Some(out_dir) = out_dir {
-            return PathBuf::from(out_dir);
-        }
-
-        // Fallback to current directory
-        if let Some(current_dir) = current_dir {
-            return current_dir;
-        }
+        // First try to get OUT_DIR environment variable, then fall back to the current
+        // directory, and as a last resort use the current directory as string
+        let dir = out_dir.map(PathBuf::from).or(current_dir).unwrap_or_else(|| PathBuf::from("."));
 
-        // Last resort - use current directory as string
-        PathBuf::from(".")
+        dir.canonicalize().unwrap_or(dir)
     }
 }
 
@@ -125,6 +244,8 @@ impl Default for Config {
                 username: "rpcuser".to_string(),
                 password: "rpcpassword".to_string(),
                 network: None,
+                timeout_secs: None,
+                max_retries: None,
             },
             logging: LoggingConfig { level: "info".to_string(), file: None },
             codegen: CodegenConfig {
@@ -168,6 +289,8 @@ mod tests {
         assert_eq!(loaded_config.bitcoin.port, 18443);
         assert_eq!(loaded_config.bitcoin.username, "rpcuser");
         assert_eq!(loaded_config.bitcoin.password, "rpcpassword");
+        assert_eq!(loaded_config.bitcoin.timeout_secs, None);
+        assert_eq!(loaded_config.bitcoin.max_retries, None);
         assert_eq!(loaded_config.logging.level, "info");
         assert_eq!(loaded_config.codegen.input_path, PathBuf::from("api.json"));
         assert_eq!(loaded_config.codegen.output_dir, PathBuf::from("generated"));
@@ -252,6 +375,125 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_timeout_and_retries_round_trip() {
+        let mut config = Config::default();
+        config.bitcoin.timeout_secs = Some(30);
+        config.bitcoin.max_retries = Some(5);
+
+        let temp_file = NamedTempFile::new().unwrap();
+        config.save(&temp_file).unwrap();
+        let loaded = Config::from_file(&temp_file).unwrap();
+        assert_eq!(loaded.bitcoin.timeout_secs, Some(30));
+        assert_eq!(loaded.bitcoin.max_retries, Some(5));
+    }
+
+    #[test]
+    fn test_merge_env() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |var: &str| vars.iter().find(|(k, _)| *k == var).map(|(_, v)| v.to_string())
+        };
+
+        // Unset variables keep the file/default values
+        let mut config = Config::default();
+        config.merge_env_internal(env(&[])).unwrap();
+        assert_eq!(config.bitcoin.host, "127.0.0.1");
+        assert_eq!(config.bitcoin.network, None);
+
+        let mut config = Config::default();
+        config
+            .merge_env_internal(env(&[
+                (ENV_RPC_HOST, "10.0.0.2"),
+                (ENV_RPC_PORT, "8332"),
+                (ENV_RPC_USER, "alice"),
+                (ENV_RPC_PASSWORD, "secret"),
+                (ENV_RPC_NETWORK, "main"),
+            ]))
+            .unwrap();
+        assert_eq!(config.bitcoin.host, "10.0.0.2");
+        assert_eq!(config.bitcoin.port, 8332);
+        assert_eq!(config.bitcoin.username, "alice");
+        assert_eq!(config.bitcoin.password, "secret");
+        assert_eq!(config.bitcoin.network, Some(Network::Bitcoin));
+
+        let mut config = Config::default();
+        config.merge_env_internal(env(&[(ENV_RPC_NETWORK, "signet")])).unwrap();
+        assert_eq!(config.bitcoin.network, Some(Network::Signet));
+
+        // Invalid values name the offending variable
+        let mut config = Config::default();
+        match config.merge_env_internal(env(&[(ENV_RPC_PORT, "eighteen")])).unwrap_err() {
+            ConfigError::InvalidEnv { var, value, .. } => {
+                assert_eq!(var, ENV_RPC_PORT);
+                assert_eq!(value, "eighteen");
+            }
+            other => panic!("Expected InvalidEnv error, got {:?}", other),
+        }
+        let err = config
+            .merge_env_internal(env(&[(ENV_RPC_HOST, "10.0.0.2"), (ENV_RPC_NETWORK, "moonnet")]))
+            .unwrap_err();
+        assert!(err.to_string().contains(ENV_RPC_NETWORK), "{err}");
+        assert_eq!(config.bitcoin.port, 18443);
+        assert_eq!(config.bitcoin.host, "127.0.0.1");
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(Config::default().validate().is_ok());
+
+        let invalid = |edit: fn(&mut BitcoinConfig)| {
+            let mut config = Config::default();
+            edit(&mut config.bitcoin);
+            match config.validate().unwrap_err() {
+                ConfigError::Invalid(field, reason) => (field, reason),
+                other => panic!("Expected Invalid error, got {:?}", other),
+            }
+        };
+        assert_eq!(invalid(|b| b.port = 0).0, "bitcoin.port");
+        assert_eq!(invalid(|b| b.host = String::new()).0, "bitcoin.host");
+        let (field, reason) = invalid(|b| b.host = "http://127.0.0.1".to_string());
+        assert_eq!(field, "bitcoin.host");
+        assert!(reason.contains("scheme"), "{reason}");
+        let (_, reason) = invalid(|b| b.host = "127.0.0.1/wallet/foo".to_string());
+        assert!(reason.contains("path"), "{reason}");
+
+        // from_file rejects invalid configs
+        let mut config = Config::default();
+        config.bitcoin.port = 0;
+        let temp_file = NamedTempFile::new().unwrap();
+        config.save(&temp_file).unwrap();
+        assert!(matches!(
+            Config::from_file(&temp_file),
+            Err(ConfigError::Invalid("bitcoin.port", _))
+        ));
+    }
+
+    #[test]
+    fn test_from_file_with_env_validates_the_merged_config() {
+        // The file leaves the host and port for the environment to fill in
+        let mut config = Config::default();
+        config.bitcoin.host = String::new();
+        config.bitcoin.port = 0;
+        let temp_file = NamedTempFile::new().unwrap();
+        config.save(&temp_file).unwrap();
+        assert!(matches!(Config::from_file(&temp_file), Err(ConfigError::Invalid(_, _))));
+
+        let env = |var: &str| match var {
+            ENV_RPC_HOST => Some("10.0.0.2".to_string()),
+            ENV_RPC_PORT => Some("8332".to_string()),
+            _ => None,
+        };
+        let config = Config::from_file_with_env_internal(&temp_file, env).unwrap();
+        assert_eq!(config.bitcoin.host, "10.0.0.2");
+        assert_eq!(config.bitcoin.port, 8332);
+
+        // Still invalid when the environment does not fill it in
+        assert!(matches!(
+            Config::from_file_with_env_internal(&temp_file, |_| None),
+            Err(ConfigError::Invalid("bitcoin.port", _))
+        ));
+    }
+
     #[test]
     fn test_default_path() {
         let path = Config::default_path().unwrap();
@@ -310,7 +552,25 @@ mod tests {
 
         // Test the fallback case by passing None for both parameters
         let dir = Config::default_output_dir_internal(None, None);
-        assert_eq!(dir, PathBuf::from("."));
+        assert_eq!(dir, PathBuf::from(".").canonicalize().unwrap());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_default_output_dir_internal_resolves_symlinks() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let real = temp_dir.path().join("real");
+        let link = temp_dir.path().join("link");
+        fs::create_dir(&real).unwrap();
+        std::os::unix::fs::symlink(&real, &link).unwrap();
+        let canonical = real.canonicalize().unwrap();
+
+        // Both sources are canonicalized alike
+        let dir = Config::default_output_dir_internal(None, Some(link.clone()));
+        assert_eq!(dir, canonical);
+        let dir =
+            Config::default_output_dir_internal(Some(link.to_str().unwrap().to_string()), None);
+        assert_eq!(dir, canonical);
     }
 
     #[test]
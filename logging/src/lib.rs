@@ -12,12 +12,12 @@
 use tracing_subscriber::{fmt, EnvFilter};
 
 /// Initialize the global tracing subscriber:
-/// - Reads `RUST_LOG` for filter directives, falling back to `"pipeline=info"`.
+/// - Reads `RUST_LOG` for filter directives, falling back to `"pipeline=info,codegen=info"`.
 /// - Uses a pretty-printed, line-based formatter.
 pub fn init() {
     // Try to parse RUST_LOG; default to showing info+ on our crate
-    let filter =
-        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("pipeline=info"));
+    let filter = EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| EnvFilter::new("pipeline=info,codegen=info"));
 
     fmt()
         .with_env_filter(filter)
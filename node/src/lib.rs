@@ -1,15 +1,21 @@
 // node/src/lib.rs
 
+use std::collections::VecDeque;
+use std::future::Future;
+use std::net::TcpListener;
+use std::path::Path;
+use std::sync::atomic::{AtomicU16, AtomicU32, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use anyhow::Result;
 use async_trait::async_trait;
+use bitcoin::Network;
 use tempfile::TempDir;
 use tokio::io::AsyncBufReadExt;
 use tokio::process::{Child, Command};
 use tokio::sync::{Mutex, RwLock};
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 pub mod test_config;
 use std::process::Stdio;
 
@@ -28,11 +34,28 @@ pub enum PortSelection {
     /// Use the specified port number
     Fixed(u16),
     /// Let the OS assign an available port
+    ///
+    /// The port stays bound by the manager until bitcoind is about to start, and a new one
+    /// is picked if bitcoind still fails to bind it.
     Dynamic,
-    /// Use port 0 (not recommended, may cause bitcoind to fail)
+    /// Leave `-rpcport` unset so bitcoind binds its network's default RPC port
+    ///
+    /// The port is only known once the node has started; see
+    /// [`BitcoinNodeManager::effective_rpc_port`].
     Zero,
 }
 
+impl PortSelection {
+    /// The selection a `TestConfig::rpc_port` asks for: [`Dynamic`](Self::Dynamic) for 0,
+    /// otherwise that fixed port
+    pub fn from_rpc_port(port: u16) -> Self {
+        match port {
+            0 => PortSelection::Dynamic,
+            port => PortSelection::Fixed(port),
+        }
+    }
+}
+
 /// Trait defining the interface for a Bitcoin node manager
 #[async_trait]
 pub trait NodeManager: Send + Sync + std::any::Any + std::fmt::Debug {
@@ -48,106 +71,308 @@ pub trait NodeManager: Send + Sync + std::any::Any + std::fmt::Debug {
 pub struct BitcoinNodeManager {
     state: Arc<RwLock<NodeState>>,
     child: Arc<Mutex<Option<Child>>>,
-    pub rpc_port: u16,
+    rpc_port: AtomicU16,
+    port_selection: PortSelection,
+    /// Listener holding a dynamically selected port until bitcoind is spawned to bind it
+    port_reservation: std::sync::Mutex<Option<TcpListener>>,
     config: TestConfig,
     _datadir: Option<TempDir>,
+    stderr_tail: Arc<std::sync::Mutex<VecDeque<String>>>,
+    /// Captured stdout and stderr lines, when `TestConfig::capture_logs` is set
+    logs: Arc<std::sync::Mutex<VecDeque<String>>>,
+    /// PID of the running bitcoind child, or 0 when no process is running
+    pid: AtomicU32,
+    /// RPC port bitcoind's startup log reports binding, or 0 until it does
+    logged_rpc_port: Arc<AtomicU16>,
+    /// RPC port the started node answered on, or 0 when it isn't running
+    effective_rpc_port: AtomicU16,
 }
 
+/// Number of trailing bitcoind stderr lines kept for error reports
+const STDERR_TAIL_LINES: usize = 20;
+
+/// How many times a dynamically selected port is replaced after bitcoind fails to bind it
+const PORT_RETRIES: usize = 3;
+
 impl BitcoinNodeManager {
     pub fn new() -> Result<Self> { Self::new_with_config(&TestConfig::default()) }
 
     pub fn new_with_config(config: &TestConfig) -> Result<Self> {
-        let datadir = TempDir::new()?;
-
-        // Handle automatic port selection:
-        // When rpc_port is 0, we need to find an available port dynamically.
-        // This is important because:
-        // 1. It allows multiple test instances to run in parallel without port conflicts
-        // 2. It prevents the "Invalid port specified in -rpcport: '0'" error from bitcoind
-        // 3. It makes the code more robust by not requiring manual port selection
-        let rpc_port = if config.rpc_port == 0 {
-            // Bind to port 0 to let the OS assign an available port
-            let listener = std::net::TcpListener::bind(("127.0.0.1", 0))?;
-            let port = listener.local_addr()?.port();
-            drop(listener);
-            port
-        } else {
-            config.rpc_port
+        Self::new_with_port_selection(config, PortSelection::from_rpc_port(config.rpc_port))
+    }
+
+    /// Create a manager whose RPC port is chosen by `port_selection` rather than by
+    /// `config.rpc_port`
+    pub fn new_with_port_selection(
+        config: &TestConfig,
+        port_selection: PortSelection,
+    ) -> Result<Self> {
+        let mut datadir = TempDir::new()?;
+        if config.keep_datadir {
+            datadir.disable_cleanup(true);
+        }
+
+        // Dynamic selection binds port 0 to let the OS assign an available port, and keeps
+        // the listener until bitcoind starts so no other process can take the port meanwhile
+        let (rpc_port, reservation) = match port_selection {
+            PortSelection::Fixed(port) => (port, None),
+            PortSelection::Dynamic => {
+                let (port, listener) = reserve_port()?;
+                (port, Some(listener))
+            }
+            PortSelection::Zero => (0, None),
         };
 
         Ok(Self {
             state: Arc::new(RwLock::new(NodeState::default())),
             child: Arc::new(Mutex::new(None)),
-            rpc_port,
+            rpc_port: AtomicU16::new(rpc_port),
+            port_selection,
+            port_reservation: std::sync::Mutex::new(reservation),
             config: config.clone(),
             _datadir: Some(datadir),
+            stderr_tail: Arc::new(std::sync::Mutex::new(VecDeque::new())),
+            logs: Arc::new(std::sync::Mutex::new(VecDeque::new())),
+            pid: AtomicU32::new(0),
+            logged_rpc_port: Arc::new(AtomicU16::new(0)),
+            effective_rpc_port: AtomicU16::new(0),
         })
     }
 
-    pub fn rpc_port(&self) -> u16 { self.rpc_port }
-}
+    /// The RPC port bitcoind is told to bind
+    ///
+    /// With [`PortSelection::Dynamic`] it changes if `start` has to pick a new port.
+    pub fn rpc_port(&self) -> u16 { self.rpc_port.load(Ordering::SeqCst) }
+
+    /// The RPC port the running node answered on, as confirmed by `start`
+    ///
+    /// Unlike [`rpc_port`](Self::rpc_port) this is the port bitcoind actually bound, which
+    /// differs when `extra_args` override `-rpcport` or bitcoind picks the port
+    /// ([`PortSelection::Zero`]). `None` until the node has started.
+    pub fn effective_rpc_port(&self) -> Option<u16> {
+        match self.effective_rpc_port.load(Ordering::SeqCst) {
+            0 => None,
+            port => Some(port),
+        }
+    }
 
-#[async_trait]
-impl NodeManager for BitcoinNodeManager {
-    async fn start(&self) -> Result<()> {
+    /// How this manager selects its RPC port
+    pub fn port_selection(&self) -> &PortSelection { &self.port_selection }
+
+    /// The temporary datadir this manager runs bitcoind in
+    pub fn datadir(&self) -> Option<&Path> { self._datadir.as_ref().map(TempDir::path) }
+
+    /// The PID of the spawned bitcoind process, if it is running
+    pub fn pid(&self) -> Option<u32> {
+        match self.pid.load(Ordering::SeqCst) {
+            0 => None,
+            pid => Some(pid),
+        }
+    }
+
+    /// The captured bitcoind stdout and stderr lines, oldest first
+    ///
+    /// Empty unless `TestConfig::capture_logs` is set; at most that many lines are kept.
+    pub fn logs(&self) -> Vec<String> {
+        self.logs.lock().unwrap_or_else(|e| e.into_inner()).iter().cloned().collect()
+    }
+
+    /// Build the bitcoind command line: the manager's defaults followed by
+    /// `TestConfig::extra_args`.
+    ///
+    /// Defaults that conflict with a user-supplied flag are dropped so the user's
+    /// override wins: the same option (including its `-no` form), any network
+    /// selection flag instead of `-chain`, or a known incompatible pair
+    /// (e.g. `-txindex` drops `-prune`).
+    fn bitcoind_args(&self, datadir: &Path) -> Vec<String> {
+        const NETWORK_FLAGS: &[&str] = &["chain", "regtest", "signet", "testnet", "testnet4"];
+        const INCOMPATIBLE: &[(&str, &str)] = &[("txindex", "prune")];
+
+        let mut defaults = vec![
+            format!("-chain={}", self.config.as_chain_str()),
+            "-listen=0".to_string(),
+            format!("-datadir={}", datadir.display()),
+        ];
+        match self.rpc_port() {
+            // Let bitcoind bind its default RPC port
+            0 => defaults.push("-rpcbind=127.0.0.1".to_string()),
+            port =>
+                defaults.extend([format!("-rpcport={port}"), format!("-rpcbind=127.0.0.1:{port}")]),
+        }
+        defaults.extend([
+            "-rpcallowip=127.0.0.1".to_string(),
+            "-fallbackfee=0.0002".to_string(),
+            "-server=1".to_string(),
+            "-prune=1".to_string(),
+            format!("-rpcuser={}", self.config.rpc_username),
+            format!("-rpcpassword={}", self.config.rpc_password),
+        ]);
+        if let (Network::Signet, Some(challenge)) =
+            (self.config.network, &self.config.signet_challenge)
+        {
+            defaults.push(format!("-signetchallenge={challenge}"));
+        }
+
+        let user_keys: Vec<&str> = self.config.extra_args.iter().map(|a| flag_key(a)).collect();
+        let overridden = |key: &str| {
+            user_keys.iter().any(|user| {
+                *user == key
+                    || (NETWORK_FLAGS.contains(user) && NETWORK_FLAGS.contains(&key))
+                    || INCOMPATIBLE.contains(&(*user, key))
+            })
+        };
+
+        defaults
+            .into_iter()
+            .filter(|arg| !overridden(flag_key(arg)))
+            .chain(self.config.extra_args.iter().cloned())
+            .collect()
+    }
+
+    /// Gracefully stop the node: issue the `stop` RPC and wait up to
+    /// `TestConfig::shutdown_timeout` for bitcoind to exit, killing it only as a fallback.
+    ///
+    /// Prefer this over dropping the manager, since `Drop` can only hard-kill the process.
+    pub async fn shutdown(&self) -> Result<()> {
         let mut state = self.state.write().await;
-        if state.is_running {
+        let Some(mut child) = self.child.lock().await.take() else {
+            state.is_running = false;
             return Ok(());
+        };
+
+        let graceful = match self.rpc_call("stop").await {
+            Ok(_) => match tokio::time::timeout(self.config.shutdown_timeout, child.wait()).await {
+                Ok(Ok(status)) => {
+                    info!("Bitcoin node on port {} stopped via RPC ({})", self.rpc_port(), status);
+                    true
+                }
+                Ok(Err(e)) => {
+                    warn!("Failed to wait for Bitcoin node on port {}: {}", self.rpc_port(), e);
+                    false
+                }
+                Err(_) => {
+                    warn!(
+                        "Bitcoin node on port {} did not exit within {:?} of the stop RPC",
+                        self.rpc_port(),
+                        self.config.shutdown_timeout
+                    );
+                    false
+                }
+            },
+            Err(e) => {
+                warn!("stop RPC to Bitcoin node on port {} failed: {}", self.rpc_port(), e);
+                false
+            }
+        };
+
+        if !graceful {
+            warn!("Killing Bitcoin node on port {}", self.rpc_port());
+            child.kill().await?;
         }
+        self.pid.store(0, Ordering::SeqCst);
+        self.effective_rpc_port.store(0, Ordering::SeqCst);
 
-        let datadir = self._datadir.as_ref().unwrap().path();
-        let mut cmd = Command::new("bitcoind");
+        state.is_running = false;
+        Ok(())
+    }
 
-        let chain = format!("-chain={}", self.config.as_chain_str());
-        let data_dir = format!("-datadir={}", datadir.display());
-        let rpc_port = format!("-rpcport={}", self.rpc_port);
-        let rpc_bind = format!("-rpcbind=127.0.0.1:{}", self.rpc_port);
-        let rpc_user = format!("-rpcuser={}", self.config.rpc_username);
-        let rpc_password = format!("-rpcpassword={}", self.config.rpc_password);
-
-        let mut args = vec![
-            &chain,
-            "-listen=0",
-            &data_dir,
-            &rpc_port,
-            &rpc_bind,
-            "-rpcallowip=127.0.0.1",
-            "-fallbackfee=0.0002",
-            "-server=1",
-            "-prune=1",
-            &rpc_user,
-            &rpc_password,
-        ];
+    /// Shut the node down like [`shutdown`](Self::shutdown), then remove its datadir
+    /// (unless `TestConfig::keep_datadir` is set)
+    ///
+    /// Returns once bitcoind has exited, so no process outlives the test that started it.
+    pub async fn teardown(self) -> Result<()> { self.shutdown().await }
+
+    /// The port to reach the node's RPC server on: the one it answered on, else the one its
+    /// log reports binding, else the configured one
+    fn rpc_target_port(&self) -> u16 {
+        self.effective_rpc_port()
+            .or(match self.logged_rpc_port.load(Ordering::SeqCst) {
+                0 => None,
+                port => Some(port),
+            })
+            .unwrap_or_else(|| self.rpc_port())
+    }
+
+    /// POST a parameterless JSON-RPC call to the node's RPC port
+    async fn rpc_call(&self, method: &str) -> reqwest::Result<reqwest::Response> {
+        reqwest::Client::new()
+            .post(format!("http://127.0.0.1:{}/", self.rpc_target_port()))
+            .basic_auth(&self.config.rpc_username, Some(&self.config.rpc_password))
+            .json(&serde_json::json!({
+                "jsonrpc": "2.0",
+                "method": method,
+                "params": [],
+                "id": 1
+            }))
+            .send()
+            .await
+    }
 
-        for arg in &self.config.extra_args {
-            args.push(arg);
+    /// The most recent bitcoind stderr lines, formatted for inclusion in an error message
+    fn stderr_tail(&self) -> String {
+        let tail = self.stderr_tail.lock().unwrap_or_else(|e| e.into_inner());
+        if tail.is_empty() {
+            return String::new();
         }
+        let lines: Vec<&str> = tail.iter().map(String::as_str).collect();
+        format!("\nbitcoind stderr (last {} lines):\n{}", lines.len(), lines.join("\n"))
+    }
 
+    /// Spawn bitcoind and wait until its RPC server answers, failing if it exits early or
+    /// `TestConfig::start_timeout` elapses
+    async fn launch(&self) -> Result<()> {
+        let datadir = self._datadir.as_ref().unwrap().path();
+        let mut cmd = Command::new("bitcoind");
+
+        let args = self.bitcoind_args(datadir);
+        debug!("Starting bitcoind with args: {:?}", args);
         cmd.args(&args);
 
         // Capture both stdout and stderr for better error reporting
         cmd.stderr(Stdio::piped());
         cmd.stdout(Stdio::piped());
 
+        // Free a reserved port only now, right before bitcoind binds it
+        drop(self.port_reservation.lock().unwrap_or_else(|e| e.into_inner()).take());
         let mut child = cmd.spawn()?;
+        self.pid.store(child.id().unwrap_or(0), Ordering::SeqCst);
+        info!("Spawned bitcoind (pid {:?}) with datadir {}", child.id(), datadir.display());
 
         // Read stderr in a separate task
         let stderr = child.stderr.take().unwrap();
         let stderr_reader = tokio::io::BufReader::new(stderr);
+        let stderr_tail = self.stderr_tail.clone();
+        stderr_tail.lock().unwrap_or_else(|e| e.into_inner()).clear();
+        self.logs.lock().unwrap_or_else(|e| e.into_inner()).clear();
+        let capture_logs = self.config.capture_logs;
+        let logs = self.logs.clone();
         tokio::spawn(async move {
             let mut lines = stderr_reader.lines();
             while let Ok(Some(line)) = lines.next_line().await {
                 error!("bitcoind stderr: {}", line);
+                if let Some(capacity) = capture_logs {
+                    push_capped(&logs, line.clone(), capacity);
+                }
+                push_capped(&stderr_tail, line, STDERR_TAIL_LINES);
             }
         });
 
-        // Read stdout in a separate task
+        // Read stdout in a separate task, noting the RPC port bitcoind reports binding
         let stdout = child.stdout.take().unwrap();
         let stdout_reader = tokio::io::BufReader::new(stdout);
+        let logs = self.logs.clone();
+        let logged_rpc_port = self.logged_rpc_port.clone();
+        logged_rpc_port.store(0, Ordering::SeqCst);
         tokio::spawn(async move {
             let mut lines = stdout_reader.lines();
             while let Ok(Some(line)) = lines.next_line().await {
                 info!("bitcoind stdout: {}", line);
+                if let Some(port) = bound_rpc_port(&line) {
+                    logged_rpc_port.store(port, Ordering::SeqCst);
+                }
+                if let Some(capacity) = capture_logs {
+                    push_capped(&logs, line, capacity);
+                }
             }
         });
 
@@ -159,35 +384,45 @@ impl NodeManager for BitcoinNodeManager {
         tokio::time::sleep(Duration::from_millis(150)).await;
 
         // Wait for node to be ready
-        let deadline = Instant::now() + Duration::from_secs(10);
+        let deadline = Instant::now() + self.config.start_timeout;
         let mut attempts = 0;
         while Instant::now() < deadline {
             if let Some(child) = child_guard.as_mut() {
                 if let Ok(Some(status)) = child.try_wait() {
-                    let error = format!("Bitcoin node exited early with status: {}", status);
+                    // Give the stderr reader a moment to drain the final lines
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    let error = format!(
+                        "Bitcoin node exited early with status: {}{}",
+                        status,
+                        self.stderr_tail()
+                    );
                     error!("{}", error);
                     anyhow::bail!(error);
                 }
             }
 
+            // Until bitcoind reports its port there is nothing to probe if it picks one
+            if self.rpc_target_port() == 0 {
+                debug!("Waiting for bitcoind to report its RPC port (attempt {})", attempts);
+                attempts += 1;
+                tokio::time::sleep(self.config.poll_interval).await;
+                continue;
+            }
+
             // Try to connect to RPC
-            let client = reqwest::Client::new();
-            match client
-                .post(format!("http://127.0.0.1:{}/", self.rpc_port))
-                .basic_auth(&self.config.rpc_username, Some(&self.config.rpc_password))
-                .json(&serde_json::json!({
-                    "jsonrpc": "2.0",
-                    "method": "getnetworkinfo",
-                    "params": [],
-                    "id": 1
-                }))
-                .send()
-                .await
-            {
+            let port = self.rpc_target_port();
+            match self.rpc_call("getnetworkinfo").await {
                 Ok(response) =>
                     if response.status().is_success() {
-                        state.is_running = true;
-                        info!("Bitcoin node started successfully on port {}", self.rpc_port);
+                        let configured = self.rpc_port();
+                        if configured != 0 && configured != port {
+                            warn!(
+                                "Bitcoin node bound RPC port {} rather than the configured {}",
+                                port, configured
+                            );
+                        }
+                        self.effective_rpc_port.store(port, Ordering::SeqCst);
+                        info!("Bitcoin node started successfully on port {}", port);
                         return Ok(());
                     } else {
                         debug!(
@@ -202,47 +437,182 @@ impl NodeManager for BitcoinNodeManager {
             }
 
             attempts += 1;
-            tokio::time::sleep(Duration::from_millis(200)).await;
+            tokio::time::sleep(self.config.poll_interval).await;
         }
 
         let error = format!(
-            "Timed out waiting for Bitcoin node to start on port {} after {} attempts",
-            self.rpc_port, attempts
+            "Timed out waiting for Bitcoin node to start on port {} after {} attempts ({:?}){}",
+            self.rpc_port(),
+            attempts,
+            self.config.start_timeout,
+            self.stderr_tail()
         );
         error!("{}", error);
         anyhow::bail!(error);
     }
+}
 
-    async fn stop(&mut self) -> Result<()> {
+#[async_trait]
+impl NodeManager for BitcoinNodeManager {
+    async fn start(&self) -> Result<()> {
         let mut state = self.state.write().await;
-        if !state.is_running {
+        if state.is_running {
             return Ok(());
         }
 
-        let child = self.child.lock().await.take();
-        if let Some(mut child) = child {
-            std::mem::drop(child.kill());
-            std::mem::drop(child.wait());
+        let mut retries = 0;
+        loop {
+            match self.launch().await {
+                Ok(()) => {
+                    state.is_running = true;
+                    return Ok(());
+                }
+                // Another process took the port between its selection and bitcoind's bind
+                Err(e)
+                    if matches!(self.port_selection, PortSelection::Dynamic)
+                        && retries < PORT_RETRIES
+                        && is_bind_failure(&self.stderr_tail()) =>
+                {
+                    retries += 1;
+                    let (port, listener) = reserve_port()?;
+                    warn!(
+                        "Bitcoin node could not bind port {}, retrying on port {}: {}",
+                        self.rpc_port(),
+                        port,
+                        e
+                    );
+                    self.rpc_port.store(port, Ordering::SeqCst);
+                    *self.port_reservation.lock().unwrap_or_else(|e| e.into_inner()) =
+                        Some(listener);
+                }
+                Err(e) => return Err(e),
+            }
         }
-
-        state.is_running = false;
-        Ok(())
     }
 
+    async fn stop(&mut self) -> Result<()> { self.shutdown().await }
+
     async fn get_state(&self) -> Result<NodeState> { Ok(self.state.read().await.clone()) }
 
-    fn rpc_port(&self) -> u16 { self.rpc_port }
+    fn rpc_port(&self) -> u16 { BitcoinNodeManager::rpc_port(self) }
+}
+
+/// Bind an OS-assigned port on localhost, returning it with the listener holding it
+fn reserve_port() -> Result<(u16, TcpListener)> {
+    let listener = TcpListener::bind(("127.0.0.1", 0))?;
+    Ok((listener.local_addr()?.port(), listener))
+}
+
+/// The port of a bitcoind log line like `Binding RPC on address 127.0.0.1 port 18443`
+fn bound_rpc_port(line: &str) -> Option<u16> {
+    let (_, binding) = line.split_once("Binding RPC on address ")?;
+    if binding.contains("failed") {
+        return None;
+    }
+    let (_, port) = binding.rsplit_once(" port ")?;
+    port.trim().parse().ok()
+}
+
+/// Whether bitcoind's stderr reports that it could not bind its RPC port
+fn is_bind_failure(stderr: &str) -> bool {
+    stderr.contains("Unable to bind") || stderr.contains("Unable to start HTTP server")
+}
+
+/// Append `line` to `buffer`, dropping the oldest lines beyond `capacity`
+fn push_capped(buffer: &std::sync::Mutex<VecDeque<String>>, line: String, capacity: usize) {
+    if capacity == 0 {
+        return;
+    }
+    let mut buffer = buffer.lock().unwrap_or_else(|e| e.into_inner());
+    while buffer.len() >= capacity {
+        buffer.pop_front();
+    }
+    buffer.push_back(line);
+}
+
+/// The option name of a bitcoind flag: `-noprune` and `-prune=1` both yield `prune`
+fn flag_key(arg: &str) -> &str {
+    let name = arg.trim_start_matches('-');
+    let name = name.split_once('=').map_or(name, |(name, _)| name);
+    name.strip_prefix("no").unwrap_or(name)
 }
 
 impl Drop for BitcoinNodeManager {
     fn drop(&mut self) {
-        if let Some(mut child) = self.child.try_lock().ok().and_then(|mut guard| guard.take()) {
-            std::mem::drop(child.kill());
-            std::mem::drop(child.wait());
+        // Safety net only: `Drop` can't await the graceful `shutdown` path. Calls that lock
+        // the child borrow `self`, so none is in flight here and the child is taken through
+        // `&mut` rather than a `try_lock` that would silently skip a held lock.
+        let child = match Arc::get_mut(&mut self.child) {
+            Some(child) => child.get_mut().take(),
+            None => match self.child.try_lock() {
+                Ok(mut guard) => guard.take(),
+                Err(_) => {
+                    error!(
+                        "BitcoinNodeManager dropped while its process is in use; Bitcoin node on port {} may be leaked",
+                        self.rpc_port()
+                    );
+                    None
+                }
+            },
+        };
+        if let Some(mut child) = child {
+            warn!(
+                "BitcoinNodeManager dropped without teardown; killing Bitcoin node on port {}",
+                self.rpc_port()
+            );
+            if let Err(e) = child.start_kill() {
+                error!("Failed to kill Bitcoin node on port {}: {}", self.rpc_port(), e);
+            }
+        }
+        if self.config.keep_datadir {
+            if let Some(datadir) = self.datadir() {
+                info!("Keeping Bitcoin node datadir at {}", datadir.display());
+            }
         }
     }
 }
 
+/// Start a node configured by `config`, run `test` against it and tear the node down
+///
+/// The node is shut down even if `test` panics, after which the panic is resumed, so a
+/// failing `#[tokio::test]` does not leak its bitcoind process.
+///
+/// # Example
+/// ```rust,ignore
+/// #[tokio::test]
+/// async fn node_starts() -> anyhow::Result<()> {
+///     with_node(&TestConfig::default(), |node| async move {
+///         assert!(node.get_state().await.unwrap().is_running);
+///     })
+///     .await
+/// }
+/// ```
+pub async fn with_node<F, Fut, T>(config: &TestConfig, test: F) -> Result<T>
+where
+    F: FnOnce(Arc<BitcoinNodeManager>) -> Fut,
+    Fut: Future<Output = T> + Send + 'static,
+    T: Send + 'static,
+{
+    let node = Arc::new(BitcoinNodeManager::new_with_config(config)?);
+    if let Err(e) = node.start().await {
+        node.shutdown().await?;
+        return Err(e);
+    }
+
+    // Run the test in its own task, so a panic is caught as a `JoinError`
+    let outcome = tokio::spawn(test(node.clone())).await;
+    let shutdown = match Arc::try_unwrap(node) {
+        Ok(node) => node.teardown().await,
+        // The test kept a handle to the node; stop it all the same
+        Err(node) => node.shutdown().await,
+    };
+    match outcome {
+        Ok(output) => shutdown.map(|()| output),
+        Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+        Err(e) => Err(e.into()),
+    }
+}
+
 impl Default for BitcoinNodeManager {
     fn default() -> Self {
         Self::new_with_config(&TestConfig::default())
@@ -264,4 +634,201 @@ mod tests {
 
         assert_eq!(node_manager.config.extra_args[0], "-debug=1");
     }
+
+    #[test]
+    fn test_start_timing_config() {
+        let defaults = TestConfig::default();
+        assert_eq!(defaults.start_timeout, Duration::from_secs(10));
+        assert_eq!(defaults.poll_interval, Duration::from_millis(200));
+
+        let config = TestConfig {
+            start_timeout: Duration::from_secs(60),
+            poll_interval: Duration::from_millis(500),
+            ..TestConfig::default()
+        };
+        let node_manager = BitcoinNodeManager::new_with_config(&config)
+            .expect("Failed to create node manager with custom timing");
+
+        assert_eq!(node_manager.config.start_timeout, Duration::from_secs(60));
+        assert_eq!(node_manager.config.poll_interval, Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_stderr_tail() {
+        let node_manager = BitcoinNodeManager::new().expect("Failed to create node manager");
+        assert_eq!(node_manager.stderr_tail(), "");
+
+        node_manager.stderr_tail.lock().unwrap().push_back("Error: bad flag".to_string());
+        assert!(node_manager.stderr_tail().ends_with("(last 1 lines):\nError: bad flag"));
+    }
+
+    #[test]
+    fn test_logs_keep_the_most_recent_lines() {
+        let node_manager = BitcoinNodeManager::new().expect("Failed to create node manager");
+        assert!(node_manager.logs().is_empty());
+
+        for i in 0..5 {
+            push_capped(&node_manager.logs, format!("line {i}"), 3);
+        }
+        assert_eq!(node_manager.logs(), ["line 2", "line 3", "line 4"]);
+    }
+
+    #[test]
+    fn test_bitcoind_args_user_overrides_win() {
+        let config = TestConfig {
+            extra_args: vec![
+                "-signet".to_string(),
+                "-txindex=1".to_string(),
+                "-blockfilterindex=1".to_string(),
+                "-nolisten".to_string(),
+                "-fallbackfee=0.001".to_string(),
+            ],
+            ..TestConfig::default()
+        };
+        let node_manager = BitcoinNodeManager::new_with_config(&config)
+            .expect("Failed to create node manager with extra args");
+        let args = node_manager.bitcoind_args(Path::new("/tmp/datadir"));
+
+        assert!(!args.iter().any(|a| a.starts_with("-chain=")));
+        assert!(!args.iter().any(|a| a.starts_with("-prune")));
+        assert!(!args.iter().any(|a| a == "-listen=0"));
+        assert!(!args.iter().any(|a| a == "-fallbackfee=0.0002"));
+        assert!(args.contains(&"-datadir=/tmp/datadir".to_string()));
+        assert!(args.contains(&format!("-rpcport={}", node_manager.rpc_port())));
+        assert_eq!(&args[args.len() - 5..], config.extra_args.as_slice());
+    }
+
+    #[test]
+    fn test_bitcoind_args_defaults() {
+        let node_manager = BitcoinNodeManager::new().expect("Failed to create node manager");
+        let args = node_manager.bitcoind_args(Path::new("/tmp/datadir"));
+
+        assert_eq!(args[0], "-chain=regtest");
+        assert!(args.contains(&"-prune=1".to_string()));
+        assert!(args.contains(&"-listen=0".to_string()));
+    }
+
+    #[test]
+    fn test_bitcoind_args_network_selection() {
+        for (network, chain) in [
+            (Network::Bitcoin, "-chain=main"),
+            (Network::Testnet, "-chain=testnet"),
+            (Network::Testnet4, "-chain=testnet4"),
+            (Network::Signet, "-chain=signet"),
+        ] {
+            let config = TestConfig { network, ..TestConfig::default() };
+            let node_manager = BitcoinNodeManager::new_with_config(&config)
+                .expect("Failed to create node manager");
+            let args = node_manager.bitcoind_args(Path::new("/tmp/datadir"));
+            assert_eq!(args[0], chain);
+            assert!(!args.iter().any(|a| a.starts_with("-signetchallenge")));
+        }
+
+        let config = TestConfig {
+            network: Network::Signet,
+            signet_challenge: Some("51".to_string()),
+            ..TestConfig::default()
+        };
+        let node_manager =
+            BitcoinNodeManager::new_with_config(&config).expect("Failed to create node manager");
+        let args = node_manager.bitcoind_args(Path::new("/tmp/datadir"));
+        assert!(args.contains(&"-signetchallenge=51".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_without_running_node() {
+        let node_manager = BitcoinNodeManager::new().expect("Failed to create node manager");
+
+        node_manager.shutdown().await.expect("shutdown of a node that never started");
+        assert!(!node_manager.get_state().await.unwrap().is_running);
+    }
+
+    #[tokio::test]
+    async fn test_teardown_removes_the_datadir() {
+        let node_manager = BitcoinNodeManager::new().expect("Failed to create node manager");
+        let datadir = node_manager.datadir().expect("datadir").to_path_buf();
+
+        node_manager.teardown().await.expect("teardown of a node that never started");
+        assert!(!datadir.exists());
+    }
+
+    #[test]
+    fn test_dynamic_ports_are_held_until_start() {
+        let handles = (0..32)
+            .map(|_| std::thread::spawn(|| BitcoinNodeManager::new().unwrap()))
+            .collect::<Vec<_>>();
+        let managers = handles.into_iter().map(|h| h.join().unwrap()).collect::<Vec<_>>();
+
+        let mut ports = managers.iter().map(BitcoinNodeManager::rpc_port).collect::<Vec<_>>();
+        ports.sort_unstable();
+        ports.dedup();
+        assert_eq!(ports.len(), managers.len());
+        // Nothing else can take a selected port before the node starts
+        assert!(TcpListener::bind(("127.0.0.1", ports[0])).is_err());
+        assert!(is_bind_failure("Error: Unable to start HTTP server. See debug log for details."));
+
+        let config = TestConfig { rpc_port: 18545, ..TestConfig::default() };
+        let node_manager =
+            BitcoinNodeManager::new_with_config(&config).expect("Failed to create node manager");
+        assert!(matches!(node_manager.port_selection(), PortSelection::Fixed(18545)));
+        assert_eq!(node_manager.rpc_port(), 18545);
+    }
+
+    #[tokio::test]
+    #[ignore = "spawns bitcoind, which must be on PATH"]
+    async fn test_many_nodes_start_concurrently() {
+        let mut nodes = tokio::task::JoinSet::new();
+        for _ in 0..16 {
+            nodes.spawn(async {
+                let node_manager = BitcoinNodeManager::new()?;
+                node_manager.start().await?;
+                node_manager.teardown().await
+            });
+        }
+        while let Some(result) = nodes.join_next().await {
+            result.unwrap().expect("node failed to start");
+        }
+    }
+
+    #[test]
+    fn test_effective_rpc_port() {
+        assert_eq!(
+            bound_rpc_port("2025-01-01T00:00:00Z Binding RPC on address 127.0.0.1 port 18443"),
+            Some(18443)
+        );
+        assert_eq!(bound_rpc_port("Binding RPC on address ::1 port 18443 failed."), None);
+        assert_eq!(bound_rpc_port("init message: Loading wallet…"), None);
+
+        let node_manager = BitcoinNodeManager::new_with_port_selection(
+            &TestConfig::default(),
+            PortSelection::Zero,
+        )
+        .expect("Failed to create node manager");
+        assert_eq!(node_manager.effective_rpc_port(), None);
+        let args = node_manager.bitcoind_args(Path::new("/tmp/datadir"));
+        assert!(!args.iter().any(|a| a.starts_with("-rpcport")));
+        assert!(args.contains(&"-rpcbind=127.0.0.1".to_string()));
+
+        // The port bitcoind reports binding is the one it is reached on
+        node_manager.logged_rpc_port.store(18443, Ordering::SeqCst);
+        assert_eq!(node_manager.rpc_target_port(), 18443);
+    }
+
+    #[test]
+    fn test_keep_datadir() {
+        let config = TestConfig { keep_datadir: true, ..TestConfig::default() };
+        let node_manager =
+            BitcoinNodeManager::new_with_config(&config).expect("Failed to create node manager");
+        let datadir = node_manager.datadir().expect("datadir").to_path_buf();
+        assert!(node_manager.pid().is_none());
+
+        drop(node_manager);
+        assert!(datadir.exists());
+        std::fs::remove_dir_all(&datadir).unwrap();
+
+        let node_manager = BitcoinNodeManager::new().expect("Failed to create node manager");
+        let datadir = node_manager.datadir().expect("datadir").to_path_buf();
+        drop(node_manager);
+        assert!(!datadir.exists());
+    }
 }
@@ -1,10 +1,20 @@
 // node/src/test_config.rs
 
 use std::env;
+use std::time::Duration;
 
 use bitcoin::Network;
 use config::{BitcoinConfig, Config};
 
+/// Default time `BitcoinNodeManager::start` waits for the node to become ready.
+pub const DEFAULT_START_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default delay between readiness probes while the node is starting.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Default grace period for bitcoind to exit after the `stop` RPC.
+pub const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
 /// TestConfig represents the configuration needed to run a Bitcoin node in a test environment.
 /// This struct is the single source of truth for test‑node settings: RPC port, username, and password.
 /// Defaults are:
@@ -12,6 +22,7 @@ use config::{BitcoinConfig, Config};
 /// - `rpc_username = "rpcuser"`
 /// - `rpc_password = "rpcpassword"`
 /// - `network = Network::Regtest` (for isolation and testability)
+/// - `start_timeout = 10s`, `poll_interval = 200ms`, `shutdown_timeout = 10s`
 ///
 /// To override any of these, simply modify fields on `TestConfig::default()`
 /// (or assign directly in code). If you prefer not to recompile for every change,
@@ -42,8 +53,25 @@ pub struct TestConfig {
     pub rpc_password: String,
     /// Which Bitcoin network to run against.
     pub network: Network,
-    /// Extra command-line arguments to pass to bitcoind
+    /// Custom signet challenge script (hex), passed as `-signetchallenge` when
+    /// `network` is `Network::Signet`. `None` uses the default public signet.
+    pub signet_challenge: Option<String>,
+    /// Extra command-line arguments to pass to bitcoind, appended after the defaults.
+    /// A default that conflicts with one of these (e.g. `-prune` vs `-txindex=1`,
+    /// or `-chain` vs `-signet`) is dropped so the override wins.
     pub extra_args: Vec<String>,
+    /// How long `start` waits for the node to answer RPC before giving up.
+    pub start_timeout: Duration,
+    /// Delay between RPC readiness probes while the node is starting.
+    pub poll_interval: Duration,
+    /// How long `shutdown` waits for bitcoind to exit after the `stop` RPC before killing it.
+    pub shutdown_timeout: Duration,
+    /// Keep the node's temporary datadir (including `debug.log`) instead of deleting it
+    /// when the manager is dropped, for post-mortem debugging.
+    pub keep_datadir: bool,
+    /// Keep the last `n` lines bitcoind writes to stdout and stderr in memory, readable
+    /// through `BitcoinNodeManager::logs`. `None` disables capture.
+    pub capture_logs: Option<usize>,
 }
 
 impl TestConfig {
@@ -109,6 +137,8 @@ impl TestConfig {
                 username: self.rpc_username,
                 password: self.rpc_password,
                 network: Some(self.network),
+                timeout_secs: None,
+                max_retries: None,
             },
             ..Config::default()
         }
@@ -121,7 +151,13 @@ impl TestConfig {
             rpc_username: config.bitcoin.username.clone(),
             rpc_password: config.bitcoin.password.clone(),
             network: config.bitcoin.network.unwrap_or(Network::Regtest), // Use config network if available
+            signet_challenge: None,
             extra_args: vec![],
+            start_timeout: DEFAULT_START_TIMEOUT,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            shutdown_timeout: DEFAULT_SHUTDOWN_TIMEOUT,
+            keep_datadir: false,
+            capture_logs: None,
         }
     }
 }
@@ -133,7 +169,13 @@ impl Default for TestConfig {
             rpc_username: "rpcuser".to_string(),
             rpc_password: "rpcpassword".to_string(),
             network: Network::Regtest,
+            signet_challenge: None,
             extra_args: vec![],
+            start_timeout: DEFAULT_START_TIMEOUT,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            shutdown_timeout: DEFAULT_SHUTDOWN_TIMEOUT,
+            keep_datadir: false,
+            capture_logs: None,
         }
     }
 }
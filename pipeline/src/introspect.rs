@@ -0,0 +1,328 @@
+//! Build an API schema from a running node's `help` output.
+//!
+//! Bitcoin Core only documents its RPCs as help text, so this parses the `Arguments:` and
+//! `Result:` sections back into the schema format the pipeline reads. Help text is lossier
+//! than a schema dumped from Core's sources: hex strings read as `string`, result amounts as
+//! `number`, and the nested fields of option-object arguments are skipped.
+
+use anyhow::{Context, Result};
+use bitcoin_rpc_types::{BtcArgument, BtcMethod, BtcResult};
+use codegen::schema_normalizer::canonical_type;
+use regex::Regex;
+use serde_json::{json, Value as JsonValue};
+use transport::BlockingRpcClient;
+
+/// Fetch every RPC's help from the node behind `client` and build a schema from it.
+///
+/// # Returns
+///
+/// Returns the schema as `{"version", "implementation", "methods"}`, ready to be written
+/// as the pipeline's input file
+pub fn introspect(client: &BlockingRpcClient) -> Result<JsonValue> {
+    let network: JsonValue = client
+        .call("getnetworkinfo", &[] as &[JsonValue])
+        .context("Failed to call getnetworkinfo")?;
+    let version = network["version"]
+        .as_u64()
+        .ok_or_else(|| anyhow::anyhow!("getnetworkinfo has no numeric 'version' field"))?;
+
+    let commands: String =
+        client.call("help", &[] as &[JsonValue]).context("Failed to list commands")?;
+    let mut methods = serde_json::Map::new();
+    for (category, name) in parse_command_list(&commands) {
+        let help: String = client
+            .call("help", &[name.as_str()])
+            .with_context(|| format!("Failed to fetch help for {name}"))?;
+        let mut method = serde_json::to_value(parse_help(&name, &help))?;
+        method["category"] = category.into();
+        methods.insert(name, method);
+    }
+
+    Ok(json!({
+        "version": version_from_number(version),
+        "implementation": "bitcoin_core",
+        "methods": methods,
+    }))
+}
+
+/// The schema version for a `getnetworkinfo` version number, e.g. `v29.1.0` for `290100`.
+pub fn version_from_number(version: u64) -> String {
+    format!("v{}.{}.{}", version / 10_000, version / 100 % 100, version % 100)
+}
+
+/// The `(category, method)` pairs listed by a bare `help` call, in listing order.
+pub fn parse_command_list(help: &str) -> Vec<(String, String)> {
+    let mut category = String::new();
+    let mut commands = Vec::new();
+    for line in help.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        if let Some(header) = line.strip_prefix("== ").and_then(|l| l.strip_suffix(" ==")) {
+            category = header.to_lowercase();
+        } else if let Some(name) = line.split_whitespace().next() {
+            commands.push((category.clone(), name.to_string()));
+        }
+    }
+    commands
+}
+
+/// Parse the output of `help <name>` into a method description.
+pub fn parse_help(name: &str, help: &str) -> BtcMethod {
+    let result_header = Regex::new(r"^Result(?: \((.*)\))?:$").expect("valid result regex");
+
+    // The first line is the call synopsis; the rest splits into titled sections
+    let mut description = Vec::new();
+    let mut sections: Vec<(&str, Vec<&str>)> = Vec::new();
+    for line in help.lines().skip(1) {
+        let title = line.trim_end();
+        if title == "Arguments:" || title == "Examples:" || result_header.is_match(title) {
+            sections.push((title, Vec::new()));
+        } else if let Some((_, lines)) = sections.last_mut() {
+            lines.push(line);
+        } else {
+            description.push(line);
+        }
+    }
+
+    let mut arguments = Vec::new();
+    let mut results = Vec::new();
+    let mut examples = String::new();
+    for (title, lines) in sections {
+        if title == "Arguments:" {
+            arguments.extend(parse_arguments(&lines));
+        } else if title == "Examples:" {
+            examples = join_lines(&lines);
+        } else {
+            let condition = result_header
+                .captures(title)
+                .and_then(|caps| caps.get(1))
+                .map_or("", |c| c.as_str());
+            let mut entries = lines.iter().map(|l| l.trim()).filter(|l| !l.is_empty());
+            for mut result in parse_results(&mut entries) {
+                result.condition = condition.to_string();
+                results.push(result);
+            }
+        }
+    }
+
+    BtcMethod {
+        name: name.to_string(),
+        description: join_lines(&description),
+        examples,
+        argument_names: arguments.iter().map(|a| a.names.join("|")).collect(),
+        arguments,
+        results,
+    }
+}
+
+/// The non-blank `lines` with surrounding blank lines dropped, each ending in a newline.
+fn join_lines(lines: &[&str]) -> String {
+    let text = lines.join("\n");
+    let text = text.trim_matches('\n');
+    if text.is_empty() {
+        String::new()
+    } else {
+        format!("{text}\n")
+    }
+}
+
+/// The schema type for a help-text type, e.g. `number` for `numeric`.
+fn schema_type(help_type: &str) -> &'static str { canonical_type(help_type).unwrap_or("any") }
+
+/// Parse the numbered entries of an `Arguments:` section.
+fn parse_arguments(lines: &[&str]) -> Vec<BtcArgument> {
+    let numbered =
+        Regex::new(r"^\d+\.\s+(\S+)\s+\(([^)]*)\)\s*(.*)$").expect("valid argument regex");
+
+    let mut arguments: Vec<BtcArgument> = Vec::new();
+    // Set once an argument's nested fields start, so their text is not appended to it
+    let mut nested = false;
+    for line in lines.iter().map(|l| l.trim()).filter(|l| !l.is_empty()) {
+        if let Some(caps) = numbered.captures(line) {
+            let mut attributes = caps[2].split(", ");
+            let type_ = schema_type(attributes.next().unwrap_or_default());
+            arguments.push(BtcArgument {
+                names: caps[1].split('|').map(str::to_string).collect(),
+                description: caps[3].to_string(),
+                oneline_description: String::new(),
+                also_positional: false,
+                type_str: None,
+                required: attributes.any(|a| a == "required"),
+                hidden: false,
+                type_: type_.to_string(),
+            });
+            nested = false;
+        } else if let Some(argument) = arguments.last_mut() {
+            if !line.starts_with(|c: char| c.is_alphanumeric()) {
+                nested = true;
+            } else if !nested {
+                argument.description.push(' ');
+                argument.description.push_str(line);
+            }
+        }
+    }
+    arguments
+}
+
+/// Parse result entries until the line closing the enclosing object or array.
+///
+/// Entries look like `"key" : value, (type, optional) description`; a value ending in
+/// `{` or `[` opens a container whose entries follow.
+fn parse_results<'a>(lines: &mut impl Iterator<Item = &'a str>) -> Vec<BtcResult> {
+    let annotation =
+        Regex::new(r"\s?\(([a-z ]+)((?:, [^)]*)?)\)\s*(.*)$").expect("valid annotation regex");
+    let keyed = Regex::new(r#"^"([^"]*)"\s*:\s*(.*)$"#).expect("valid key regex");
+
+    let mut results = Vec::new();
+    while let Some(line) = lines.next() {
+        if line.starts_with('}') || line.starts_with(']') {
+            break;
+        }
+        if line.starts_with("...") {
+            continue;
+        }
+
+        let (head, type_, optional, description) = match annotation.captures(line) {
+            Some(caps) => (
+                line[..caps.get(0).unwrap().start()].trim(),
+                schema_type(&caps[1]),
+                caps[2].contains("optional"),
+                caps[3].to_string(),
+            ),
+            None => (line, "any", false, String::new()),
+        };
+        let (key_name, value) = match keyed.captures(head) {
+            Some(caps) => (caps.get(1).unwrap().as_str(), caps.get(2).unwrap().as_str()),
+            None => ("", head),
+        };
+        let value = value.trim_end_matches(',').trim_end();
+        let opens = value.ends_with('{') || value.ends_with('[');
+        let type_ = match type_ {
+            "any" if value.ends_with('{') => "object",
+            "any" if value.ends_with('[') => "array",
+            type_ => type_,
+        };
+
+        results.push(BtcResult {
+            type_: type_.to_string(),
+            optional,
+            description,
+            key_name: key_name.to_string(),
+            inner: if opens { parse_results(lines) } else { Vec::new() },
+            ..Default::default()
+        });
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GETBLOCK_HELP: &str = r#"getblock "blockhash" ( verbosity )
+
+If verbosity is 0, returns a string that is serialized, hex-encoded data for block 'hash'.
+If verbosity is 1, returns an Object with information about block <hash>.
+
+Arguments:
+1. blockhash    (string, required) The block hash
+2. verbosity    (numeric, optional, default=1) 0 for hex-encoded data, 1 for a JSON object,
+                and 2 for JSON object with transaction data
+
+Result (for verbosity = 0):
+"hex"    (string) A string that is serialized, hex-encoded data for block 'hash'
+
+Result (for verbosity = 1):
+{                                 (json object)
+  "hash" : "hex",                 (string) the block hash (same as provided)
+  "confirmations" : n,            (numeric) The number of confirmations
+  "tx" : [                        (json array) The transaction ids
+    "hex",                        (string) The transaction id
+    ...
+  ],
+  "previousblockhash" : "hex"     (string, optional) The hash of the previous block
+}
+
+Examples:
+> bitcoin-cli getblock "00000000c937983704a73af28acdec37b049d214adbda81d7e2a3dd146f6ed09"
+"#;
+
+    #[test]
+    fn test_parse_help_reads_arguments_and_results() {
+        let method = parse_help("getblock", GETBLOCK_HELP);
+        assert!(method.description.starts_with("If verbosity is 0, returns a string"));
+        assert!(method.description.ends_with("about block <hash>.\n"));
+        assert!(method.examples.starts_with("> bitcoin-cli getblock"));
+
+        assert_eq!(method.argument_names, ["blockhash", "verbosity"]);
+        assert_eq!(method.arguments[0].type_, "string");
+        assert!(method.arguments[0].required);
+        assert_eq!(method.arguments[1].type_, "number");
+        assert!(!method.arguments[1].required);
+        assert!(method.arguments[1]
+            .description
+            .ends_with("and 2 for JSON object with transaction data"));
+
+        let [raw, verbose] = &method.results[..] else { panic!("{:?}", method.results) };
+        assert_eq!((raw.type_.as_str(), raw.condition.as_str()), ("string", "for verbosity = 0"));
+        assert_eq!(verbose.type_, "object");
+        assert_eq!(verbose.condition, "for verbosity = 1");
+
+        let fields = verbose.inner.iter().map(|r| r.key_name.as_str()).collect::<Vec<_>>();
+        assert_eq!(fields, ["hash", "confirmations", "tx", "previousblockhash"]);
+        assert_eq!(verbose.inner[1].type_, "number");
+        assert_eq!(verbose.inner[2].type_, "array");
+        assert_eq!(verbose.inner[2].inner.len(), 1);
+        assert_eq!(verbose.inner[2].inner[0].description, "The transaction id");
+        assert!(verbose.inner[3].optional);
+    }
+
+    #[test]
+    fn test_parse_command_list_and_version() {
+        let help = "== Blockchain ==\ngetbestblockhash\ngetblock \"blockhash\" ( verbosity )\n\n\
+                    == Rawtransactions ==\nsendrawtransaction \"hexstring\"\n";
+        assert_eq!(
+            parse_command_list(help),
+            [
+                ("blockchain".to_string(), "getbestblockhash".to_string()),
+                ("blockchain".to_string(), "getblock".to_string()),
+                ("rawtransactions".to_string(), "sendrawtransaction".to_string()),
+            ]
+        );
+        assert_eq!(version_from_number(300000), "v30.0.0");
+        assert_eq!(version_from_number(290100), "v29.1.0");
+    }
+
+    #[test]
+    fn test_introspect_builds_a_loadable_schema() {
+        let mut server = mockito::Server::new();
+        let mut mock = |params: JsonValue, result: JsonValue| {
+            server
+                .mock("POST", "/")
+                .match_body(mockito::Matcher::PartialJson(params))
+                .with_body_from_request(move |request| {
+                    let request: JsonValue =
+                        serde_json::from_slice(request.body().unwrap()).unwrap();
+                    json!({"result": result, "id": request["id"]}).to_string().into()
+                })
+                .create()
+        };
+        let _network = mock(
+            json!({"method": "getnetworkinfo"}),
+            json!({"version": 300000, "subversion": "/Satoshi:30.0.0/"}),
+        );
+        let _list = mock(
+            json!({"method": "help", "params": []}),
+            json!("== Blockchain ==\ngetblock \"blockhash\" ( verbosity )"),
+        );
+        let _getblock =
+            mock(json!({"method": "help", "params": ["getblock"]}), json!(GETBLOCK_HELP));
+
+        let client = BlockingRpcClient::new(transport::Transport::new(server.url())).unwrap();
+        let schema = introspect(&client).unwrap();
+        assert_eq!(schema["version"], "v30.0.0");
+        assert_eq!(schema["methods"]["getblock"]["category"], "blockchain");
+
+        let methods = codegen::load_api_methods_from_str(&schema.to_string()).unwrap();
+        assert_eq!(methods.len(), 1);
+        assert_eq!(methods[0].results.len(), 2);
+    }
+}
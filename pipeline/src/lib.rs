@@ -4,24 +4,52 @@
 //! This module provides the core functionality for generating a complete Bitcoin RPC client
 //! library, including transport layer, type definitions, and test node helpers.
 
+pub mod introspect;
+
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt::Write as _;
-use std::fs::File;
-use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::{env, fs};
 
 use anyhow::{Context, Result};
+use bitcoin_hashes::{sha256, Hash};
+use bitcoin_rpc_types::BtcMethod;
 use codegen::generators::test_node::TestNodeGenerator;
-use codegen::generators::{BatchBuilderGenerator, ClientTraitGenerator, ResponseTypeCodeGenerator};
+use codegen::generators::{
+    verbosity, BatchBuilderGenerator, ClientTraitGenerator, CombinedClientGenerator,
+    OptionObjectGenerator, ResponseTypeCodeGenerator, RpcComponentGenerator, RpcErrorGenerator,
+};
 use codegen::namespace_scaffolder::ModuleGenerator;
+use codegen::utils::sanitize_ident;
 use codegen::versioning::Version;
 use codegen::{
-    load_api_methods_from_file, write_generated, CodeGenerator, TransportCodeGenerator,
+    load_api_methods_from_str, load_method_categories_from_str, load_option_objects_from_str,
+    write_generated, CodeGenerator, MethodCategories, SchemaValidator, TransportCodeGenerator,
     TransportCoreGenerator,
 };
+use regex::Regex;
 use serde_json::Value as JsonValue;
 
-/// Extract version from JSON
+/// Extract version from the input file name, e.g. `api_v29.json`, `api_v29_10.json`
+/// or `api_v27_1_0.json` (major, optional minor, optional patch).
+fn extract_version_from_filename(input_path: &Path) -> Result<String> {
+    let file_name = input_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| anyhow::anyhow!("Input path has no file name: {input_path:?}"))?;
+
+    let re = Regex::new(r"^api_v(\d+)(?:_(\d+))?(?:_(\d+))?\.json$").expect("valid version regex");
+    let caps = re.captures(file_name).ok_or_else(|| {
+        anyhow::anyhow!(
+            "Cannot extract version from file name '{file_name}': expected `api_v<major>[_<minor>[_<patch>]].json`"
+        )
+    })?;
+
+    let parts: Vec<&str> = caps.iter().skip(1).flatten().map(|m| m.as_str()).collect();
+    Ok(format!("v{}", parts.join(".")))
+}
+
+/// Extract version from JSON, falling back to the file name if there is no `version` field
 fn extract_version(input_path: &Path) -> Result<String> {
     let bytes = fs::read(input_path).with_context(|| {
         format!("Failed to read input file for version extraction: {input_path:?}")
@@ -29,24 +57,15 @@ fn extract_version(input_path: &Path) -> Result<String> {
     let json: JsonValue = serde_json::from_slice(&bytes)
         .with_context(|| format!("Failed to parse JSON from: {input_path:?}"))?;
 
-    let v = json
-        .get("version")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| anyhow::anyhow!("Missing 'version' field in JSON"))?;
-
-    let v = v.trim();
-    let v = if v.starts_with('v') { v.to_string() } else { format!("v{}", v) };
-
-    // Normalize to major.minor (drop patch if present)
-    let parts: Vec<&str> = v.trim_start_matches('v').split('.').collect();
-    let normalized = match parts.as_slice() {
-        [maj] => format!("v{}", maj),
-        [maj, min] => format!("v{}.{}", maj, min),
-        [maj, min, _patch, ..] => format!("v{}.{}", maj, min),
-        _ => v,
+    let v = match json.get("version").and_then(|v| v.as_str()) {
+        Some(v) => v.to_string(),
+        None =>
+            extract_version_from_filename(input_path).context("Missing 'version' field in JSON")?,
     };
 
-    Ok(normalized)
+    // `Version::from_string` drops a zero patch, so e.g. `v30.0.0` still generates `v30`
+    let v = v.trim();
+    Ok(if v.starts_with('v') { v.to_string() } else { format!("v{v}") })
 }
 
 /// Generates a complete Bitcoin RPC client library structure and code.
@@ -101,48 +120,303 @@ pub fn run(input_path: Option<&PathBuf>) -> Result<()> {
         ));
     }
 
-    let crate_root = project_root.join("bitcoin-rpc-midas");
+    run_with_output(&input_path, &project_root.join("bitcoin-rpc-midas"))
+}
 
-    if crate_root.exists() {
-        fs::remove_dir_all(&crate_root).with_context(|| {
-            format!("Failed to remove existing bitcoin-rpc-midas directory: {crate_root:?}")
-        })?;
+/// Generates the `bitcoin-rpc-midas` crate into an explicit `output_root` directory.
+///
+/// If `output_root` already exists it must be empty or look like a previously
+/// generated crate (it contains the [`GENERATED_SENTINEL`] file); it is then regenerated
+/// in place. Only files whose contents changed are rewritten, so cargo does not rebuild
+/// the whole crate, and sources under `src/` that are no longer generated are removed.
+/// Any other existing directory is left untouched and an error is returned, so a
+/// mistyped path can never delete unrelated files.
+///
+/// # Arguments
+///
+/// * `input_path` - Path to the JSON API spec (see [`run`]).
+/// * `output_root` - Directory that will contain the generated crate's `Cargo.toml`.
+///
+/// # Returns
+///
+/// Returns `Result<()>` indicating success or failure of the generation process
+pub fn run_with_output(input_path: &Path, output_root: &Path) -> Result<()> {
+    if !input_path.exists() {
+        return Err(anyhow::anyhow!("Input file not found: {:?}", input_path));
     }
 
-    let src_dir = crate_root.join("src");
-    fs::create_dir_all(&src_dir)
-        .with_context(|| format!("Failed to create src directory: {src_dir:?}"))?;
+    // Extract version early to pass to functions that need it (prefer JSON's version field)
+    let version_str = extract_version(input_path)?;
+    let target_version = Version::from_string(&version_str)?;
+    run_with_version(input_path, output_root, &target_version)
+}
 
-    copy_templates_to(&src_dir)
-        .with_context(|| format!("Failed to copy template files to {src_dir:?}"))?;
+/// Generates the `bitcoin-rpc-midas` crate into `output_root` for an explicit
+/// `target_version`, instead of the version the schema declares.
+///
+/// See [`run_with_output`] for how an existing `output_root` is handled.
+///
+/// # Arguments
+///
+/// * `input_path` - Path to the JSON API spec (see [`run`]).
+/// * `output_root` - Directory that will contain the generated crate's `Cargo.toml`.
+/// * `target_version` - The Bitcoin Core version being targeted
+///
+/// # Returns
+///
+/// Returns `Result<()>` indicating success or failure of the generation process
+pub fn run_with_version(
+    input_path: &Path,
+    output_root: &Path,
+    target_version: &Version,
+) -> Result<()> {
+    if !input_path.exists() {
+        return Err(anyhow::anyhow!("Input file not found: {:?}", input_path));
+    }
 
-    // Extract version early to pass to functions that need it (prefer JSON's version field)
-    let version_str = extract_version(&input_path)?;
+    check_output_root(output_root)?;
+    let rendered = render_crate(input_path, target_version)?;
+    let touched = sync_output_root(rendered.path(), output_root)?;
+    tracing::info!("{touched} files changed in {output_root:?}");
+    Ok(())
+}
+
+/// Lists the files [`run_with_output`] would write, without touching `output_root`.
+///
+/// The schema is parsed and validated and the crate is rendered into a scratch directory,
+/// so the plan matches a real run byte for byte. `output_root` is checked the same way a
+/// real run checks it, but neither removed nor written.
+///
+/// # Arguments
+///
+/// * `input_path` - Path to the JSON API spec (see [`run`]).
+/// * `output_root` - Directory that would contain the generated crate's `Cargo.toml`.
+///
+/// # Returns
+///
+/// Returns every file path under `output_root` with its size in bytes, sorted by path
+pub fn plan_with_output(input_path: &Path, output_root: &Path) -> Result<Vec<(PathBuf, usize)>> {
+    if !input_path.exists() {
+        return Err(anyhow::anyhow!("Input file not found: {:?}", input_path));
+    }
+
+    let version_str = extract_version(input_path)?;
     let target_version = Version::from_string(&version_str)?;
-    println!("Generating midas client for Bitcoin Core {}", version_str);
+    plan_with_version(input_path, output_root, &target_version)
+}
+
+/// Lists the files [`run_with_version`] would write, without touching `output_root`.
+///
+/// See [`plan_with_output`].
+///
+/// # Arguments
+///
+/// * `input_path` - Path to the JSON API spec (see [`run`]).
+/// * `output_root` - Directory that would contain the generated crate's `Cargo.toml`.
+/// * `target_version` - The Bitcoin Core version being targeted
+///
+/// # Returns
+///
+/// Returns every file path under `output_root` with its size in bytes, sorted by path
+pub fn plan_with_version(
+    input_path: &Path,
+    output_root: &Path,
+    target_version: &Version,
+) -> Result<Vec<(PathBuf, usize)>> {
+    if !input_path.exists() {
+        return Err(anyhow::anyhow!("Input file not found: {:?}", input_path));
+    }
+
+    check_output_root(output_root)?;
+
+    let rendered = render_crate(input_path, target_version)?;
+    let mut plan = Vec::new();
+    for path in list_files(rendered.path())? {
+        let len = fs::metadata(rendered.path().join(&path))?.len() as usize;
+        plan.push((output_root.join(path), len));
+    }
+    Ok(plan)
+}
+
+/// Render the whole generated crate, marked with [`GENERATED_SENTINEL`], into a fresh
+/// scratch directory.
+fn render_crate(input_path: &Path, target_version: &Version) -> Result<tempfile::TempDir> {
+    let scratch = tempfile::tempdir().context("Failed to create scratch directory")?;
+    fs::write(
+        scratch.path().join(GENERATED_SENTINEL),
+        "Generated by bitcoin-rpc-codegen. This directory is regenerated in place by the pipeline.\n",
+    )
+    .with_context(|| format!("Failed to write {GENERATED_SENTINEL} in {scratch:?}"))?;
+    write_crate(input_path, scratch.path(), target_version)?;
+    Ok(scratch)
+}
+
+/// Write the generated crate into the empty directory `output_root`.
+fn write_crate(input_path: &Path, output_root: &Path, target_version: &Version) -> Result<()> {
+    let src_dir = output_root.join("src");
+    fs::create_dir_all(&src_dir)
+        .with_context(|| format!("Failed to create src directory: {src_dir:?}"))?;
 
-    write_cargo_toml(&crate_root, &target_version)
-        .with_context(|| format!("Failed to write Cargo.toml in: {crate_root:?}"))?;
+    tracing::info!("Generating midas client for Bitcoin Core {}", target_version.as_str());
 
-    let gitignore_path = crate_root.join(".gitignore");
+    let schema =
+        fs::read_to_string(input_path).with_context(|| format!("Failed to read {input_path:?}"))?;
+    let categories =
+        load_method_categories_from_str(&schema).context("Failed to parse API categories")?;
+    write_cargo_toml(output_root, target_version, &categories)
+        .with_context(|| format!("Failed to write Cargo.toml in: {output_root:?}"))?;
+
+    let gitignore_path = output_root.join(".gitignore");
     fs::write(&gitignore_path, "/target\n/Cargo.lock\n")
         .with_context(|| format!("Failed to write .gitignore at {gitignore_path:?}"))?;
 
-    write_readme(&crate_root, &target_version)
-        .with_context(|| format!("Failed to write README.md in: {crate_root:?}"))?;
+    write_readme(output_root, target_version)
+        .with_context(|| format!("Failed to write README.md in: {output_root:?}"))?;
 
-    write_contributing(&crate_root)
-        .with_context(|| format!("Failed to write CONTRIBUTING.md in: {crate_root:?}"))?;
+    write_contributing(output_root)
+        .with_context(|| format!("Failed to write CONTRIBUTING.md in: {output_root:?}"))?;
 
-    write_license(&crate_root)
-        .with_context(|| format!("Failed to write LICENSE.md in: {crate_root:?}"))?;
+    write_license(output_root)
+        .with_context(|| format!("Failed to write LICENSE.md in: {output_root:?}"))?;
 
-    generate_into(&src_dir, &input_path, &target_version)
+    generate_into(&src_dir, input_path, target_version)
         .with_context(|| format!("generate_into failed for src_dir {src_dir:?}"))?;
 
     Ok(())
 }
 
+/// Parse the schema at `input_path` the way generation would, without writing anything.
+///
+/// # Returns
+///
+/// Returns the number of methods the schema describes
+pub fn check_schema(input_path: &Path) -> Result<usize> {
+    let schema =
+        fs::read_to_string(input_path).with_context(|| format!("Failed to read {input_path:?}"))?;
+    let methods = load_api_methods_from_str(&schema).context("Failed to parse API JSON")?;
+    validate_schema(&methods)?;
+    load_option_objects_from_str(&schema).context("Failed to parse API option objects")?;
+    Ok(methods.len())
+}
+
+/// Fail with every invariant `methods` violates, one per line.
+fn validate_schema(methods: &[BtcMethod]) -> Result<()> {
+    SchemaValidator::validate_methods(methods).map_err(|errors| {
+        let problems = errors.iter().map(|e| format!("\n  - {e}")).collect::<String>();
+        anyhow::anyhow!("Invalid API schema ({} problems):{problems}", errors.len())
+    })
+}
+
+/// Marker file written at the root of every generated crate. Only directories
+/// containing it are ever written to by [`run_with_output`].
+pub const GENERATED_SENTINEL: &str = ".bitcoin-rpc-codegen";
+
+/// Whether `dir` holds a crate produced by this pipeline: it has the sentinel, or
+/// (for crates generated before the sentinel existed) a `bitcoin-rpc-midas` manifest.
+fn looks_generated(dir: &Path) -> bool {
+    dir.join(GENERATED_SENTINEL).is_file()
+        || fs::read_to_string(dir.join("Cargo.toml"))
+            .is_ok_and(|toml| toml.contains("name = \"bitcoin-rpc-midas\""))
+}
+
+/// Fail if `output_root` is an existing, non-empty directory that has no sentinel.
+fn check_output_root(output_root: &Path) -> Result<()> {
+    if !output_root.exists() {
+        return Ok(());
+    }
+    let is_empty = fs::read_dir(output_root)
+        .with_context(|| format!("Failed to read output directory: {output_root:?}"))?
+        .next()
+        .is_none();
+    if !is_empty && !looks_generated(output_root) {
+        return Err(anyhow::anyhow!(
+            "Refusing to overwrite {output_root:?}: it is not empty and has no \
+             {GENERATED_SENTINEL} file, so it does not look like a generated crate"
+        ));
+    }
+    Ok(())
+}
+
+/// Make `output_root` match the crate rendered into `rendered`.
+///
+/// Files whose contents are unchanged are left alone, keeping their mtimes. Files under
+/// `src/` that were not rendered are removed; nothing else is, so `target/` and other
+/// local files survive.
+///
+/// # Returns
+///
+/// Returns the number of files written or removed
+fn sync_output_root(rendered: &Path, output_root: &Path) -> Result<usize> {
+    check_output_root(output_root)?;
+
+    let mut touched = 0;
+    let files = list_files(rendered)?;
+    for path in &files {
+        let contents = fs::read(rendered.join(path))?;
+        let dest = output_root.join(path);
+        if fs::read(&dest).is_ok_and(|existing| existing == contents) {
+            continue;
+        }
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {parent:?}"))?;
+        }
+        fs::write(&dest, contents).with_context(|| format!("Failed to write {dest:?}"))?;
+        touched += 1;
+    }
+
+    let src_dir = output_root.join("src");
+    if src_dir.is_dir() {
+        for path in list_files(&src_dir)? {
+            let path = Path::new("src").join(path);
+            if files.binary_search(&path).is_err() {
+                let stale = output_root.join(&path);
+                fs::remove_file(&stale)
+                    .with_context(|| format!("Failed to remove stale file: {stale:?}"))?;
+                touched += 1;
+            }
+        }
+        remove_empty_dirs(&src_dir)?;
+    }
+
+    Ok(touched)
+}
+
+/// Every file under `dir`, relative to it and sorted.
+fn list_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        for entry in
+            fs::read_dir(&current).with_context(|| format!("Failed to read {current:?}"))?
+        {
+            let path = entry?.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else {
+                files.push(path.strip_prefix(dir)?.to_path_buf());
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Remove the directories under `dir` that are left empty.
+fn remove_empty_dirs(dir: &Path) -> Result<()> {
+    for entry in fs::read_dir(dir).with_context(|| format!("Failed to read {dir:?}"))? {
+        let path = entry?.path();
+        if path.is_dir() {
+            remove_empty_dirs(&path)?;
+            if fs::read_dir(&path)?.next().is_none() {
+                fs::remove_dir(&path)
+                    .with_context(|| format!("Failed to remove empty directory: {path:?}"))?;
+            }
+        }
+    }
+    Ok(())
+}
+
 /// Find the workspace root by looking for the root Cargo.toml
 ///
 ///
@@ -174,94 +448,228 @@ fn find_project_root() -> Result<PathBuf> {
 /// * `input_path` - Path to the input JSON file
 /// * `target_version` - The Bitcoin Core version being targeted
 pub fn generate_into(out_dir: &Path, input_path: &Path, target_version: &Version) -> Result<()> {
-    let subdirs = ["transport", "responses", "node", "client_trait"];
-    for sub in &subdirs {
-        let module_dir = out_dir.join(sub);
-        fs::create_dir_all(&module_dir)
-            .with_context(|| format!("Failed to create module directory: {module_dir:?}"))?;
-
-        // Skip creating mod.rs for node directory since we'll handle it separately
-        if *sub != "node" {
-            let mod_rs = module_dir.join("mod.rs");
-            if !mod_rs.exists() {
-                fs::write(&mod_rs, format!("// Auto-generated `{sub}` module\n"))
-                    .with_context(|| format!("Failed to write mod.rs at {mod_rs:?}"))?;
-            }
+    let schema =
+        fs::read_to_string(input_path).with_context(|| format!("Failed to read {input_path:?}"))?;
+    generate_into_from_schema(out_dir, &schema, target_version)
+}
+
+/// Generates all the code into the specified output directory from a schema held in memory
+///
+/// Templates are embedded at build time, so nothing but `out_dir` is touched on disk and the
+/// schema can come from `include_str!` where the source tree is unavailable. This writes
+/// the files of [`generate_files`], running the generators' output through `rustfmt`.
+///
+/// # Arguments
+///
+/// * `out_dir` - The output directory to write generated code to
+/// * `schema` - The JSON API spec (see [`run`])
+/// * `target_version` - The Bitcoin Core version being targeted
+pub fn generate_into_from_schema(
+    out_dir: &Path,
+    schema: &str,
+    target_version: &Version,
+) -> Result<()> {
+    // Reject a bad schema before anything is written
+    render_tree(&[(schema, target_version.clone())])?.write(out_dir)
+}
+
+/// Generates clients for several Bitcoin Core versions into one output directory
+///
+/// Writes the files of [`generate_files_for_versions`], like [`generate_into_from_schema`].
+///
+/// # Arguments
+///
+/// * `out_dir` - The output directory to write generated code to
+/// * `schemas` - The JSON API spec of each version, with the version it describes
+pub fn generate_into_from_schemas(out_dir: &Path, schemas: &[(&str, Version)]) -> Result<()> {
+    render_tree(schemas)?.write(out_dir)
+}
+
+/// Generates all the code [`generate_into_from_schema`] writes, purely in memory
+///
+/// Nothing is read from or written to disk, so the output can be inspected, post-processed
+/// and routed anywhere (a zip, a git tree, ...). Sources are returned as the generators
+/// emit them, before the `rustfmt` pass [`generate_into_from_schema`] applies.
+///
+/// # Arguments
+///
+/// * `schema` - The JSON API spec (see [`run`])
+/// * `target_version` - The Bitcoin Core version being targeted
+///
+/// # Returns
+///
+/// Returns `(path relative to the output directory, source)` pairs, sorted by path
+pub fn generate_files(schema: &str, target_version: &Version) -> Result<Vec<(PathBuf, String)>> {
+    generate_files_for_versions(&[(schema, target_version.clone())])
+}
+
+/// Generates clients for several Bitcoin Core versions side by side, purely in memory
+///
+/// The newest version is the primary one: everything [`generate_files`] generates comes
+/// from its schema. Every older version adds its response types as
+/// `responses::<version>_responses` and its client traits as `client_trait::<version>`,
+/// e.g. `client_trait::v28::BitcoinClientV28`, which are not split by category features.
+/// The generated `ClientVersion` picks the client to use for a node at runtime.
+///
+/// # Arguments
+///
+/// * `schemas` - The JSON API spec of each version, with the version it describes
+///
+/// # Returns
+///
+/// Returns `(path relative to the output directory, source)` pairs, sorted by path
+pub fn generate_files_for_versions(schemas: &[(&str, Version)]) -> Result<Vec<(PathBuf, String)>> {
+    Ok(render_tree(schemas)?.files.into_iter().collect())
+}
+
+/// Files generated in memory, keyed by their path relative to the output directory
+#[derive(Debug, Default)]
+struct GeneratedTree {
+    files: BTreeMap<PathBuf, String>,
+    /// Generator output, which is formatted with `rustfmt` when written
+    formatted: BTreeSet<PathBuf>,
+}
+
+impl GeneratedTree {
+    /// Add generator output to module `dir`, appending `.rs` to file names like
+    /// [`write_generated`]
+    fn add_generated(&mut self, dir: &str, files: &[(String, String)]) {
+        for (name, src) in files {
+            let name = if name.ends_with(".rs") { name.clone() } else { format!("{name}.rs") };
+            let path = Path::new(dir).join(name);
+            self.formatted.insert(path.clone());
+            self.files.insert(path, src.clone());
         }
     }
 
-    copy_templates_to(out_dir).with_context(|| "Failed to copy template files")?;
+    /// Add a file that is written verbatim, replacing any earlier file at `path`
+    fn add(&mut self, path: impl Into<PathBuf>, src: impl Into<String>) {
+        let path = path.into();
+        self.formatted.remove(&path);
+        self.files.insert(path, src.into());
+    }
 
-    let node_dir = out_dir.join("node");
-    let node_mod_rs = node_dir.join("mod.rs");
+    /// Write every file under `out_dir`, formatting generator output with one `rustfmt` run
+    fn write(&self, out_dir: &Path) -> Result<()> {
+        let mut generated = Vec::new();
+        for (path, src) in &self.files {
+            if self.formatted.contains(path) {
+                generated.push((path.to_string_lossy().into_owned(), src.clone()));
+                continue;
+            }
+            let dest = out_dir.join(path);
+            if let Some(parent) = dest.parent() {
+                fs::create_dir_all(parent)
+                    .with_context(|| format!("Failed to create directory: {parent:?}"))?;
+            }
+            fs::write(&dest, src).with_context(|| format!("Failed to write {dest:?}"))?;
+        }
+        write_generated(out_dir, &generated).context("Failed to write generated files")?;
+        Ok(())
+    }
+}
 
-    let project_root = find_project_root()?;
-    let node_impl_src = std::fs::read_to_string(project_root.join("templates/node.rs"))
-        .with_context(|| "Failed to read templates/node.rs")?;
-    std::fs::write(&node_mod_rs, node_impl_src)
-        .with_context(|| format!("Failed to write node/mod.rs at {node_mod_rs:?}"))?;
+/// Generate every file of the output directory from `schemas`, the newest of which is the
+/// primary version (see [`generate_files_for_versions`])
+fn render_tree(schemas: &[(&str, Version)]) -> Result<GeneratedTree> {
+    let mut schemas = schemas.to_vec();
+    schemas.sort_by(|(_, a), (_, b)| a.cmp(b));
+    if let Some(pair) = schemas.windows(2).find(|pair| pair[0].1 == pair[1].1) {
+        return Err(anyhow::anyhow!("Version {} is given more than once", pair[0].1.as_str()));
+    }
+    let Some(((schema, target_version), older)) = schemas.split_last() else {
+        return Err(anyhow::anyhow!("No schema to generate from"));
+    };
 
-    // Create test_node directory without writing mod.rs
-    let test_node_dir = out_dir.join("test_node");
-    fs::create_dir_all(&test_node_dir)
-        .with_context(|| format!("Failed to create test_node directory: {test_node_dir:?}"))?;
+    let norm = load_api_methods_from_str(schema).context("Failed to parse API JSON")?;
+    validate_schema(&norm)?;
 
-    let norm = load_api_methods_from_file(input_path).context("Failed to parse API JSON")?;
+    let mut tree = GeneratedTree::default();
 
-    let tx_files = TransportCodeGenerator::new(target_version.clone()).generate(&norm);
-    write_generated(out_dir.join("transport"), &tx_files)
-        .context("Failed to write transport files")?;
+    for (filename, contents) in TEMPLATE_FILES {
+        tree.add(*filename, *contents);
+    }
+    tree.add(Path::new("node").join("mod.rs"), NODE_TEMPLATE);
 
-    let core_files = TransportCoreGenerator.generate(&norm);
-    write_generated(out_dir.join("transport"), &core_files)
-        .context("Failed to write core transport files")?;
+    let schema_sha256 = sha256::Hash::hash(schema.as_bytes()).to_string();
+    tree.add(GENERATED_MANIFEST, render_manifest(target_version, &norm, &schema_sha256)?);
+
+    let option_objects =
+        load_option_objects_from_str(schema).context("Failed to parse API option objects")?;
+    let categories =
+        load_method_categories_from_str(schema).context("Failed to parse API categories")?;
 
-    let batch_files = BatchBuilderGenerator.generate(&norm);
-    write_generated(out_dir.join("transport"), &batch_files)
-        .context("Failed to write batch builder files")?;
+    // Wrappers, client methods and response types also cover each verbosity form
+    let with_overloads = verbosity::with_overloads(&norm)?;
 
-    ensure_rpc_client(&out_dir.join("transport")).context("Failed to ensure rpc_client stub")?;
+    let tx_files = TransportCodeGenerator::new(target_version.clone())
+        .with_option_objects(option_objects.clone())
+        .generate(&with_overloads);
+    let core_files = TransportCoreGenerator.generate(&norm);
+    let batch_files =
+        BatchBuilderGenerator::default().with_categories(categories.clone()).generate(&norm);
+    let option_files = OptionObjectGenerator::new(&option_objects).generate(&norm);
+    let error_files = RpcErrorGenerator.generate(&norm);
+    let component_files =
+        RpcComponentGenerator::default().with_categories(categories.clone()).generate(&norm);
 
     let all_transport_files = tx_files
         .iter()
         .chain(core_files.iter())
         .chain(batch_files.iter())
+        .chain(option_files.iter())
+        .chain(error_files.iter())
+        .chain(component_files.iter())
         .cloned()
         .collect::<Vec<_>>();
-    write_mod_rs(&out_dir.join("transport"), &all_transport_files)
-        .context("Failed to write transport mod.rs")?;
-
-    let client_trait_files = ClientTraitGenerator::new(target_version.as_str()).generate(&norm);
-    write_generated(out_dir.join("client_trait"), &client_trait_files)
-        .context("Failed to write client trait files")?;
-
-    write_mod_rs(&out_dir.join("client_trait"), &client_trait_files)
-        .context("Failed to write client_trait mod.rs")?;
-
-    let ty_files = ResponseTypeCodeGenerator::new(target_version.as_str()).generate(&norm);
-    write_generated(out_dir.join("responses"), &ty_files)
-        .context("Failed to write response types files")?;
-    write_mod_rs(&out_dir.join("responses"), &ty_files)
-        .context("Failed to write responses mod.rs")?;
+    tree.add_generated("transport", &all_transport_files);
+    tree.files
+        .entry(Path::new("transport").join("rpc_client.rs"))
+        .or_insert_with(|| RPC_CLIENT_STUB.to_string());
+    tree.add(
+        Path::new("transport").join("mod.rs"),
+        render_mod_rs("transport", &all_transport_files, &categories)?,
+    );
 
-    let tn_files = TestNodeGenerator::new(target_version.clone()).generate(&norm);
+    let client_trait_files = ClientTraitGenerator::new(target_version.as_str())
+        .with_blocking(true)
+        .with_categories(categories.clone())
+        .generate(&with_overloads);
+    tree.add_generated("client_trait", &client_trait_files);
+    let mut client_trait_mod_rs =
+        render_mod_rs("client_trait", &client_trait_files, &MethodCategories::default())?;
+    let mut combined =
+        CombinedClientGenerator::new(target_version.clone()).with_categories(categories.clone());
+    for (schema, version) in older {
+        let methods = render_older_version(&mut tree, schema, version)?;
+        combined = combined.with_older_version(version.clone(), methods);
+        writeln!(client_trait_mod_rs, "pub mod {};", version.as_module_name())?;
+    }
+    tree.add(Path::new("client_trait").join("mod.rs"), client_trait_mod_rs);
+    tree.add_generated("", &combined.generate(&with_overloads));
+
+    let ty_files = ResponseTypeCodeGenerator::new(target_version.as_str())
+        .with_categories(categories.clone())
+        .generate(&with_overloads);
+    tree.add_generated("responses", &ty_files);
+
+    let tn_files = TestNodeGenerator::new(target_version.clone())
+        .with_categories(categories.clone())
+        .generate(&norm);
+    tree.add_generated("test_node", &tn_files);
+    tree.add(
+        Path::new("test_node").join("mod.rs"),
+        render_mod_rs("test_node", &tn_files, &MethodCategories::default())?,
+    );
 
-    write_generated(&test_node_dir, &tn_files).context("Failed to write test_node files")?;
-    write_mod_rs(&test_node_dir, &tn_files).context("Failed to write test_node mod.rs")?;
+    let test_node_cfg = test_node_cfg(&categories);
+    let test_node_cfg_indented = test_node_cfg.replace('\n', "\n    ");
 
-    let lib_rs = out_dir.join("lib.rs");
-    let mut file =
-        File::create(&lib_rs).with_context(|| format!("Failed to create lib.rs at {lib_rs:?}"))?;
-
-    let version_nodots = target_version.as_str().replace('.', "_");
-    let version_capitalized = if let Some(stripped) = version_nodots.strip_prefix('v') {
-        format!("V{}", stripped)
-    } else {
-        version_nodots.to_uppercase()
-    };
+    let version_capitalized = client_suffix(target_version);
 
+    let mut lib_rs = String::new();
     writeln!(
-        file,
+        lib_rs,
         "//! Generated Bitcoin RPC client library.\n\
      //!\n\
      //! This library provides a strongly-typed interface to the Bitcoin RPC API.\n\
@@ -269,44 +677,227 @@ pub fn generate_into(out_dir: &Path, input_path: &Path, target_version: &Version
      // Core modules\n\
      pub mod config;\n\
      pub mod client_trait;\n\
+     pub mod combined;\n\
      pub mod node;\n\
      pub mod test_config;\n\
-     pub mod test_node;\n\
+     {test_node_cfg}pub mod test_node;\n\
      pub mod transport;\n\
-     pub mod responses;\n\n\
+     pub mod responses;\n\
+     pub mod versions;\n\
+     #[cfg(feature = \"zmq\")]\n\
+     pub mod zmq;\n\n\
      // Re-exports for ergonomic access\n\
      pub use config::Config;\n\
      pub use client_trait::client::BitcoinClient{version_capitalized};\n\
+     pub use combined::{{CombinedClient, CombinedClientError}};\n\
+     #[cfg(feature = \"blocking\")]\n\
+     pub use client_trait::blocking::{{BlockingBitcoinClient{version_capitalized}, BlockingTransport}};\n\
      pub use node::BitcoinNodeManager;\n\
      pub use bitcoin::{{Network, Txid}};\n\
      pub use test_config::TestConfig;\n\
-     pub use test_node::client::BitcoinTestClient;\n\
+     {test_node_cfg}pub use test_node::client::BitcoinTestClient;\n\
      pub use responses::*;\n\
-     pub use transport::{{\n    DefaultTransport,\n    TransportError,\n    RpcClient,\n    BatchBuilder,\n}};\n"
+     pub use versions::ClientVersion;\n\
+     pub use transport::{{\n    AddressType,\n    DefaultTransport,\n    Descriptor,\n    EstimateMode,\n    TransportError,\n    RpcClient,\n    NodeVersion,\n    SyncStatus,\n    BatchBuilder,\n}};\n\n\
+     /// The recommended imports: `use bitcoin_rpc_midas::prelude::*;`\n\
+     ///\n\
+     /// Re-exports the clients, configuration, errors and response types without the\n\
+     /// transport internals the crate root's glob re-exports also pull in.\n\
+     pub mod prelude {{\n    \
+     pub use crate::client_trait::client::BitcoinClient{version_capitalized};\n    \
+     pub use crate::combined::{{CombinedClient, CombinedClientError}};\n    \
+     #[cfg(feature = \"blocking\")]\n    \
+     pub use crate::client_trait::blocking::BlockingBitcoinClient{version_capitalized};\n    \
+     pub use crate::config::Config;\n    \
+     pub use crate::responses::*;\n    \
+     {test_node_cfg_indented}pub use crate::test_node::client::BitcoinTestClient;\n    \
+     pub use crate::transport::{{AddressType, BitcoinRpcError, DefaultTransport, Descriptor, EstimateMode, RpcClient, TransportError}};\n    \
+     pub use bitcoin::Network;\n\
+     }}\n\n\
+     /// Bitcoin Core version this crate was generated for (see `{GENERATED_MANIFEST}`).\n\
+     pub const GENERATED_FROM: &str = \"{version}\";\n\
+     /// SHA-256 of the API schema this crate was generated from.\n\
+     pub const GENERATED_SCHEMA_SHA256: &str = \"{schema_sha256}\";\n",
+        version = target_version.as_str(),
     )?;
 
-    ModuleGenerator::new(vec![target_version.clone()], out_dir.to_path_buf())
-        .generate_all()
-        .context("ModuleGenerator failed")?;
+    tree.add("lib.rs", lib_rs);
 
-    let project_root = find_project_root()?;
-    let batch_transport_src =
-        std::fs::read_to_string(project_root.join("transport/src/batch_transport.rs"))
-            .with_context(|| {
-                format!(
-                    "Failed to read batch_transport.rs at {:?}",
-                    project_root.join("transport/src/batch_transport.rs")
-                )
-            })?;
-    let dest_path = out_dir.join("transport").join("batch_transport.rs");
-
-    std::fs::create_dir_all(dest_path.parent().unwrap())
-        .with_context(|| format!("Failed to create directory for {dest_path:?}"))?;
-
-    std::fs::write(&dest_path, batch_transport_src)
-        .with_context(|| format!("Failed to write batch_transport.rs at {dest_path:?}"))?;
+    let versions = schemas.iter().map(|(_, version)| version.clone()).collect::<Vec<_>>();
+    tree.add("versions.rs", render_versions_rs(&versions)?);
 
-    Ok(())
+    for (path, src) in ModuleGenerator::new(versions, PathBuf::new())
+        .render_all()
+        .context("ModuleGenerator failed")?
+    {
+        tree.add(path, src);
+    }
+
+    // Transport helpers shared verbatim with the `transport` crate
+    for (name, src) in SHARED_TRANSPORT_FILES {
+        tree.add(Path::new("transport").join(name), *src);
+    }
+
+    Ok(tree)
+}
+
+/// Add the response types and client traits of `version`, generated alongside the primary
+/// version, as `responses/<version>_responses.rs` and `client_trait/<version>/`
+///
+/// Returns the methods the client traits were generated from
+fn render_older_version(
+    tree: &mut GeneratedTree,
+    schema: &str,
+    version: &Version,
+) -> Result<Vec<BtcMethod>> {
+    let methods = load_api_methods_from_str(schema)
+        .with_context(|| format!("Failed to parse API JSON for {}", version.as_str()))?;
+    validate_schema(&methods)?;
+    let with_overloads = verbosity::with_overloads(&methods)?;
+
+    let ty_files = ResponseTypeCodeGenerator::new(version.as_str()).generate(&with_overloads);
+    tree.add_generated("responses", &ty_files);
+
+    let client_trait_files =
+        ClientTraitGenerator::new(version.as_str()).with_blocking(true).generate(&with_overloads);
+    tree.add_generated(&format!("client_trait/{}", version.as_module_name()), &client_trait_files);
+    Ok(with_overloads)
+}
+
+/// Suffix of the client traits of `version`, e.g. `V29_1` in `BitcoinClientV29_1`
+fn client_suffix(version: &Version) -> String {
+    let version_nodots = version.as_str().replace('.', "_");
+    if let Some(stripped) = version_nodots.strip_prefix('v') {
+        format!("V{}", stripped)
+    } else {
+        version_nodots.to_uppercase()
+    }
+}
+
+/// Render `versions.rs`, whose `ClientVersion` picks the generated client matching a node
+///
+/// # Arguments
+///
+/// * `versions` - Every generated version, oldest first
+///
+/// # Returns
+///
+/// Returns the module's source
+fn render_versions_rs(versions: &[Version]) -> Result<String> {
+    let newest = versions.last().context("No version to generate")?;
+    let mut variants = String::new();
+    let mut all = Vec::new();
+    let mut core_versions = String::new();
+    for version in versions {
+        let variant = client_suffix(version);
+        let module = if version == newest {
+            "client_trait".to_string()
+        } else {
+            format!("client_trait::{}", version.as_module_name())
+        };
+        writeln!(
+            variants,
+            "    /// Bitcoin Core {}, served by `{module}::BitcoinClient{variant}`\n    {variant},",
+            version.as_doc_version()
+        )?;
+        all.push(format!("ClientVersion::{variant}"));
+        writeln!(
+            core_versions,
+            "            ClientVersion::{variant} => ({}, {}, {}),",
+            version.major(),
+            version.minor(),
+            version.patch()
+        )?;
+    }
+    let allow = if versions.iter().any(|version| version.minor() != 0 || version.patch() != 0) {
+        "#[allow(non_camel_case_types)]\n"
+    } else {
+        ""
+    };
+
+    Ok(format!(
+        r#"//! The Bitcoin Core versions this crate has clients for.
+
+use crate::transport::{{NodeVersion, RpcClient, TransportError}};
+
+/// A Bitcoin Core version this crate has a client for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+{allow}pub enum ClientVersion {{
+{variants}}}
+
+impl ClientVersion {{
+    /// Every generated version, oldest first
+    pub const ALL: &'static [ClientVersion] = &[{all}];
+
+    /// The `(major, minor, patch)` Bitcoin Core version the client was generated for
+    pub fn core_version(self) -> (u32, u32, u32) {{
+        match self {{
+{core_versions}        }}
+    }}
+
+    /// The newest client not newer than `node`, or `None` if the node predates them all
+    pub fn for_node(node: NodeVersion) -> Option<Self> {{
+        Self::ALL.iter().rev().copied().find(|v| v.core_version() <= (node.major, node.minor, node.patch))
+    }}
+
+    /// Ask the node for its version and pick the client to use with [`Self::for_node`]
+    pub async fn detect(client: &RpcClient) -> Result<Option<Self>, TransportError> {{
+        Ok(Self::for_node(client.detect_version().await?))
+    }}
+}}
+"#,
+        all = all.join(", "),
+    ))
+}
+
+/// Categories whose methods `BitcoinTestClient`'s helpers call to mine, fund wallets and
+/// reset the chain.
+const TEST_NODE_CATEGORIES: &[&str] = &["blockchain", "hidden", "wallet"];
+
+/// `#[cfg]` line requiring the features of [`TEST_NODE_CATEGORIES`] the crate declares, or
+/// an empty string if it declares none.
+fn test_node_cfg(categories: &MethodCategories) -> String {
+    let features = categories.features();
+    let required = TEST_NODE_CATEGORIES
+        .iter()
+        .filter(|c| features.contains(**c))
+        .map(|c| format!("feature = \"{c}\""))
+        .collect::<Vec<_>>();
+    if required.is_empty() {
+        String::new()
+    } else {
+        format!("#[cfg(all({}))]\n", required.join(", "))
+    }
+}
+
+/// Name of the machine-readable manifest [`generate_into`] writes next to the generated modules.
+pub const GENERATED_MANIFEST: &str = "generated_manifest.json";
+
+/// Render [`GENERATED_MANIFEST`] recording what the generated code was built from
+///
+/// # Arguments
+///
+/// * `target_version` - The Bitcoin Core version being targeted
+/// * `methods` - The methods code is generated for
+/// * `schema_sha256` - Hex SHA-256 of the input schema file
+///
+/// # Returns
+///
+/// Returns the manifest's JSON contents
+fn render_manifest(
+    target_version: &Version,
+    methods: &[BtcMethod],
+    schema_sha256: &str,
+) -> Result<String> {
+    let manifest = serde_json::json!({
+        "version": target_version.as_str(),
+        "major": target_version.major(),
+        "minor": target_version.minor(),
+        "patch": target_version.patch(),
+        "schema_sha256": schema_sha256,
+        "methods": methods.iter().map(|m| &m.name).collect::<Vec<_>>(),
+    });
+    Ok(serde_json::to_string_pretty(&manifest)? + "\n")
 }
 
 /// Write the Cargo.toml file for the generated crate
@@ -315,13 +906,22 @@ pub fn generate_into(out_dir: &Path, input_path: &Path, target_version: &Version
 ///
 /// * `root` - The root directory of the generated crate
 /// * `target_version` - The Bitcoin Core version being targeted
+/// * `categories` - The method categories, each declared as a default feature
 ///
 /// # Returns
 ///
 /// Returns `Result<()>` indicating success or failure of writing the Cargo.toml file
-fn write_cargo_toml(root: &Path, target_version: &Version) -> Result<()> {
+fn write_cargo_toml(
+    root: &Path,
+    target_version: &Version,
+    categories: &MethodCategories,
+) -> Result<()> {
     let version = target_version.crate_version();
     let bitcoin_version = target_version.as_doc_version();
+    let features = categories.features();
+    let default_features =
+        features.iter().map(|f| format!("\"{f}\"")).collect::<Vec<_>>().join(", ");
+    let category_features = features.iter().map(|f| format!("{f} = []\n")).collect::<String>();
     let toml = format!(
         r#"[package]
 publish = true
@@ -343,18 +943,37 @@ documentation = "https://docs.rs/bitcoin-rpc-midas"
 anyhow = "1.0"
 async-trait = "0.1"
 bitcoin = {{ version = "0.32.6", features = ["rand", "serde"] }}
+base64 = {{ version = "0.22", optional = true }}
 bitcoin-rpc-types = "1.0.0"
+futures-util = {{ version = "0.3", default-features = false, optional = true }}
+http-body-util = {{ version = "0.1", optional = true }}
+hyper = {{ version = "1", features = ["client", "http1"], optional = true }}
+hyper-util = {{ version = "0.1", features = ["tokio"], optional = true }}
 reqwest = {{ version = "0.12.15", default-features = false, features = [
     "json",
     "rustls-tls",
 ] }}
 serde = {{ version = "1.0", features = ["derive"] }}
 serde_json = "1.0"
-tempfile = "3.10"
+serde_path_to_error = "0.1"
+tempfile = "3.20"
 thiserror = "2.0.12"
 tokio = {{ version = "1.0", features = ["time", "process", "io-util"] }}
 tracing = "0.1"
 
+[dev-dependencies]
+tokio = {{ version = "1.0", features = ["macros", "rt"] }}
+
+[features]
+default = [{default_features}]
+# Synchronous `BlockingBitcoinClientV*` mirror of the client trait
+blocking = ["tokio/rt"]
+# `zmq::ZmqSubscriber` for Bitcoin Core's ZMQ notifications
+zmq = ["tokio/net", "dep:futures-util"]
+# `transport::UnixTransport` and `RpcClient::new_unix`, JSON-RPC over a Unix domain socket
+unix = ["tokio/net", "tokio/rt", "dep:base64", "dep:http-body-util", "dep:hyper", "dep:hyper-util"]
+# One feature per RPC category, gating its methods and response types
+{category_features}
 [workspace]
 "#,
         version, bitcoin_version
@@ -416,10 +1035,10 @@ bitcoin-rpc-midas = "{version}"
 tokio = {{ version = "1.0", features = ["full"] }}  
 ```
 
-And then the code:
+And then the code, importing the recommended set of items from the prelude:
 
 ```rust
-use bitcoin_rpc_midas::*;
+use bitcoin_rpc_midas::prelude::*;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {{
@@ -598,25 +1217,169 @@ SOFTWARE."#;
     Ok(())
 }
 
-/// Ensure the RPC client stub exists in the transport directory
-///
-/// # Arguments
-///
-/// * `transport_dir` - The transport module directory
-///
-/// # Returns
-///
-/// Returns `Result<()>` indicating success or failure of ensuring the RPC client stub
-fn ensure_rpc_client(transport_dir: &Path) -> Result<()> {
-    let stub_path = transport_dir.join("rpc_client.rs");
-    if stub_path.exists() {
-        return Ok(());
-    }
-    let stub = r#"use anyhow::Result;
+/// The RPC client stub written to the transport directory's `rpc_client.rs`
+const RPC_CLIENT_STUB: &str = r#"use anyhow::Result;
+use serde::Deserialize;
 use serde_json::Value;
 use std::sync::Arc;
 use std::fmt;
-use crate::transport::{TransportTrait, TransportError, DefaultTransport, BatchBuilder};
+use std::time::{Duration, Instant};
+use crate::transport::{decode_result, TransportTrait, TransportError, DefaultTransport, BatchBuilder, ComponentTransport, EstimateMode, RpcComponent};
+
+/// A Bitcoin Core version, e.g. as reported by `getnetworkinfo`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl NodeVersion {
+    /// Decode Core's integer version (`major * 10000 + minor * 100 + patch`, e.g. `290100`)
+    pub fn from_core_int(version: u64) -> Self {
+        let part = |v: u64| u32::try_from(v).unwrap_or(u32::MAX);
+        Self {
+            major: part(version / 10000),
+            minor: part(version / 100 % 100),
+            patch: part(version % 100),
+        }
+    }
+
+    /// The version this crate was generated for ([`crate::GENERATED_FROM`], e.g. `v29.1`)
+    pub fn generated() -> Self {
+        let mut parts = crate::GENERATED_FROM.trim_start_matches('v').split('.').map(|p| p.parse().unwrap_or(0));
+        Self {
+            major: parts.next().unwrap_or(0),
+            minor: parts.next().unwrap_or(0),
+            patch: parts.next().unwrap_or(0),
+        }
+    }
+}
+
+impl fmt::Display for NodeVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "v{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Block download progress, as reported by `getblockchaininfo`
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct SyncStatus {
+    /// Height of the most-work fully-validated chain
+    pub blocks: u64,
+    /// Height of the most-work header chain
+    pub headers: u64,
+    /// Estimated fraction of the chain verified, from 0.0 to 1.0
+    #[serde(rename = "verificationprogress")]
+    pub verification_progress: f64,
+    /// Whether the node considers itself in initial block download
+    #[serde(rename = "initialblockdownload")]
+    pub initial_block_download: bool,
+}
+
+impl SyncStatus {
+    /// Whether the node has caught up with its best header chain
+    ///
+    /// Trusts the node's own `initialblockdownload` flag. A node that never leaves IBD
+    /// because it has no recent blocks (e.g. a fresh regtest node) also counts as synced
+    /// once every header is validated and progress is within 1e-4 of 1.0.
+    pub fn is_synced(&self) -> bool {
+        !self.initial_block_download
+            || (self.blocks == self.headers && self.verification_progress >= 1.0 - 1e-4)
+    }
+}
+
+/// The `estimatesmartfee` response fields [`RpcClient::estimate_fee`] reads
+#[derive(Debug, Deserialize)]
+struct SmartFeeEstimate {
+    /// Fee rate in BTC/kvB, absent when there is no estimate
+    feerate: Option<f64>,
+    /// Why there is no estimate
+    #[serde(default)]
+    errors: Vec<String>,
+}
+
+/// The wallet's transactions, a page of `listtransactions` at a time, from
+/// [`RpcClient::list_all_transactions`]
+#[cfg(feature = "wallet")]
+#[derive(Debug)]
+pub struct TransactionPages<'a> {
+    client: &'a RpcClient,
+    label: String,
+    page_size: usize,
+    skip: usize,
+    done: bool,
+}
+
+#[cfg(feature = "wallet")]
+impl TransactionPages<'_> {
+    /// The next page, oldest transaction first, or `Ok(None)` after the last one
+    ///
+    /// Pages go from the newest transactions to the oldest. Each page skips the
+    /// transactions of the pages before it counted from the newest, so a transaction the
+    /// wallet sees while paging shifts later pages by one and one transaction is returned twice.
+    pub async fn next_page(&mut self) -> Result<Option<Vec<crate::responses::ListtransactionsResponseItem>>, TransportError> {
+        if self.done {
+            return Ok(None);
+        }
+        let params = [serde_json::json!(self.label), serde_json::json!(self.page_size), serde_json::json!(self.skip)];
+        let page: Vec<crate::responses::ListtransactionsResponseItem> =
+            decode_result("listtransactions", &self.client.call_method("listtransactions", &params).await?)?;
+        self.skip += page.len();
+        // A short page is the last one; after a full one there may be nothing left
+        self.done = page.len() < self.page_size;
+        Ok(Some(page).filter(|page| !page.is_empty()))
+    }
+}
+
+/// The wallet's transactions since a block, following `listsinceblock`'s `lastblock`
+/// across calls, from [`RpcClient::list_since_all`]
+#[cfg(feature = "wallet")]
+#[derive(Debug)]
+pub struct SinceBlockPages<'a> {
+    client: &'a RpcClient,
+    cursor: String,
+    seen: std::collections::HashSet<String>,
+    done: bool,
+}
+
+#[cfg(feature = "wallet")]
+impl SinceBlockPages<'_> {
+    /// The transactions since the previous page's `lastblock`, or `Ok(None)` once the
+    /// node's `lastblock` stops moving
+    ///
+    /// `listsinceblock` returns every unconfirmed transaction again on each call, so
+    /// transactions an earlier page returned in the same block (or unconfirmed) are
+    /// dropped. A transaction is returned again once it confirms. `removed` is passed on
+    /// as the node returns it.
+    pub async fn next_page(&mut self) -> Result<Option<crate::responses::ListsinceblockResponse>, TransportError> {
+        if self.done {
+            return Ok(None);
+        }
+        let params = [serde_json::json!(self.cursor)];
+        let mut page = self.client.call_method("listsinceblock", &params).await?;
+        let lastblock = page
+            .get("lastblock")
+            .and_then(Value::as_str)
+            .ok_or_else(|| TransportError::Rpc("listsinceblock returned no `lastblock`".to_string()))?
+            .to_string();
+        let mut fresh = 0;
+        if let Some(Value::Array(transactions)) = page.get_mut("transactions") {
+            transactions.retain(|tx| {
+                let key = ["txid", "vout", "category", "blockhash"].map(|field| tx.get(field).map(Value::to_string).unwrap_or_default()).join(":");
+                self.seen.insert(key)
+            });
+            fresh = transactions.len();
+        }
+        // Caught up: no block was connected since the previous call
+        self.done = lastblock == self.cursor;
+        self.cursor = lastblock;
+        if self.done && fresh == 0 {
+            return Ok(None);
+        }
+        Ok(Some(decode_result("listsinceblock", &page)?))
+    }
+}
 
 /// Thin wrapper around a transport for making RPC calls
 pub struct RpcClient {
@@ -646,92 +1409,1182 @@ impl RpcClient {
         Self { transport: Arc::new(transport) }
     }
 
+    /// Create a client speaking JSON-RPC over the Unix domain socket at `path`
+    ///
+    /// Use [`crate::transport::UnixTransport::with_auth`] and [`Self::from_transport`] if
+    /// the socket requires credentials.
+    #[cfg(all(unix, feature = "unix"))]
+    pub fn new_unix(path: impl Into<std::path::PathBuf>) -> Self {
+        Self::from_transport(Arc::new(crate::transport::UnixTransport::new(path)))
+    }
+
+    /// Create a client sending through an existing `reqwest::Client`
+    ///
+    /// Authentication must already be configured on `client`; see [`DefaultTransport::from_client`].
+    pub fn from_client(client: reqwest::Client, url: &str) -> Self {
+        Self::from_transport(Arc::new(DefaultTransport::from_client(client, url)))
+    }
+
+    /// Send the calls of `component`'s methods through `transport`, e.g. wallet RPCs to a
+    /// separate wallet process, and the others through this client's transport
+    pub fn with_component_transport(self, component: RpcComponent, transport: Arc<dyn TransportTrait>) -> Self {
+        let routed = ComponentTransport::new(self.transport).with_route(component, transport);
+        Self::from_transport(Arc::new(routed))
+    }
+
+    /// Return a client whose calls are routed to the `/wallet/<name>` endpoint
+    pub fn wallet(&self, name: &str) -> Result<Self, TransportError> {
+        let transport = self.transport.for_wallet(name).ok_or_else(|| {
+            TransportError::Rpc("Transport does not support per-wallet routing".to_string())
+        })?;
+        Ok(Self { transport })
+    }
+
     /// Call a JSON-RPC method
     pub async fn call_method(&self, method: &str, params: &[Value]) -> Result<Value, TransportError> {
         self.transport.send_request(method, params).await
     }
 
+    /// Call a JSON-RPC method once, returning the `result` both deserialized into `R` and raw
+    ///
+    /// Fields the typed response does not model are dropped by deserialization; the raw
+    /// `Value` keeps them for logging or forward compatibility.
+    pub async fn call_with_raw<R: serde::de::DeserializeOwned>(
+        &self,
+        method: &str,
+        params: &[Value],
+    ) -> Result<(R, Value), TransportError> {
+        let raw = self.transport.send_request(method, params).await?;
+        let typed = decode_result(method, &raw)?;
+        Ok((typed, raw))
+    }
+
+    /// Call a JSON-RPC method with parameters passed by name
+    pub async fn call_named(&self, method: &str, params: serde_json::Map<String, Value>) -> Result<Value, TransportError> {
+        self.transport.send_request_named(method, &params).await
+    }
+
+    /// Call a JSON-RPC method that may block for up to `max_wait`, such as a long-poll
+    ///
+    /// The call uses its own timeout instead of the transport's (e.g.
+    /// `DefaultTransport::with_timeout`), so awaiting it may block for the full `max_wait`.
+    /// Returns `Ok(None)` if the node has not answered by then.
+    pub async fn long_poll(
+        &self,
+        method: &str,
+        params: &[Value],
+        max_wait: Duration,
+    ) -> Result<Option<Value>, TransportError> {
+        self.transport.send_request_long_poll(method, params, max_wait).await
+    }
+
+    /// Wait up to `timeout` for a block template newer than the one whose `longpollid` is
+    /// `prev_id`, long-polling `getblocktemplate`
+    ///
+    /// Polls are re-sent every minute at most, so idle connections are not dropped, and
+    /// until the node returns a template with a different `longpollid`. Returns `Ok(None)`
+    /// if no new template arrived within `timeout`.
+    #[cfg(feature = "mining")]
+    pub async fn get_block_template_longpoll(
+        &self,
+        prev_id: &str,
+        timeout: Duration,
+    ) -> Result<Option<crate::responses::GetblocktemplateResponse>, TransportError> {
+        const MAX_POLL: Duration = Duration::from_secs(60);
+        // Core rejects requests without `segwit`, and on signet without `signet`
+        let params = [serde_json::json!({ "rules": ["segwit", "signet"], "longpollid": prev_id })];
+        let deadline = Instant::now() + timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Ok(None);
+            }
+            let Some(template) = self.long_poll("getblocktemplate", &params, remaining.min(MAX_POLL)).await? else {
+                continue;
+            };
+            if template.get("longpollid").and_then(Value::as_str) != Some(prev_id) {
+                return Ok(Some(decode_result("getblocktemplate", &template)?));
+            }
+        }
+    }
+
+    /// Page through the wallet's transactions with `label` (`"*"` for all), `page_size` at
+    /// a time with `listtransactions`, until a short page (see [`TransactionPages`])
+    #[cfg(feature = "wallet")]
+    pub fn list_all_transactions(&self, label: &str, page_size: usize) -> TransactionPages<'_> {
+        TransactionPages { client: self, label: label.to_string(), page_size: page_size.max(1), skip: 0, done: false }
+    }
+
+    /// Follow the wallet's transactions from `blockhash` on with `listsinceblock`, calling
+    /// again from each returned `lastblock` until it stops moving (see [`SinceBlockPages`])
+    #[cfg(feature = "wallet")]
+    pub fn list_since_all(&self, blockhash: &str) -> SinceBlockPages<'_> {
+        SinceBlockPages { client: self, cursor: blockhash.to_string(), seen: Default::default(), done: false }
+    }
+
+    /// Wait until the node answers RPC calls, retrying while it reports `-28` (warming up)
+    /// or `-4`, which nodes also return while still loading at startup.
+    ///
+    /// Any other error is returned immediately; if the node is still warming up
+    /// when `timeout` elapses, the last warm-up error is returned.
+    pub async fn wait_until_ready(&self, timeout: Duration) -> Result<(), TransportError> {
+        const WARMUP_CODES: [i32; 2] = [-28, -4];
+        let deadline = Instant::now() + timeout;
+        loop {
+            match self.transport.send_request("getblockchaininfo", &[]).await {
+                Ok(_) => return Ok(()),
+                Err(e) if e.rpc_code().is_some_and(|code| WARMUP_CODES.contains(&code)) && Instant::now() < deadline => {
+                    tracing::debug!("Node warming up: {}", e);
+                    tokio::time::sleep(Duration::from_millis(250)).await;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Check that the node is reachable and answering, returning the round-trip time
+    ///
+    /// Sends `uptime`, which needs no wallet and returns a single number, so it suits
+    /// readiness probes better than `getblockchaininfo`. Any transport or RPC error
+    /// (including `-28` while warming up) is returned as is.
+    pub async fn ping(&self) -> Result<Duration, TransportError> {
+        let start = Instant::now();
+        self.transport.send_request("uptime", &[]).await?;
+        Ok(start.elapsed())
+    }
+
+    /// The node's block download progress, from `getblockchaininfo`
+    pub async fn sync_status(&self) -> Result<SyncStatus, TransportError> {
+        let info = self.transport.send_request("getblockchaininfo", &[]).await?;
+        decode_result("getblockchaininfo", &info)
+    }
+
+    /// Poll [`sync_status`](Self::sync_status) every second until the node is synced
+    /// (see [`SyncStatus::is_synced`])
+    ///
+    /// Returns the synced status, or `Ok(None)` if the node is still syncing after
+    /// `timeout`. Errors from the node end the wait immediately.
+    pub async fn await_synced(&self, timeout: Duration) -> Result<Option<SyncStatus>, TransportError> {
+        const POLL_INTERVAL: Duration = Duration::from_secs(1);
+        let deadline = Instant::now() + timeout;
+        loop {
+            let status = self.sync_status().await?;
+            if status.is_synced() {
+                return Ok(Some(status));
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Ok(None);
+            }
+            tracing::debug!("Node syncing: {}/{} blocks", status.blocks, status.headers);
+            tokio::time::sleep(remaining.min(POLL_INTERVAL)).await;
+        }
+    }
+
+    /// The node's fee rate estimate for confirmation within `conf_target` blocks, from
+    /// `estimatesmartfee` with `mode`, or the node's default mode if `None`
+    ///
+    /// Returns `Ok(None)` when the node has no estimate (e.g. too few blocks seen), which it
+    /// reports in `errors`. The node's BTC/kvB rate is converted exactly to satoshis, then
+    /// to sat/kwu rounding up, so the fee rate never falls below the estimate.
+    pub async fn estimate_fee(&self, conf_target: u16, mode: Option<EstimateMode>) -> Result<Option<bitcoin::FeeRate>, TransportError> {
+        let mut params = vec![serde_json::json!(conf_target)];
+        params.extend(mode.map(|mode| serde_json::json!(mode)));
+        let response = self.transport.send_request("estimatesmartfee", &params).await?;
+        let estimate: SmartFeeEstimate = decode_result("estimatesmartfee", &response)?;
+        let Some(btc_per_kvb) = estimate.feerate.filter(|_| estimate.errors.is_empty()) else {
+            return Ok(None);
+        };
+        let sat_per_kvb = bitcoin::Amount::from_btc(btc_per_kvb)
+            .map_err(|e| TransportError::Rpc(format!("invalid estimatesmartfee feerate {btc_per_kvb}: {e}")))?
+            .to_sat();
+        // 1 kvB is 4 kwu
+        Ok(Some(bitcoin::FeeRate::from_sat_per_kwu(sat_per_kvb.div_ceil(4))))
+    }
+
+    /// Ask the connected node for its version via `getnetworkinfo`
+    pub async fn detect_version(&self) -> Result<NodeVersion, TransportError> {
+        let info = self.transport.send_request("getnetworkinfo", &[]).await?;
+        let version = info.get("version").and_then(Value::as_u64).ok_or_else(|| {
+            TransportError::Rpc("getnetworkinfo returned no integer `version`".to_string())
+        })?;
+        Ok(NodeVersion::from_core_int(version))
+    }
+
+    /// Fail with `TransportError::IncompatibleVersion` unless the connected node has the
+    /// same major version as `expected` (usually [`NodeVersion::generated`])
+    ///
+    /// Methods and arguments change between major versions, so calling a node of another
+    /// major version may send arguments it does not support.
+    pub async fn ensure_compatible(&self, expected: NodeVersion) -> Result<NodeVersion, TransportError> {
+        let actual = self.detect_version().await?;
+        if actual.major != expected.major {
+            return Err(TransportError::IncompatibleVersion {
+                expected: expected.to_string(),
+                actual: actual.to_string(),
+            });
+        }
+        Ok(actual)
+    }
+
     /// Start building a batch of RPC calls
     pub fn batch(&self) -> BatchBuilder {
         BatchBuilder::new(self.transport.clone())
     }
-}"#;
-    fs::write(&stub_path, stub)
-        .with_context(|| format!("Failed to write rpc_client stub at {stub_path:?}"))?;
-    Ok(())
 }
 
-/// Write the mod.rs file for a module directory
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::transport::MockTransport;
+
+    fn client(mock: &Arc<MockTransport>) -> RpcClient {
+        RpcClient::from_transport(mock.clone())
+    }
+
+    fn rpc_error(code: i32) -> TransportError {
+        TransportError::RpcCode { code, message: format!("error {code}"), data: None }
+    }
+
+    /// A `listtransactions`/`listsinceblock` entry for a receive of `txid`, confirmed in
+    /// `blockhash` if given
+    #[cfg(feature = "wallet")]
+    fn wallet_tx(txid: &str, blockhash: Option<&str>) -> Value {
+        let mut tx = json!({
+            "address": "bcrt1q5juj78fuy3lsp4s8telwe76ztyaehygr87t9nn",
+            "category": "receive",
+            "amount": 1.0,
+            "label": "",
+            "vout": 0,
+            "confirmations": 0,
+            "trusted": false,
+            "txid": txid,
+            "wtxid": txid,
+            "walletconflicts": [],
+            "mempoolconflicts": [],
+            "time": 1700000000,
+            "timereceived": 1700000000,
+            "bip125-replaceable": "no",
+            "parent_descs": [],
+            "abandoned": false,
+        });
+        if let Some(blockhash) = blockhash {
+            tx["confirmations"] = json!(1);
+            tx["blockhash"] = json!(blockhash);
+            tx["blockheight"] = json!(101);
+            tx["blockindex"] = json!(1);
+            tx["blocktime"] = json!(1700000000);
+        }
+        tx
+    }
+
+    #[cfg(feature = "wallet")]
+    fn hash(byte: char) -> String {
+        byte.to_string().repeat(64)
+    }
+
+    #[tokio::test]
+    async fn wait_until_ready_retries_warmup_errors() {
+        let mock = Arc::new(MockTransport::new());
+        mock.expect("getblockchaininfo", Err(rpc_error(-28)))
+            .expect("getblockchaininfo", Err(rpc_error(-4)))
+            .expect("getblockchaininfo", Ok(json!({})));
+
+        client(&mock).wait_until_ready(Duration::from_secs(10)).await.unwrap();
+        assert_eq!(mock.calls("getblockchaininfo").len(), 3);
+    }
+
+    #[tokio::test]
+    async fn wait_until_ready_returns_the_warmup_error_after_timeout() {
+        let mock = Arc::new(MockTransport::new());
+        mock.expect("getblockchaininfo", Err(rpc_error(-28)));
+
+        let err = client(&mock).wait_until_ready(Duration::ZERO).await.unwrap_err();
+        assert_eq!(err.rpc_code(), Some(-28));
+        assert_eq!(mock.calls("getblockchaininfo").len(), 1);
+    }
+
+    #[tokio::test]
+    async fn wait_until_ready_returns_other_errors_immediately() {
+        let mock = Arc::new(MockTransport::new());
+        mock.expect("getblockchaininfo", Err(rpc_error(-1)));
+
+        let err = client(&mock).wait_until_ready(Duration::from_secs(10)).await.unwrap_err();
+        assert_eq!(err.rpc_code(), Some(-1));
+        assert_eq!(mock.calls("getblockchaininfo").len(), 1);
+    }
+
+    #[tokio::test]
+    async fn estimate_fee_converts_btc_per_kvb() {
+        let mock = Arc::new(MockTransport::new());
+        mock.expect("estimatesmartfee", Ok(json!({ "feerate": 0.00001, "blocks": 2 })));
+
+        let fee_rate = client(&mock).estimate_fee(2, Some(EstimateMode::Economical)).await.unwrap();
+        assert_eq!(fee_rate, Some(bitcoin::FeeRate::from_sat_per_vb_unchecked(1)));
+        assert_eq!(mock.calls("estimatesmartfee"), vec![vec![json!(2), json!("economical")]]);
+    }
+
+    #[tokio::test]
+    async fn estimate_fee_rounds_up() {
+        let mock = Arc::new(MockTransport::new());
+        // 1001 sat/kvB is 250.25 sat/kwu
+        mock.expect("estimatesmartfee", Ok(json!({ "feerate": 0.00001001, "blocks": 2 })));
+
+        let fee_rate = client(&mock).estimate_fee(2, None).await.unwrap();
+        assert_eq!(fee_rate, Some(bitcoin::FeeRate::from_sat_per_kwu(251)));
+        assert_eq!(mock.calls("estimatesmartfee"), vec![vec![json!(2)]]);
+    }
+
+    #[tokio::test]
+    async fn estimate_fee_without_estimate_is_none() {
+        let mock = Arc::new(MockTransport::new());
+        mock.expect("estimatesmartfee", Ok(json!({ "errors": ["Insufficient data or no feerate found"], "blocks": 0 })));
+
+        assert_eq!(client(&mock).estimate_fee(2, None).await.unwrap(), None);
+    }
+
+    #[cfg(feature = "wallet")]
+    #[tokio::test]
+    async fn list_all_transactions_stops_after_an_empty_page() {
+        let mock = Arc::new(MockTransport::new());
+        // Exactly two full pages: only the empty third one shows there is nothing left
+        mock.expect("listtransactions", Ok(json!([wallet_tx(&hash('a'), None), wallet_tx(&hash('b'), None)])))
+            .expect("listtransactions", Ok(json!([wallet_tx(&hash('c'), None), wallet_tx(&hash('d'), None)])))
+            .expect("listtransactions", Ok(json!([])));
+
+        let client = client(&mock);
+        let mut pages = client.list_all_transactions("*", 2);
+        assert_eq!(pages.next_page().await.unwrap().unwrap().len(), 2);
+        assert_eq!(pages.next_page().await.unwrap().unwrap().len(), 2);
+        assert!(pages.next_page().await.unwrap().is_none());
+        assert!(pages.next_page().await.unwrap().is_none());
+
+        let skips = mock.calls("listtransactions").iter().map(|params| params[2].clone()).collect::<Vec<_>>();
+        assert_eq!(skips, [json!(0), json!(2), json!(4)]);
+    }
+
+    #[cfg(feature = "wallet")]
+    #[tokio::test]
+    async fn list_all_transactions_of_an_empty_wallet() {
+        let mock = Arc::new(MockTransport::new());
+        mock.expect("listtransactions", Ok(json!([])));
+
+        let client = client(&mock);
+        let mut pages = client.list_all_transactions("*", 10);
+        assert!(pages.next_page().await.unwrap().is_none());
+        assert!(pages.next_page().await.unwrap().is_none());
+        assert_eq!(mock.calls("listtransactions"), vec![vec![json!("*"), json!(10), json!(0)]]);
+    }
+
+    #[cfg(feature = "wallet")]
+    #[tokio::test]
+    async fn list_since_all_drops_transactions_returned_again() {
+        let (start, first, second) = (hash('0'), hash('1'), hash('2'));
+        let (unconfirmed, confirmed) = (hash('a'), hash('b'));
+        let mock = Arc::new(MockTransport::new());
+        mock.expect("listsinceblock", Ok(json!({
+            "transactions": [wallet_tx(&unconfirmed, None)],
+            "removed": [],
+            "lastblock": first,
+        })))
+        // The unconfirmed transaction is returned on every call until it confirms
+        .expect("listsinceblock", Ok(json!({
+            "transactions": [wallet_tx(&unconfirmed, None), wallet_tx(&confirmed, Some(&second))],
+            "removed": [],
+            "lastblock": second,
+        })))
+        .expect("listsinceblock", Ok(json!({
+            "transactions": [wallet_tx(&unconfirmed, None)],
+            "removed": [],
+            "lastblock": second,
+        })));
+
+        let client = client(&mock);
+        let mut pages = client.list_since_all(&start);
+        let txids = |page: crate::responses::ListsinceblockResponse| {
+            page.transactions.iter().map(|tx| tx.txid.to_string()).collect::<Vec<_>>()
+        };
+        assert_eq!(txids(pages.next_page().await.unwrap().unwrap()), [unconfirmed.clone()]);
+        assert_eq!(txids(pages.next_page().await.unwrap().unwrap()), [confirmed]);
+        assert!(pages.next_page().await.unwrap().is_none());
+
+        let cursors = mock.calls("listsinceblock").iter().map(|params| params[0].clone()).collect::<Vec<_>>();
+        assert_eq!(cursors, [json!(start), json!(first), json!(second)]);
+    }
+
+    #[test]
+    fn node_version_from_core_int() {
+        assert_eq!(NodeVersion::from_core_int(290100), NodeVersion { major: 29, minor: 1, patch: 0 });
+        assert_eq!(NodeVersion::from_core_int(270201), NodeVersion { major: 27, minor: 2, patch: 1 });
+        assert_eq!(NodeVersion::from_core_int(290100).to_string(), "v29.1.0");
+    }
+
+    #[tokio::test]
+    async fn ensure_compatible_accepts_the_same_major_version() {
+        let mock = Arc::new(MockTransport::new());
+        mock.expect("getnetworkinfo", Ok(json!({ "version": 290100, "subversion": "/Satoshi:29.1.0/" })));
+
+        let expected = NodeVersion { major: 29, minor: 0, patch: 0 };
+        let actual = client(&mock).ensure_compatible(expected).await.unwrap();
+        assert_eq!(actual, NodeVersion { major: 29, minor: 1, patch: 0 });
+    }
+
+    #[tokio::test]
+    async fn ensure_compatible_rejects_another_major_version() {
+        let mock = Arc::new(MockTransport::new());
+        mock.expect("getnetworkinfo", Ok(json!({ "version": 280000 })));
+
+        let expected = NodeVersion { major: 29, minor: 1, patch: 0 };
+        match client(&mock).ensure_compatible(expected).await {
+            Err(TransportError::IncompatibleVersion { expected, actual }) => {
+                assert_eq!((expected.as_str(), actual.as_str()), ("v29.1.0", "v28.0.0"));
+            }
+            other => panic!("expected IncompatibleVersion, got {other:?}"),
+        }
+    }
+
+    /// A transport whose requests never complete, like a long-poll nothing answers
+    #[derive(Debug)]
+    struct Unanswered;
+
+    impl TransportTrait for Unanswered {
+        fn send_request<'a>(&'a self, _method: &'a str, _params: &'a [Value]) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<Value, TransportError>> + Send + 'a>> {
+            Box::pin(std::future::pending())
+        }
+
+        fn send_batch<'a>(&'a self, _bodies: &'a [Value]) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<Vec<Value>, TransportError>> + Send + 'a>> {
+            Box::pin(std::future::pending())
+        }
+
+        fn url(&self) -> &str {
+            "unanswered://"
+        }
+    }
+
+    #[tokio::test]
+    async fn long_poll_times_out_with_none() {
+        let client = RpcClient::from_transport(Arc::new(Unanswered));
+        let result = client.long_poll("getblocktemplate", &[], Duration::from_millis(10)).await.unwrap();
+        assert_eq!(result, None);
+    }
+
+    #[cfg(feature = "mining")]
+    fn block_template(longpollid: &str) -> Value {
+        json!({
+            "version": 536870912,
+            "rules": ["csv", "!segwit", "taproot"],
+            "vbavailable": {},
+            "capabilities": ["proposal"],
+            "vbrequired": 0,
+            "previousblockhash": "746a606ff20cda994bf809a6927c1b0f689ca2cad6397ab2bfdaaa8708f30b97",
+            "transactions": [],
+            "coinbaseaux": {},
+            "coinbasevalue": 5000000000u64,
+            "longpollid": longpollid,
+            "target": "7fffff0000000000000000000000000000000000000000000000000000000000",
+            "mintime": 1700000000,
+            "mutable": ["time", "transactions", "prevblock"],
+            "noncerange": "00000000ffffffff",
+            "sigoplimit": 80000,
+            "sizelimit": 4000000,
+            "weightlimit": 4000000,
+            "curtime": 1700000001,
+            "bits": "207fffff",
+            "height": 103,
+            "default_witness_commitment": "6a24aa21a9ede2f61c3f71d1defd3fa999dfa36953755c690689799962b48bebd836974e8cf9",
+        })
+    }
+
+    #[cfg(feature = "mining")]
+    #[tokio::test]
+    async fn block_template_longpoll_waits_for_a_new_template() {
+        let mock = Arc::new(MockTransport::new());
+        // The node answers with the same template, e.g. on a timed-out poll, before a new one
+        mock.expect("getblocktemplate", Ok(block_template("old")))
+            .expect("getblocktemplate", Ok(block_template("new")));
+
+        let template = client(&mock).get_block_template_longpoll("old", Duration::from_secs(10)).await.unwrap().unwrap();
+        assert_eq!(template.height, 103);
+
+        let calls = mock.calls("getblocktemplate");
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0][0]["longpollid"], json!("old"));
+    }
+
+    #[cfg(feature = "mining")]
+    #[tokio::test]
+    async fn block_template_longpoll_times_out_with_none() {
+        let mock = Arc::new(MockTransport::new());
+        let template = client(&mock).get_block_template_longpoll("old", Duration::ZERO).await.unwrap();
+        assert!(template.is_none());
+        assert!(mock.calls("getblocktemplate").is_empty());
+    }
+
+    #[tokio::test]
+    async fn call_with_raw_returns_typed_and_raw_results() {
+        #[derive(Debug, Deserialize)]
+        struct ChainInfo {
+            blocks: u64,
+        }
+
+        let mock = Arc::new(MockTransport::new());
+        let result = json!({ "blocks": 101, "unmodelled": "kept" });
+        mock.expect("getblockchaininfo", Ok(result.clone()));
+
+        let (typed, raw): (ChainInfo, Value) = client(&mock).call_with_raw("getblockchaininfo", &[]).await.unwrap();
+        assert_eq!(typed.blocks, 101);
+        assert_eq!(raw, result);
+        assert_eq!(mock.calls("getblockchaininfo").len(), 1);
+    }
+
+    #[tokio::test]
+    async fn call_with_raw_names_the_field_failing_to_decode() {
+        let mock = Arc::new(MockTransport::new());
+        mock.expect("getblockhashes", Ok(json!([1, "two"])));
+
+        let err = client(&mock).call_with_raw::<Vec<u64>>("getblockhashes", &[]).await.unwrap_err();
+        match err {
+            TransportError::ResponseDecode { method, message } => {
+                assert_eq!(method, "getblockhashes");
+                assert!(message.starts_with("[1]: invalid type"), "{message}");
+            }
+            other => panic!("expected a decode error, got {other:?}"),
+        }
+    }
+
+    fn chain_info(blocks: u64, headers: u64, verification_progress: f64, initial_block_download: bool) -> Value {
+        json!({
+            "chain": "regtest",
+            "blocks": blocks,
+            "headers": headers,
+            "verificationprogress": verification_progress,
+            "initialblockdownload": initial_block_download,
+        })
+    }
+
+    #[tokio::test]
+    async fn sync_status_reads_progress() {
+        let mock = Arc::new(MockTransport::new());
+        mock.expect("getblockchaininfo", Ok(chain_info(500, 1000, 0.25, true)))
+            // A fresh regtest node stays in IBD, but has validated every header
+            .expect("getblockchaininfo", Ok(chain_info(0, 0, 0.99995, true)))
+            .expect("getblockchaininfo", Ok(chain_info(1000, 1000, 0.9, false)));
+
+        let client = client(&mock);
+        let status = client.sync_status().await.unwrap();
+        assert_eq!(status, SyncStatus { blocks: 500, headers: 1000, verification_progress: 0.25, initial_block_download: true });
+        assert!(!status.is_synced());
+        assert!(client.sync_status().await.unwrap().is_synced());
+        assert!(client.sync_status().await.unwrap().is_synced());
+    }
+
+    #[tokio::test]
+    async fn sync_status_names_the_field_failing_to_decode() {
+        let mut info = chain_info(500, 1000, 0.25, true);
+        info["blocks"] = json!("500");
+        let mock = Arc::new(MockTransport::new());
+        mock.expect("getblockchaininfo", Ok(info));
+
+        let err = client(&mock).sync_status().await.unwrap_err();
+        assert!(
+            matches!(err, TransportError::ResponseDecode { ref method, ref message } if method == "getblockchaininfo" && message.starts_with("blocks: ")),
+            "{err:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn await_synced_polls_until_synced() {
+        let mock = Arc::new(MockTransport::new());
+        mock.expect("getblockchaininfo", Ok(chain_info(500, 1000, 0.25, true)))
+            .expect("getblockchaininfo", Ok(chain_info(1000, 1000, 1.0, false)));
+
+        let status = client(&mock).await_synced(Duration::from_secs(10)).await.unwrap();
+        assert_eq!(status.map(|status| status.blocks), Some(1000));
+        assert_eq!(mock.calls("getblockchaininfo").len(), 2);
+    }
+
+    #[tokio::test]
+    async fn await_synced_gives_up_after_timeout() {
+        let mock = Arc::new(MockTransport::new());
+        mock.expect("getblockchaininfo", Ok(chain_info(500, 1000, 0.25, true)));
+
+        assert_eq!(client(&mock).await_synced(Duration::ZERO).await.unwrap(), None);
+        assert_eq!(mock.calls("getblockchaininfo").len(), 1);
+    }
+
+    #[tokio::test]
+    async fn ping_sends_uptime() {
+        let mock = Arc::new(MockTransport::new());
+        mock.expect("uptime", Ok(json!(42)));
+
+        client(&mock).ping().await.unwrap();
+        assert_eq!(mock.calls("uptime"), vec![Vec::<Value>::new()]);
+    }
+
+    #[tokio::test]
+    async fn ping_returns_errors_as_is() {
+        let mock = Arc::new(MockTransport::new());
+        mock.expect("uptime", Err(rpc_error(-28)));
+
+        assert_eq!(client(&mock).ping().await.unwrap_err().rpc_code(), Some(-28));
+    }
+}"#;
+
+/// Render the mod.rs file of a module directory
+///
+/// Modules are glob re-exported unless they define a public name an earlier module already
+/// exports; those get an explicit `pub use` of their remaining names, so the combined
+/// re-exports are never ambiguous.
 ///
 /// # Arguments
 ///
-/// * `dir` - The module directory
+/// * `module` - The name of the module directory
 /// * `files` - List of (filename, content) pairs to include in the module
+/// * `categories` - Gates modules named after a method behind the feature of its category
 ///
 /// # Returns
 ///
-/// Returns `Result<()>` indicating success or failure of writing the mod.rs file
-fn write_mod_rs(dir: &Path, files: &[(String, String)]) -> Result<()> {
-    let mod_rs = dir.join("mod.rs");
+/// Returns the mod.rs contents
+fn render_mod_rs(
+    module: &str,
+    files: &[(String, String)],
+    categories: &MethodCategories,
+) -> Result<String> {
     let mut content = String::new();
 
-    // Special-case re-exports for transport core types, batch_transport, batch_builder & rpc_client
-    if dir.ends_with("transport") {
+    // Special-case re-exports for transport core types, batch/mock transports, batch_builder & rpc_client
+    if module == "transport" {
         writeln!(
             content,
             "pub mod core;\n\
-             pub use core::{{TransportTrait, TransportError, DefaultTransport, TransportExt}};\n\
+             pub use core::{{decode_result, TransportTrait, TransportError, DefaultTransport, TransportExt, DEFAULT_METHOD_TIMEOUTS}};\n\
+             pub mod arguments;\n\
+             pub use arguments::{{AddressType, Descriptor, DescriptorError, EstimateMode}};\n\
              pub mod batch_transport;\n\
              pub use batch_transport::BatchTransport;\n\
+             pub mod mock_transport;\n\
+             pub use mock_transport::MockTransport;\n\
+             #[cfg(all(unix, feature = \"unix\"))]\n\
+             pub mod unix_transport;\n\
+             #[cfg(all(unix, feature = \"unix\"))]\n\
+             pub use unix_transport::UnixTransport;\n\
              pub mod batch_builder;\n\
              pub use batch_builder::BatchBuilder;\n\
              pub mod rpc_client;\n\
-             pub use rpc_client::RpcClient;\n"
+             pub use rpc_client::{{NodeVersion, RpcClient, SyncStatus}};\n"
         )?;
     }
 
     // Add module declarations and re-exports for everything else
-    for (name, _) in files {
-        let module_name = if name.ends_with(".rs") { name.trim_end_matches(".rs") } else { name };
+    let modules = files
+        .iter()
+        .map(|(name, src)| {
+            let module_name =
+                if name.ends_with(".rs") { name.trim_end_matches(".rs") } else { name };
+            (module_name, public_items(src))
+        })
+        .collect::<Vec<_>>();
+
+    // Each public name is glob re-exported from the first module defining it only
+    let mut owners = BTreeMap::new();
+    for (module_name, items) in &modules {
+        for item in items {
+            owners.entry(item.as_str()).or_insert(*module_name);
+        }
+    }
+
+    for (module_name, items) in &modules {
+        let module_name = *module_name;
 
         // skip files we special-cased, plus `mod.rs` itself
+        // the blocking client is opt-in through the generated crate's `blocking` feature
+        if module_name == "blocking" {
+            writeln!(content, "#[cfg(feature = \"blocking\")]\npub mod blocking;")?;
+            writeln!(content, "#[cfg(feature = \"blocking\")]\npub use blocking::*;")?;
+            continue;
+        }
+
         if module_name != "mod"
             && module_name != "core"
             && module_name != "batch_transport"
+            && module_name != "mock_transport"
             && module_name != "batch_builder"
             && module_name != "rpc_client"
         {
-            writeln!(content, "pub mod {module_name};")?;
-            writeln!(content, "pub use {module_name}::*;")?;
+            // A method named like a keyword or starting with a digit still has a plain file name
+            let ident = sanitize_ident(module_name);
+            let cfg = categories.cfg(module_name, "");
+            content.push_str(&cfg);
+            if ident.trim_start_matches("r#") != module_name {
+                writeln!(content, "#[path = \"{module_name}.rs\"]")?;
+            }
+            writeln!(content, "pub mod {ident};")?;
+            let owned = items
+                .iter()
+                .filter(|item| owners[item.as_str()] == module_name)
+                .map(String::as_str)
+                .collect::<Vec<_>>();
+            if owned.len() == items.len() {
+                writeln!(content, "{cfg}pub use {ident}::*;")?;
+            } else if !owned.is_empty() {
+                // Some names collide with an earlier module; re-export the rest explicitly
+                writeln!(content, "{cfg}pub use {ident}::{{{}}};", owned.join(", "))?;
+            }
         }
     }
 
-    fs::write(&mod_rs, content).with_context(|| format!("Failed to write mod.rs at {mod_rs:?}"))?;
-    Ok(())
+    Ok(content)
 }
 
-/// Copy template files to the destination directory
-///
-/// # Arguments
-///
-/// * `dst_dir` - The destination directory for the template files
-///
-/// # Returns
-///
-/// Returns `Result<()>` indicating success or failure of copying the template files
-fn copy_templates_to(dst_dir: &Path) -> Result<()> {
-    let project_root = find_project_root()?;
-    let src_dir = project_root.join("templates");
+/// Names of the top-level `pub` items defined in a generated source file
+fn public_items(src: &str) -> Vec<String> {
+    let re = Regex::new(
+        r"(?m)^pub\s+(?:async\s+)?(?:unsafe\s+)?(?:fn|struct|enum|trait|type|const|static|mod)\s+((?:r#)?[A-Za-z_][A-Za-z0-9_]*)",
+    )
+    .expect("valid item regex");
+    re.captures_iter(src).map(|caps| caps[1].to_string()).collect()
+}
 
-    for filename in TEMPLATE_FILES {
-        let src_path = src_dir.join(filename);
-        let dst_path = dst_dir.join(filename);
-        fs::copy(&src_path, &dst_path)
-            .with_context(|| format!("Failed to copy template file: {filename:?}"))?;
+/// Template files to be copied to the generated crate, as `(file name, contents)`
+const TEMPLATE_FILES: &[(&str, &str)] = &[
+    ("config.rs", include_str!("../templates/config.rs")),
+    ("test_config.rs", include_str!("../templates/test_config.rs")),
+    ("zmq.rs", include_str!("../templates/zmq.rs")),
+];
+
+/// Template written to the generated crate's `node/mod.rs`
+const NODE_TEMPLATE: &str = include_str!("../templates/node.rs");
+
+/// Transport helpers shared verbatim with the `transport` crate, as `(file name, contents)`
+const SHARED_TRANSPORT_FILES: &[(&str, &str)] = transport::SHARED_SOURCES;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A schema with `getblockcount` only, shared with the CLI tests
+    const GETBLOCKCOUNT_SCHEMA: &str = include_str!("../tests/data/getblockcount.json");
+
+    #[test]
+    fn test_render_mod_rs_avoids_ambiguous_reexports() {
+        let helper = "pub fn amount_from_btc_float() {}\n";
+        let files = vec![
+            ("alpha".to_string(), format!("{helper}pub struct AlphaResponse;\n")),
+            ("beta".to_string(), format!("{helper}pub struct BetaResponse;\n")),
+            ("gamma".to_string(), helper.to_string()),
+        ];
+        let root = tempfile::tempdir().unwrap();
+        let dir = root.path().join("generated");
+        fs::create_dir_all(&dir).unwrap();
+        write_generated(&dir, &files).unwrap();
+        let mod_rs = render_mod_rs("generated", &files, &MethodCategories::default()).unwrap();
+        fs::write(dir.join("mod.rs"), &mod_rs).unwrap();
+
+        assert!(mod_rs.contains("pub use alpha::*;"), "{mod_rs}");
+        assert!(mod_rs.contains("pub use beta::{BetaResponse};"), "{mod_rs}");
+        assert!(mod_rs.contains("pub mod gamma;") && !mod_rs.contains("pub use gamma"), "{mod_rs}");
+
+        // The combined module compiles without ambiguous glob re-exports
+        let lib_rs = root.path().join("lib.rs");
+        fs::write(&lib_rs, "pub mod generated;\npub use generated::*;\n").unwrap();
+        let output = std::process::Command::new(env::var("RUSTC").unwrap_or("rustc".into()))
+            .args(["--edition=2021", "--crate-type=lib", "-D", "warnings", "--out-dir"])
+            .arg(root.path())
+            .arg(&lib_rs)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
     }
 
-    Ok(())
-}
+    #[test]
+    fn test_render_mod_rs_escapes_keyword_module_names() {
+        let files = vec![
+            ("match".to_string(), "pub fn r#match() {}\n".to_string()),
+            ("self".to_string(), "pub fn self_() {}\n".to_string()),
+            ("10th".to_string(), "pub fn _10th() {}\n".to_string()),
+        ];
+        let root = tempfile::tempdir().unwrap();
+        let dir = root.path().join("generated");
+        fs::create_dir_all(&dir).unwrap();
+        write_generated(&dir, &files).unwrap();
+        let mod_rs = render_mod_rs("generated", &files, &MethodCategories::default()).unwrap();
+        fs::write(dir.join("mod.rs"), &mod_rs).unwrap();
+
+        assert!(mod_rs.contains("pub mod r#match;\npub use r#match::*;"), "{mod_rs}");
+        assert!(mod_rs.contains("#[path = \"self.rs\"]\npub mod self_;"), "{mod_rs}");
+        assert!(mod_rs.contains("#[path = \"10th.rs\"]\npub mod _10th;"), "{mod_rs}");
+
+        let lib_rs = root.path().join("lib.rs");
+        fs::write(&lib_rs, "pub mod generated;\npub use generated::*;\n").unwrap();
+        let output = std::process::Command::new(env::var("RUSTC").unwrap_or("rustc".into()))
+            .args(["--edition=2021", "--crate-type=lib", "--out-dir"])
+            .arg(root.path())
+            .arg(&lib_rs)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    }
+
+    #[test]
+    fn test_extract_version_from_filename() {
+        let cases = [
+            ("api_v29.json", "v29"),
+            ("api_v29_1.json", "v29.1"),
+            ("api_v29_10.json", "v29.10"),
+            ("api_v27_1_0.json", "v27.1.0"),
+        ];
+        for (file_name, expected) in cases {
+            let version = extract_version_from_filename(Path::new(file_name)).unwrap();
+            assert_eq!(version, expected);
+            assert!(Version::from_string(&version).is_ok());
+        }
+
+        let v = Version::from_string(
+            &extract_version_from_filename(Path::new("/some/dir/api_v29_10.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!((v.major(), v.minor()), (29, 10));
+    }
+
+    #[test]
+    fn test_extract_version_from_malformed_filename() {
+        for file_name in
+            ["api_29.json", "api_v29_.json", "api_vX.json", "api_v29_1_0_1.json", "v29.json"]
+        {
+            let err = extract_version_from_filename(Path::new(file_name)).unwrap_err();
+            assert!(
+                err.to_string().contains("expected `api_v<major>[_<minor>[_<patch>]].json`"),
+                "unexpected error for {file_name}: {err}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_sync_output_root_requires_sentinel() {
+        let dir = tempfile::tempdir().unwrap();
+        let rendered = dir.path().join("rendered");
+        fs::create_dir_all(rendered.join("src")).unwrap();
+        fs::write(rendered.join(GENERATED_SENTINEL), "").unwrap();
+        fs::write(rendered.join("src/lib.rs"), "// generated\n").unwrap();
+
+        // Missing and empty directories are fine
+        let fresh = dir.path().join("fresh");
+        assert_eq!(sync_output_root(&rendered, &fresh).unwrap(), 2);
+        assert!(fresh.join(GENERATED_SENTINEL).is_file());
+
+        // Stale sources of a previously generated crate are removed, build output is kept
+        fs::create_dir_all(fresh.join("src/stale")).unwrap();
+        fs::write(fresh.join("src/stale/old.rs"), "").unwrap();
+        fs::create_dir_all(fresh.join("target")).unwrap();
+        fs::write(fresh.join("target/build.log"), "").unwrap();
+        assert_eq!(sync_output_root(&rendered, &fresh).unwrap(), 1);
+        assert!(!fresh.join("src/stale").exists());
+        assert!(fresh.join("target/build.log").is_file());
+
+        // A crate generated before the sentinel existed is recognised by its manifest
+        let legacy = dir.path().join("legacy");
+        fs::create_dir_all(&legacy).unwrap();
+        fs::write(legacy.join("Cargo.toml"), "[package]\nname = \"bitcoin-rpc-midas\"\n").unwrap();
+        sync_output_root(&rendered, &legacy).unwrap();
+        assert!(legacy.join(GENERATED_SENTINEL).is_file());
+
+        // An unrelated directory is left untouched
+        let unrelated = dir.path().join("unrelated");
+        fs::create_dir_all(&unrelated).unwrap();
+        fs::write(unrelated.join("important.txt"), "keep me").unwrap();
+        let err = sync_output_root(&rendered, &unrelated).unwrap_err();
+        assert!(err.to_string().contains("Refusing to overwrite"));
+        assert!(unrelated.join("important.txt").exists());
+        assert!(!unrelated.join("src").exists());
+    }
+
+    #[test]
+    fn test_regenerating_an_unchanged_schema_touches_no_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let schema_path = dir.path().join("api.json");
+        fs::write(&schema_path, GETBLOCKCOUNT_SCHEMA).unwrap();
+        let output_root = dir.path().join("midas");
+        run_with_output(&schema_path, &output_root).unwrap();
+
+        let mtimes = || {
+            list_files(&output_root)
+                .unwrap()
+                .into_iter()
+                .map(|path| {
+                    let modified = fs::metadata(output_root.join(&path)).unwrap().modified();
+                    (path, modified.unwrap())
+                })
+                .collect::<Vec<_>>()
+        };
+        let before = mtimes();
+
+        let rendered = render_crate(&schema_path, &Version::new(30, 0)).unwrap();
+        assert_eq!(sync_output_root(rendered.path(), &output_root).unwrap(), 0);
+        run_with_output(&schema_path, &output_root).unwrap();
+        assert_eq!(mtimes(), before);
+    }
+
+    #[test]
+    fn test_plan_matches_a_real_run_without_writing() {
+        let dir = tempfile::tempdir().unwrap();
+        let schema_path = dir.path().join("api.json");
+        fs::write(&schema_path, GETBLOCKCOUNT_SCHEMA).unwrap();
+        let output_root = dir.path().join("midas");
+
+        let plan = plan_with_output(&schema_path, &output_root).unwrap();
+        assert!(!output_root.exists());
+
+        run_with_output(&schema_path, &output_root).unwrap();
+        let written = read_tree(&output_root)
+            .into_iter()
+            .map(|(path, contents)| (output_root.join(path), contents.len()))
+            .collect::<Vec<_>>();
+        assert_eq!(plan, written);
+
+        // An unrelated directory is refused up front, as a real run would
+        let unrelated = dir.path().join("unrelated");
+        fs::create_dir_all(&unrelated).unwrap();
+        fs::write(unrelated.join("important.txt"), "keep me").unwrap();
+        let err = plan_with_output(&schema_path, &unrelated).unwrap_err();
+        assert!(err.to_string().contains("Refusing to overwrite"));
+    }
+
+    #[test]
+    fn test_patch_versions_get_their_own_variant() {
+        let versions = [Version::from_string("v29.0.1").unwrap(), Version::new(30, 0)];
+        let src = render_versions_rs(&versions).unwrap();
+
+        assert!(src.contains("#[allow(non_camel_case_types)]\npub enum ClientVersion {"), "{src}");
+        assert!(src.contains("ClientVersion::V29_0_1 => (29, 0, 1),"), "{src}");
+
+        let src = render_versions_rs(&[Version::new(30, 0)]).unwrap();
+        assert!(!src.contains("non_camel_case_types"), "{src}");
+    }
+
+    #[test]
+    fn test_extract_version_falls_back_to_filename() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("api_v29_10.json");
+        fs::write(&path, r#"{"methods": {}}"#).unwrap();
+        assert_eq!(extract_version(&path).unwrap(), "v29.10");
+        let path = dir.path().join("api_v29_0_1.json");
+        fs::write(&path, r#"{"methods": {}}"#).unwrap();
+        assert_eq!(extract_version(&path).unwrap(), "v29.0.1");
+
+        let path = dir.path().join("schema.json");
+        fs::write(&path, r#"{"methods": {}}"#).unwrap();
+        let err = extract_version(&path).unwrap_err();
+        assert!(format!("{err:#}").contains("Missing 'version' field"));
+    }
+
+    #[test]
+    fn test_generate_into_writes_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        let schema_path = dir.path().join("api.json");
+        fs::write(&schema_path, GETBLOCKCOUNT_SCHEMA).unwrap();
+        let out_dir = dir.path().join("src");
+        fs::create_dir_all(&out_dir).unwrap();
+
+        generate_into(&out_dir, &schema_path, &Version::new(30, 0)).unwrap();
+
+        let sha = sha256::Hash::hash(GETBLOCKCOUNT_SCHEMA.as_bytes()).to_string();
+        let manifest: JsonValue =
+            serde_json::from_str(&fs::read_to_string(out_dir.join(GENERATED_MANIFEST)).unwrap())
+                .unwrap();
+        assert_eq!(manifest["version"], "v30");
+        assert_eq!(manifest["major"], 30);
+        assert_eq!(manifest["methods"], serde_json::json!(["getblockcount"]));
+        assert_eq!(manifest["schema_sha256"], sha.as_str());
+
+        let lib_rs = fs::read_to_string(out_dir.join("lib.rs")).unwrap();
+        assert!(lib_rs.contains("pub const GENERATED_FROM: &str = \"v30\";"), "{lib_rs}");
+        assert!(lib_rs.contains(&format!("pub const GENERATED_SCHEMA_SHA256: &str = \"{sha}\";")));
+    }
+
+    #[test]
+    fn test_generate_into_from_embedded_schema() {
+        let out_dir = tempfile::tempdir().unwrap();
+
+        generate_into_from_schema(out_dir.path(), GETBLOCKCOUNT_SCHEMA, &Version::new(30, 0))
+            .unwrap();
+
+        let sha = sha256::Hash::hash(GETBLOCKCOUNT_SCHEMA.as_bytes()).to_string();
+        let lib_rs = fs::read_to_string(out_dir.path().join("lib.rs")).unwrap();
+        assert!(lib_rs.contains(&format!("pub const GENERATED_SCHEMA_SHA256: &str = \"{sha}\";")));
+        // Templates and shared transport helpers come from the binary, not the source tree
+        assert_eq!(fs::read_to_string(out_dir.path().join("node/mod.rs")).unwrap(), NODE_TEMPLATE);
+        assert!(out_dir.path().join("zmq.rs").is_file());
+        assert!(out_dir.path().join("transport/mock_transport.rs").is_file());
+        assert!(fs::read_to_string(out_dir.path().join("transport/mod.rs"))
+            .unwrap()
+            .contains("getblockcount"));
+    }
+
+    #[test]
+    fn test_generate_files_matches_generate_into() {
+        let schema_path = find_project_root().unwrap().join("bitcoin-core-api.json");
+        let schema = fs::read_to_string(&schema_path).unwrap();
+        let version = Version::from_string(&extract_version(&schema_path).unwrap()).unwrap();
+
+        let files = generate_files(&schema, &version).unwrap();
+        let paths = files.iter().map(|(path, _)| path.clone()).collect::<Vec<_>>();
+
+        let out_dir = tempfile::tempdir().unwrap();
+        generate_into_from_schema(out_dir.path(), &schema, &version).unwrap();
+        assert_eq!(paths, read_tree(out_dir.path()).into_keys().collect::<Vec<_>>());
+
+        // Files written verbatim are identical; generator output is only reformatted
+        let source = |path: &str| &files.iter().find(|(p, _)| p == Path::new(path)).unwrap().1;
+        assert_eq!(source("node/mod.rs"), NODE_TEMPLATE);
+        assert_eq!(*source("lib.rs"), fs::read_to_string(out_dir.path().join("lib.rs")).unwrap());
+        assert!(source("transport/getblockcount.rs").contains("pub async fn getblockcount("));
+    }
+
+    #[test]
+    fn test_generate_two_versions_side_by_side() {
+        let schema_path = find_project_root().unwrap().join("bitcoin-core-api.json");
+        let schema = fs::read_to_string(&schema_path).unwrap();
+        // v29 lacks a method v30 added, and has one v30 removed
+        let mut v29_schema: serde_json::Value = serde_json::from_str(&schema).unwrap();
+        v29_schema["version"] = serde_json::json!("v29.0.0");
+        let methods = v29_schema["methods"].as_object_mut().unwrap();
+        methods.remove("getdescriptoractivity").unwrap();
+        let mut getoldinfo = methods["uptime"].clone();
+        getoldinfo["name"] = serde_json::json!("getoldinfo");
+        methods.insert("getoldinfo".to_string(), getoldinfo);
+        let v29_schema = v29_schema.to_string();
+
+        let files = generate_files_for_versions(&[
+            (schema.as_str(), Version::new(30, 0)),
+            (v29_schema.as_str(), Version::new(29, 0)),
+        ])
+        .unwrap();
+        let source = |path: &str| &files.iter().find(|(p, _)| p == Path::new(path)).unwrap().1;
+
+        // Each version has its own response types and client traits
+        assert!(source("responses/v29_responses.rs").contains("pub struct GetblockcountResponse"));
+        assert!(source("responses/v30_responses.rs").contains("pub struct GetblockcountResponse"));
+        let v29_client = source("client_trait/v29/client.rs");
+        assert!(v29_client.contains("pub trait BitcoinClientV29: Send + Sync {"), "{v29_client}");
+        assert!(v29_client.contains("use crate::responses::v29_responses::*;"), "{v29_client}");
+        assert!(
+            source("client_trait/v29/mod.rs").contains("pub use self::client::BitcoinClientV29;")
+        );
+        assert!(v29_client.contains("fn getoldinfo("), "{v29_client}");
+        assert!(!v29_client.contains("fn getdescriptoractivity("), "{v29_client}");
+        let v30_client = source("client_trait/client.rs");
+        assert!(v30_client.contains("fn getdescriptoractivity("), "{v30_client}");
+        assert!(!v30_client.contains("fn getoldinfo("), "{v30_client}");
+
+        // Only the newest version's names are glob re-exported, so none collide
+        let responses = source("responses/mod.rs");
+        assert!(responses.contains("pub mod v29_responses;"), "{responses}");
+        assert!(!responses.contains("pub use self::v29_responses::*;"), "{responses}");
+        let client_trait = source("client_trait/mod.rs");
+        assert!(client_trait.contains("pub mod v29;") && !client_trait.contains("pub use v29"));
+        let lib_rs = source("lib.rs");
+        assert!(lib_rs.contains("pub use client_trait::client::BitcoinClientV30;"), "{lib_rs}");
+        // Older traits have the same method names, so importing both would be ambiguous
+        assert!(!lib_rs.contains("BitcoinClientV29"), "{lib_rs}");
+        assert!(lib_rs.contains("pub const GENERATED_FROM: &str = \"v30\";"), "{lib_rs}");
+
+        let versions = source("versions.rs");
+        assert!(versions.contains("pub const ALL: &'static [ClientVersion] = &[ClientVersion::V29, ClientVersion::V30];"), "{versions}");
+        assert!(versions.contains("ClientVersion::V29 => (29, 0, 0),"), "{versions}");
+
+        // The combined client dispatches each call on the node's version
+        assert!(lib_rs.contains("pub use combined::{CombinedClient, CombinedClientError};"));
+        let combined = source("combined.rs");
+        assert!(combined.contains("pub struct CombinedClient<T> {"), "{combined}");
+        assert!(
+            combined.contains(
+                "ClientVersion::V30 | ClientVersion::V29 => Ok(crate::client_trait::client::BitcoinClientV30::getblockcount(&self.transport).await?),"
+            ),
+            "{combined}"
+        );
+        for method in ["getoldinfo", "getdescriptoractivity"] {
+            assert!(
+                combined.contains(&format!(
+                    "version => Err(CombinedClientError::Unsupported {{ method: \"{method}\", version }}),"
+                )),
+                "{combined}"
+            );
+        }
+
+        let err = generate_files_for_versions(&[
+            (schema.as_str(), Version::new(29, 0)),
+            (schema.as_str(), Version::new(29, 0)),
+        ])
+        .unwrap_err();
+        assert_eq!(err.to_string(), "Version v29 is given more than once");
+    }
+
+    #[test]
+    fn test_generate_into_rejects_invalid_schema_before_writing() {
+        let schema = r#"{
+            "methods": {
+                "sendtoaddress": {
+                    "name": "sendtoaddress",
+                    "description": "",
+                    "arguments": [
+                        {"names": ["address"], "description": "", "required": true, "type": "string"},
+                        {"names": ["address"], "description": "", "required": true, "type": "string"},
+                        {"names": [], "description": "", "required": false, "type": "string"}
+                    ],
+                    "results": [{"type": "hex", "description": "", "inner": [
+                        {"type": "number", "description": "", "key_name": "vout"}
+                    ]}]
+                }
+            }
+        }"#;
+        let out_dir = tempfile::tempdir().unwrap();
+
+        let err = generate_into_from_schema(out_dir.path(), schema, &Version::new(30, 0))
+            .unwrap_err()
+            .to_string();
+        assert!(err.starts_with("Invalid API schema (3 problems):"), "{err}");
+        assert!(err.contains("\n  - sendtoaddress: argument name 'address' is used more than once"));
+        assert!(err.contains("\n  - sendtoaddress: argument 2 has no name"));
+        assert!(err.contains("result 'results[0]' of type 'hex' cannot have nested fields"));
+        assert_eq!(fs::read_dir(out_dir.path()).unwrap().count(), 0);
+    }
 
-/// Template files to be copied to the generated crate
-const TEMPLATE_FILES: &[&str] = &["config.rs", "test_config.rs"];
+    /// Every file under `dir` (relative path to contents), in path order.
+    fn read_tree(dir: &Path) -> BTreeMap<PathBuf, Vec<u8>> {
+        let mut files = BTreeMap::new();
+        let mut pending = vec![dir.to_path_buf()];
+        while let Some(current) = pending.pop() {
+            for entry in fs::read_dir(&current).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_dir() {
+                    pending.push(path);
+                } else {
+                    files.insert(
+                        path.strip_prefix(dir).unwrap().to_path_buf(),
+                        fs::read(&path).unwrap(),
+                    );
+                }
+            }
+        }
+        files
+    }
+
+    #[test]
+    fn test_generate_into_is_deterministic() {
+        let schema_path = find_project_root().unwrap().join("bitcoin-core-api.json");
+        let version = Version::from_string(&extract_version(&schema_path).unwrap()).unwrap();
+
+        let first = tempfile::tempdir().unwrap();
+        let second = tempfile::tempdir().unwrap();
+        generate_into(first.path(), &schema_path, &version).unwrap();
+        generate_into(second.path(), &schema_path, &version).unwrap();
+
+        let (first, second) = (read_tree(first.path()), read_tree(second.path()));
+        assert_eq!(first.keys().collect::<Vec<_>>(), second.keys().collect::<Vec<_>>());
+        for (path, contents) in &first {
+            assert!(second[path] == *contents, "{path:?} differs between runs");
+        }
+    }
+}
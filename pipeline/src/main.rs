@@ -1,24 +1,179 @@
-use std::env;
 use std::path::PathBuf;
+use std::{env, fs};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use codegen::versioning::Version;
+use transport::{BlockingRpcClient, Transport};
+
+const USAGE: &str = "\
+Usage:
+  pipeline [input] [output]
+  pipeline generate --input <api.json> [--output <dir>] [--version <version>] [--check | --dry-run]
+  pipeline introspect --rpc-url <url> [--rpc-user <user> --rpc-pass <pass>] --out <api.json>
+
+Commands:
+  generate    Generate a client crate from a schema
+  introspect  Build a schema from a running node's `help` output
+
+Options for `generate`:
+  --input <api.json>     The Bitcoin Core API schema
+  --output <dir>         Directory for the generated crate (required unless --check)
+  --version <version>    Target version, e.g. v29 or 29.1 (default: the schema's version)
+  --check                Validate the schema without writing anything
+  --dry-run              List the files that would be written, with their sizes
+
+Options for `introspect`:
+  --rpc-url <url>        The node's RPC endpoint, e.g. http://127.0.0.1:18443
+  --rpc-user <user>      RPC username
+  --rpc-pass <pass>      RPC password
+  --out <api.json>       Where to write the schema";
+
+/// Arguments of the `generate` subcommand
+#[derive(Debug, Default)]
+struct GenerateArgs {
+    input: Option<PathBuf>,
+    output: Option<PathBuf>,
+    version: Option<String>,
+    check: bool,
+    dry_run: bool,
+}
+
+impl GenerateArgs {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self> {
+        let mut parsed = Self::default();
+        while let Some(arg) = args.next() {
+            let mut value = |flag: &str| {
+                args.next().ok_or_else(|| anyhow::anyhow!("{flag} needs a value\n\n{USAGE}"))
+            };
+            match arg.as_str() {
+                "--input" => parsed.input = Some(value("--input")?.into()),
+                "--output" => parsed.output = Some(value("--output")?.into()),
+                "--version" => parsed.version = Some(value("--version")?),
+                "--check" => parsed.check = true,
+                "--dry-run" => parsed.dry_run = true,
+                other => return Err(anyhow::anyhow!("Unexpected argument {other:?}\n\n{USAGE}")),
+            }
+        }
+        Ok(parsed)
+    }
+
+    fn run(self) -> Result<()> {
+        let input = self.input.ok_or_else(|| anyhow::anyhow!("--input is required\n\n{USAGE}"))?;
+        let version = self
+            .version
+            .map(|version| Version::from_string(&version))
+            .transpose()
+            .context("Invalid --version")?;
+
+        if self.check {
+            let methods = pipeline::check_schema(&input)?;
+            println!("{}: {methods} methods, schema OK", input.display());
+            return Ok(());
+        }
+
+        let output =
+            self.output.ok_or_else(|| anyhow::anyhow!("--output is required\n\n{USAGE}"))?;
+        if self.dry_run {
+            let plan = match version {
+                Some(version) => pipeline::plan_with_version(&input, &output, &version)?,
+                None => pipeline::plan_with_output(&input, &output)?,
+            };
+            for (path, len) in &plan {
+                println!("{len:>9}  {}", path.display());
+            }
+            let total = plan.iter().map(|(_, len)| len).sum::<usize>();
+            println!("{} files, {total} bytes would be written", plan.len());
+            return Ok(());
+        }
+
+        match version {
+            Some(version) => pipeline::run_with_version(&input, &output, &version),
+            None => pipeline::run_with_output(&input, &output),
+        }
+    }
+}
+
+/// Arguments of the `introspect` subcommand
+#[derive(Debug, Default)]
+struct IntrospectArgs {
+    rpc_url: Option<String>,
+    rpc_user: Option<String>,
+    rpc_pass: Option<String>,
+    out: Option<PathBuf>,
+}
+
+impl IntrospectArgs {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self> {
+        let mut parsed = Self::default();
+        while let Some(arg) = args.next() {
+            let mut value = |flag: &str| {
+                args.next().ok_or_else(|| anyhow::anyhow!("{flag} needs a value\n\n{USAGE}"))
+            };
+            match arg.as_str() {
+                "--rpc-url" => parsed.rpc_url = Some(value("--rpc-url")?),
+                "--rpc-user" => parsed.rpc_user = Some(value("--rpc-user")?),
+                "--rpc-pass" => parsed.rpc_pass = Some(value("--rpc-pass")?),
+                "--out" => parsed.out = Some(value("--out")?.into()),
+                other => return Err(anyhow::anyhow!("Unexpected argument {other:?}\n\n{USAGE}")),
+            }
+        }
+        Ok(parsed)
+    }
+
+    fn run(self) -> Result<()> {
+        let url =
+            self.rpc_url.ok_or_else(|| anyhow::anyhow!("--rpc-url is required\n\n{USAGE}"))?;
+        let out = self.out.ok_or_else(|| anyhow::anyhow!("--out is required\n\n{USAGE}"))?;
+        let transport = match (self.rpc_user, self.rpc_pass) {
+            (Some(user), Some(pass)) => Transport::new_with_auth(url, &user, &pass),
+            (None, None) => Transport::new(url),
+            _ => return Err(anyhow::anyhow!("--rpc-user and --rpc-pass go together\n\n{USAGE}")),
+        };
+        let client = BlockingRpcClient::new(transport)?;
+
+        let schema = pipeline::introspect::introspect(&client)?;
+        fs::write(&out, serde_json::to_string_pretty(&schema)? + "\n")
+            .with_context(|| format!("Failed to write {out:?}"))?;
+        println!(
+            "{}: {} methods from Bitcoin Core {}",
+            out.display(),
+            schema["methods"].as_object().map_or(0, |methods| methods.len()),
+            schema["version"].as_str().unwrap_or_default()
+        );
+        Ok(())
+    }
+}
 
 fn main() -> Result<()> {
-    let args: Vec<String> = env::args().collect();
+    logging::init();
+
+    // Ignore the "pipeline" argument from cargo run
+    let mut args = env::args().skip(1).filter(|arg| arg.as_str() != "pipeline").peekable();
+
+    match args.peek().map(String::as_str) {
+        Some("generate") => return GenerateArgs::parse(args.skip(1))?.run(),
+        Some("introspect") => return IntrospectArgs::parse(args.skip(1))?.run(),
+        Some("-h" | "--help") => {
+            println!("{USAGE}");
+            return Ok(());
+        }
+        _ => {}
+    }
 
     // Get input path from first argument, or use default
-    let input_path = args
-        .get(1)
-        .filter(|arg| arg.as_str() != "pipeline") // Ignore the "pipeline" argument from cargo run
-        .map(PathBuf::from)
-        .or_else(|| {
-            let default = PathBuf::from("bitcoin-core-api.json");
-            if default.exists() {
-                Some(default)
-            } else {
-                None
-            }
-        });
+    let input_path = args.next().map(PathBuf::from).or_else(|| {
+        let default = PathBuf::from("bitcoin-core-api.json");
+        if default.exists() {
+            Some(default)
+        } else {
+            None
+        }
+    });
 
-    pipeline::run(input_path.as_ref())
+    // An explicit output directory replaces the workspace's `bitcoin-rpc-midas`
+    match (input_path, args.next().map(PathBuf::from)) {
+        (Some(input_path), Some(output_root)) =>
+            pipeline::run_with_output(&input_path, &output_root),
+        (input_path, _) => pipeline::run(input_path.as_ref()),
+    }
 }
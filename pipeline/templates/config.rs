@@ -0,0 +1,12 @@
+//! Configuration interface for Bitcoin RPC clients
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub rpc_url: String,
+    pub rpc_user: String,
+    pub rpc_password: String,
+    /// Per-request timeout in seconds; `None` waits indefinitely.
+    pub timeout_secs: Option<u64>,
+    /// Retries for failed HTTP requests; `None` disables retrying.
+    pub max_retries: Option<u32>,
+}
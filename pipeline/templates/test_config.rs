@@ -3,10 +3,20 @@
 //! This module provides configuration utilities for running Bitcoin nodes in test environments.
 
 use std::env;
+use std::time::Duration;
 
 use bitcoin::Network;
 use crate::config::Config;
 
+/// Default time `BitcoinNodeManager::start` waits for the node to become ready.
+pub const DEFAULT_START_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default delay between readiness probes while the node is starting.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Default grace period for bitcoind to exit after the `stop` RPC.
+pub const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
 /// TestConfig represents the configuration needed to run a Bitcoin node in a test environment.
 /// This struct is the single source of truth for test‑node settings: RPC port, username, and password.
 /// Defaults are:
@@ -14,6 +24,7 @@ use crate::config::Config;
 /// - `rpc_username = "rpcuser"`
 /// - `rpc_password = "rpcpassword"`
 /// - `network = Network::Regtest` (for isolation and testability)
+/// - `start_timeout = 10s`, `poll_interval = 200ms`, `shutdown_timeout = 10s`
 ///
 /// To override any of these, simply modify fields on `TestConfig::default()`
 /// (or assign directly in code). If you prefer not to recompile for every change,
@@ -44,8 +55,25 @@ pub struct TestConfig {
     pub rpc_password: String,
     /// Which Bitcoin network to run against.
     pub network: Network,
-    /// Extra command-line arguments to pass to bitcoind
+    /// Custom signet challenge script (hex), passed as `-signetchallenge` when
+    /// `network` is `Network::Signet`. `None` uses the default public signet.
+    pub signet_challenge: Option<String>,
+    /// Extra command-line arguments to pass to bitcoind, appended after the defaults.
+    /// A default that conflicts with one of these (e.g. `-prune` vs `-txindex=1`,
+    /// or `-chain` vs `-signet`) is dropped so the override wins.
     pub extra_args: Vec<String>,
+    /// How long `start` waits for the node to answer RPC before giving up.
+    pub start_timeout: Duration,
+    /// Delay between RPC readiness probes while the node is starting.
+    pub poll_interval: Duration,
+    /// How long `shutdown` waits for bitcoind to exit after the `stop` RPC before killing it.
+    pub shutdown_timeout: Duration,
+    /// Keep the node's temporary datadir (including `debug.log`) instead of deleting it
+    /// when the manager is dropped, for post-mortem debugging.
+    pub keep_datadir: bool,
+    /// Keep the last `n` lines bitcoind writes to stdout and stderr in memory, readable
+    /// through `BitcoinNodeManager::logs`. `None` disables capture.
+    pub capture_logs: Option<usize>,
 }
 
 impl TestConfig {
@@ -108,6 +136,8 @@ impl TestConfig {
             rpc_url: format!("http://127.0.0.1:{}", self.rpc_port),
             rpc_user: self.rpc_username,
             rpc_password: self.rpc_password,
+            timeout_secs: None,
+            max_retries: None,
         }
     }
 
@@ -125,7 +155,13 @@ impl TestConfig {
             rpc_username: config.rpc_user.clone(),
             rpc_password: config.rpc_password.clone(),
             network: Network::Regtest, // Default to regtest for test environments
+            signet_challenge: None,
             extra_args: vec![],
+            start_timeout: DEFAULT_START_TIMEOUT,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            shutdown_timeout: DEFAULT_SHUTDOWN_TIMEOUT,
+            keep_datadir: false,
+            capture_logs: None,
         }
     }
 }
@@ -137,7 +173,13 @@ impl Default for TestConfig {
             rpc_username: "rpcuser".to_string(),
             rpc_password: "rpcpassword".to_string(),
             network: Network::Regtest,
+            signet_challenge: None,
             extra_args: vec![],
+            start_timeout: DEFAULT_START_TIMEOUT,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            shutdown_timeout: DEFAULT_SHUTDOWN_TIMEOUT,
+            keep_datadir: false,
+            capture_logs: None,
         }
     }
 }
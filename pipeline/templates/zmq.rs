@@ -0,0 +1,428 @@
+//! Subscribe to Bitcoin Core's ZMQ notifications (`-zmqpub*`).
+//!
+//! [`ZmqSubscriber`] speaks ZMTP 3.0 with the NULL mechanism directly over TCP, which is all
+//! Bitcoin Core's publisher needs, so no system `libzmq` is required. Enabled by the `zmq`
+//! feature.
+
+use std::collections::BTreeMap;
+
+use bitcoin::hashes::Hash;
+use bitcoin::{BlockHash, Txid};
+use futures_util::Stream;
+use thiserror::Error;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+use crate::responses::GetzmqnotificationsResponse;
+
+/// Errors from a ZMQ subscription.
+#[derive(Debug, Error)]
+pub enum ZmqError {
+    #[error("ZMQ I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Unsupported ZMQ endpoint {0:?} (only tcp:// is supported)")]
+    UnsupportedEndpoint(String),
+    #[error("ZMQ protocol error: {0}")]
+    Protocol(String),
+    #[error("Malformed `{topic}` notification: {reason}")]
+    Malformed { topic: String, reason: String },
+}
+
+/// A notification topic Bitcoin Core publishes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ZmqTopic {
+    /// `-zmqpubhashblock`
+    HashBlock,
+    /// `-zmqpubhashtx`
+    HashTx,
+    /// `-zmqpubrawblock`
+    RawBlock,
+    /// `-zmqpubrawtx`
+    RawTx,
+    /// `-zmqpubsequence`
+    Sequence,
+}
+
+impl ZmqTopic {
+    /// The topic string on the wire, e.g. `hashblock`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ZmqTopic::HashBlock => "hashblock",
+            ZmqTopic::HashTx => "hashtx",
+            ZmqTopic::RawBlock => "rawblock",
+            ZmqTopic::RawTx => "rawtx",
+            ZmqTopic::Sequence => "sequence",
+        }
+    }
+
+    /// The topic for a `getzmqnotifications` type, e.g. `pubhashblock`.
+    pub fn from_notification_type(ty: &str) -> Option<Self> {
+        match ty {
+            "pubhashblock" => Some(ZmqTopic::HashBlock),
+            "pubhashtx" => Some(ZmqTopic::HashTx),
+            "pubrawblock" => Some(ZmqTopic::RawBlock),
+            "pubrawtx" => Some(ZmqTopic::RawTx),
+            "pubsequence" => Some(ZmqTopic::Sequence),
+            _ => None,
+        }
+    }
+}
+
+/// An event published on the `sequence` topic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SequenceEvent {
+    BlockConnected(BlockHash),
+    BlockDisconnected(BlockHash),
+    TxAdded { txid: Txid, mempool_sequence: u64 },
+    TxRemoved { txid: Txid, mempool_sequence: u64 },
+}
+
+/// A typed ZMQ notification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ZmqNotification {
+    /// A new chain tip (`hashblock`).
+    Block(BlockHash),
+    /// A transaction entered the mempool or a block (`hashtx`).
+    Tx(Txid),
+    /// A consensus-serialized block (`rawblock`).
+    RawBlock(Vec<u8>),
+    /// A consensus-serialized transaction (`rawtx`).
+    RawTx(Vec<u8>),
+    /// A block or mempool change (`sequence`).
+    Sequence(SequenceEvent),
+}
+
+impl ZmqNotification {
+    /// Decode a notification's topic and body frames, or `None` for an unknown topic.
+    pub fn decode(topic: &[u8], body: &[u8]) -> Result<Option<Self>, ZmqError> {
+        let malformed = |reason: &str| ZmqError::Malformed {
+            topic: String::from_utf8_lossy(topic).into_owned(),
+            reason: reason.to_string(),
+        };
+        // Hashes are published in RPC (byte-reversed) order
+        let hash = |bytes: &[u8]| -> Result<[u8; 32], ZmqError> {
+            let mut hash: [u8; 32] = bytes
+                .get(..32)
+                .and_then(|b| b.try_into().ok())
+                .ok_or_else(|| malformed("short hash"))?;
+            hash.reverse();
+            Ok(hash)
+        };
+
+        let notification = match topic {
+            b"hashblock" => ZmqNotification::Block(BlockHash::from_byte_array(hash(body)?)),
+            b"hashtx" => ZmqNotification::Tx(Txid::from_byte_array(hash(body)?)),
+            b"rawblock" => ZmqNotification::RawBlock(body.to_vec()),
+            b"rawtx" => ZmqNotification::RawTx(body.to_vec()),
+            b"sequence" => {
+                let hash = hash(body)?;
+                let mempool_sequence = || -> Result<u64, ZmqError> {
+                    body.get(33..41)
+                        .and_then(|b| b.try_into().ok())
+                        .map(u64::from_le_bytes)
+                        .ok_or_else(|| malformed("missing mempool sequence"))
+                };
+                let event = match body.get(32) {
+                    Some(b'C') => SequenceEvent::BlockConnected(BlockHash::from_byte_array(hash)),
+                    Some(b'D') =>
+                        SequenceEvent::BlockDisconnected(BlockHash::from_byte_array(hash)),
+                    Some(b'A') => SequenceEvent::TxAdded {
+                        txid: Txid::from_byte_array(hash),
+                        mempool_sequence: mempool_sequence()?,
+                    },
+                    Some(b'R') => SequenceEvent::TxRemoved {
+                        txid: Txid::from_byte_array(hash),
+                        mempool_sequence: mempool_sequence()?,
+                    },
+                    _ => return Err(malformed("unknown sequence label")),
+                };
+                ZmqNotification::Sequence(event)
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(notification))
+    }
+}
+
+/// Group the endpoints reported by `getzmqnotifications` by address.
+pub fn endpoints(notifications: &GetzmqnotificationsResponse) -> BTreeMap<String, Vec<ZmqTopic>> {
+    let mut endpoints = BTreeMap::<String, Vec<ZmqTopic>>::new();
+    for item in &notifications.0 {
+        if let Some(topic) = ZmqTopic::from_notification_type(&item.r#type) {
+            endpoints.entry(item.address.clone()).or_default().push(topic);
+        }
+    }
+    endpoints
+}
+
+const FLAG_MORE: u8 = 0x01;
+const FLAG_LONG: u8 = 0x02;
+const FLAG_COMMAND: u8 = 0x04;
+
+/// Largest frame accepted, well above Core's largest notification (a `rawblock` of at most
+/// 4 MB), so a corrupt or hostile length prefix cannot exhaust memory.
+const MAX_FRAME_LEN: u64 = 32 * 1024 * 1024;
+
+/// A ZMQ `SUB` connection to one of Bitcoin Core's publishers.
+///
+/// ```rust,ignore
+/// let mut sub = ZmqSubscriber::connect("tcp://127.0.0.1:28332").await?;
+/// while let Ok(notification) = sub.recv().await {
+///     println!("{notification:?}");
+/// }
+/// ```
+#[derive(Debug)]
+pub struct ZmqSubscriber {
+    stream: TcpStream,
+}
+
+impl ZmqSubscriber {
+    /// Connect to `endpoint` (e.g. `tcp://127.0.0.1:28332`) and subscribe to every topic.
+    pub async fn connect(endpoint: &str) -> Result<Self, ZmqError> {
+        Self::connect_topics(endpoint, &[]).await
+    }
+
+    /// Connect to `endpoint` and subscribe to `topics` only (all of them if empty).
+    pub async fn connect_topics(endpoint: &str, topics: &[ZmqTopic]) -> Result<Self, ZmqError> {
+        let address = endpoint
+            .strip_prefix("tcp://")
+            .ok_or_else(|| ZmqError::UnsupportedEndpoint(endpoint.to_string()))?;
+        let mut subscriber = Self { stream: TcpStream::connect(address).await? };
+        subscriber.handshake().await?;
+        if topics.is_empty() {
+            subscriber.subscribe(b"").await?;
+        }
+        for topic in topics {
+            subscriber.subscribe(topic.as_str().as_bytes()).await?;
+        }
+        Ok(subscriber)
+    }
+
+    /// Connect to every endpoint a node reports through `getzmqnotifications`.
+    pub async fn from_notifications(
+        notifications: &GetzmqnotificationsResponse,
+    ) -> Result<Vec<Self>, ZmqError> {
+        let mut subscribers = Vec::new();
+        for (address, topics) in endpoints(notifications) {
+            subscribers.push(Self::connect_topics(&address, &topics).await?);
+        }
+        Ok(subscribers)
+    }
+
+    /// Wait for the next notification, skipping topics this module does not know.
+    pub async fn recv(&mut self) -> Result<ZmqNotification, ZmqError> {
+        loop {
+            let frames = self.read_message().await?;
+            let [topic, body, ..] = frames.as_slice() else {
+                return Err(ZmqError::Protocol(format!(
+                    "expected topic and body frames, got {} frame(s)",
+                    frames.len()
+                )));
+            };
+            if let Some(notification) = ZmqNotification::decode(topic, body)? {
+                return Ok(notification);
+            }
+        }
+    }
+
+    /// The notifications as a `Stream`, ending after the first error.
+    pub fn into_stream(self) -> impl Stream<Item = Result<ZmqNotification, ZmqError>> {
+        futures_util::stream::unfold(Some(self), |subscriber| async move {
+            let mut subscriber = subscriber?;
+            match subscriber.recv().await {
+                Ok(notification) => Some((Ok(notification), Some(subscriber))),
+                Err(e) => Some((Err(e), None)),
+            }
+        })
+    }
+
+    async fn handshake(&mut self) -> Result<(), ZmqError> {
+        // Signature, version 3.0, NULL mechanism, as-server = 0, filler
+        let mut greeting = [0u8; 64];
+        greeting[0] = 0xff;
+        greeting[9] = 0x7f;
+        greeting[10] = 3;
+        greeting[12..16].copy_from_slice(b"NULL");
+        self.stream.write_all(&greeting).await?;
+
+        let mut peer = [0u8; 64];
+        self.stream.read_exact(&mut peer).await?;
+        if peer[0] != 0xff || peer[9] != 0x7f || peer[10] < 3 {
+            return Err(ZmqError::Protocol("peer did not send a ZMTP 3 greeting".into()));
+        }
+        if &peer[12..16] != b"NULL" {
+            return Err(ZmqError::Protocol("peer requires a security mechanism".into()));
+        }
+
+        let mut ready = b"\x05READY\x0bSocket-Type".to_vec();
+        ready.extend_from_slice(&3u32.to_be_bytes());
+        ready.extend_from_slice(b"SUB");
+        self.write_frame(FLAG_COMMAND, &ready).await?;
+
+        let (flags, body) = self.read_frame().await?;
+        if flags & FLAG_COMMAND == 0 || !body.starts_with(b"\x05READY") {
+            return Err(ZmqError::Protocol("peer did not send READY".into()));
+        }
+        Ok(())
+    }
+
+    async fn subscribe(&mut self, prefix: &[u8]) -> Result<(), ZmqError> {
+        let mut body = vec![0x01];
+        body.extend_from_slice(prefix);
+        self.write_frame(0, &body).await
+    }
+
+    async fn write_frame(&mut self, flags: u8, body: &[u8]) -> Result<(), ZmqError> {
+        let mut frame = Vec::with_capacity(body.len() + 9);
+        match u8::try_from(body.len()) {
+            Ok(len) => frame.extend_from_slice(&[flags, len]),
+            Err(_) => {
+                frame.push(flags | FLAG_LONG);
+                frame.extend_from_slice(&(body.len() as u64).to_be_bytes());
+            }
+        }
+        frame.extend_from_slice(body);
+        self.stream.write_all(&frame).await?;
+        Ok(())
+    }
+
+    async fn read_frame(&mut self) -> Result<(u8, Vec<u8>), ZmqError> {
+        let flags = self.stream.read_u8().await?;
+        let len = if flags & FLAG_LONG != 0 {
+            self.stream.read_u64().await?
+        } else {
+            u64::from(self.stream.read_u8().await?)
+        };
+        if len > MAX_FRAME_LEN {
+            return Err(ZmqError::Protocol(format!(
+                "frame of {len} bytes exceeds the {MAX_FRAME_LEN}-byte limit"
+            )));
+        }
+        let mut body = vec![0u8; len as usize];
+        self.stream.read_exact(&mut body).await?;
+        Ok((flags, body))
+    }
+
+    /// Read one multipart message, skipping commands (e.g. heartbeats).
+    async fn read_message(&mut self) -> Result<Vec<Vec<u8>>, ZmqError> {
+        let mut frames = Vec::new();
+        loop {
+            let (flags, body) = self.read_frame().await?;
+            if flags & FLAG_COMMAND != 0 {
+                continue;
+            }
+            frames.push(body);
+            if flags & FLAG_MORE == 0 {
+                return Ok(frames);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::StreamExt;
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    fn frame(flags: u8, body: &[u8]) -> Vec<u8> {
+        let mut frame = vec![flags, body.len() as u8];
+        frame.extend_from_slice(body);
+        frame
+    }
+
+    /// A minimal publisher: answers the handshake, checks the subscriptions and publishes
+    /// `hashblock` followed by a mempool `sequence` event.
+    async fn publish(listener: TcpListener, id: [u8; 32]) {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut greeting = [0u8; 64];
+        socket.read_exact(&mut greeting).await.unwrap();
+        assert_eq!(&greeting[12..16], b"NULL");
+        socket.write_all(&greeting).await.unwrap();
+
+        let mut ready = [0u8; 2];
+        socket.read_exact(&mut ready).await.unwrap();
+        let mut body = vec![0u8; ready[1] as usize];
+        socket.read_exact(&mut body).await.unwrap();
+        assert!(body.ends_with(b"SUB"));
+        socket
+            .write_all(&frame(FLAG_COMMAND, b"\x05READY\x0bSocket-Type\x00\x00\x00\x03PUB"))
+            .await
+            .unwrap();
+
+        let mut subscribe = [0u8; 23];
+        socket.read_exact(&mut subscribe).await.unwrap();
+        assert_eq!(&subscribe, b"\x00\x0a\x01hashblock\x00\x09\x01sequence");
+
+        let mut message = frame(FLAG_MORE, b"hashblock");
+        message.extend(frame(FLAG_MORE, &id));
+        message.extend(frame(0, &0u32.to_le_bytes()));
+        let mut sequence = id.to_vec();
+        sequence.push(b'A');
+        sequence.extend_from_slice(&7u64.to_le_bytes());
+        message.extend(frame(FLAG_MORE, b"sequence"));
+        message.extend(frame(FLAG_MORE, &sequence));
+        message.extend(frame(0, &1u32.to_le_bytes()));
+        socket.write_all(&message).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn subscriber_decodes_published_notifications() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("tcp://{}", listener.local_addr().unwrap());
+        let mut id = [0u8; 32];
+        id[0] = 0xab;
+        let publisher = tokio::spawn(publish(listener, id));
+
+        let subscriber =
+            ZmqSubscriber::connect_topics(&endpoint, &[ZmqTopic::HashBlock, ZmqTopic::Sequence])
+                .await
+                .unwrap();
+        let notifications = subscriber.into_stream().take(2).collect::<Vec<_>>().await;
+        publisher.await.unwrap();
+
+        let mut reversed = id;
+        reversed.reverse();
+        let hash = BlockHash::from_byte_array(reversed);
+        assert!(hash.to_string().starts_with("ab"));
+        assert_eq!(*notifications[0].as_ref().unwrap(), ZmqNotification::Block(hash));
+        assert_eq!(
+            *notifications[1].as_ref().unwrap(),
+            ZmqNotification::Sequence(SequenceEvent::TxAdded {
+                txid: Txid::from_byte_array(reversed),
+                mempool_sequence: 7,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn oversized_frames_are_rejected() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let stream = TcpStream::connect(listener.local_addr().unwrap()).await.unwrap();
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut header = vec![FLAG_LONG];
+        header.extend_from_slice(&u64::MAX.to_be_bytes());
+        socket.write_all(&header).await.unwrap();
+
+        let mut subscriber = ZmqSubscriber { stream };
+        match subscriber.read_frame().await {
+            Err(ZmqError::Protocol(reason)) => assert!(reason.contains("exceeds"), "{reason}"),
+            other => panic!("expected a protocol error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn endpoints_group_topics_by_address() {
+        let notifications: GetzmqnotificationsResponse =
+            serde_json::from_value(serde_json::json!([
+                { "type": "pubhashblock", "address": "tcp://127.0.0.1:28332", "hwm": 1000 },
+                { "type": "pubrawtx", "address": "tcp://127.0.0.1:28332", "hwm": 1000 },
+                { "type": "pubsequence", "address": "tcp://127.0.0.1:28333", "hwm": 1000 },
+            ]))
+            .unwrap();
+        let endpoints = endpoints(&notifications);
+        assert_eq!(endpoints["tcp://127.0.0.1:28332"], [ZmqTopic::HashBlock, ZmqTopic::RawTx]);
+        assert_eq!(endpoints["tcp://127.0.0.1:28333"], [ZmqTopic::Sequence]);
+    }
+}
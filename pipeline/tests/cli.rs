@@ -0,0 +1,188 @@
+// pipeline/tests/cli.rs
+
+use std::fs;
+use std::process::Command;
+
+/// A schema with `getblockcount` only, shared with the library's tests
+const SCHEMA: &str = include_str!("data/getblockcount.json");
+
+#[test]
+fn cli_generates_crate_from_schema_into_output_dir() {
+    let dir = tempfile::tempdir().unwrap();
+    let input = dir.path().join("api_v30.json");
+    fs::write(&input, SCHEMA).unwrap();
+    let output = dir.path().join("midas");
+
+    // Run outside the workspace: nothing but the schema and output directory is needed
+    let status = Command::new(env!("CARGO_BIN_EXE_pipeline"))
+        .arg(&input)
+        .arg(&output)
+        .current_dir(dir.path())
+        .env(codegen::NO_RUSTFMT_ENV, "1")
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let cargo_toml = fs::read_to_string(output.join("Cargo.toml")).unwrap();
+    assert!(cargo_toml.contains("name = \"bitcoin-rpc-midas\""), "{cargo_toml}");
+    let lib_rs = fs::read_to_string(output.join("src/lib.rs")).unwrap();
+    assert!(lib_rs.contains("pub const GENERATED_FROM: &str = \"v30\";"), "{lib_rs}");
+    assert!(output.join("src/transport/mod.rs").is_file());
+    assert!(output.join(pipeline::GENERATED_SENTINEL).is_file());
+}
+
+#[test]
+fn cli_fails_on_missing_input() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("midas");
+
+    let status = Command::new(env!("CARGO_BIN_EXE_pipeline"))
+        .arg(dir.path().join("missing.json"))
+        .arg(&output)
+        .current_dir(dir.path())
+        .status()
+        .unwrap();
+    assert!(!status.success());
+    assert!(!output.exists());
+}
+
+#[test]
+fn generate_subcommand_overrides_version() {
+    let dir = tempfile::tempdir().unwrap();
+    let input = dir.path().join("api.json");
+    fs::write(&input, SCHEMA).unwrap();
+    let output = dir.path().join("midas");
+
+    let status = Command::new(env!("CARGO_BIN_EXE_pipeline"))
+        .args(["generate", "--version", "v29.1", "--input"])
+        .arg(&input)
+        .arg("--output")
+        .arg(&output)
+        .current_dir(dir.path())
+        .env(codegen::NO_RUSTFMT_ENV, "1")
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let lib_rs = fs::read_to_string(output.join("src/lib.rs")).unwrap();
+    assert!(lib_rs.contains("pub const GENERATED_FROM: &str = \"v29.1\";"), "{lib_rs}");
+}
+
+#[test]
+fn generate_check_validates_without_writing() {
+    let dir = tempfile::tempdir().unwrap();
+    let input = dir.path().join("api.json");
+    fs::write(&input, SCHEMA).unwrap();
+    let output = dir.path().join("midas");
+
+    let run = |input: &std::path::Path| {
+        Command::new(env!("CARGO_BIN_EXE_pipeline"))
+            .args(["generate", "--check", "--input"])
+            .arg(input)
+            .arg("--output")
+            .arg(&output)
+            .current_dir(dir.path())
+            .output()
+            .unwrap()
+    };
+
+    let checked = run(&input);
+    assert!(checked.status.success());
+    assert!(String::from_utf8_lossy(&checked.stdout).contains("1 methods, schema OK"));
+    assert!(!output.exists());
+
+    let broken = dir.path().join("broken.json");
+    fs::write(&broken, r#"{"methods": []}"#).unwrap();
+    assert!(!run(&broken).status.success());
+}
+
+#[test]
+fn generate_dry_run_lists_files_without_writing() {
+    let dir = tempfile::tempdir().unwrap();
+    let input = dir.path().join("api.json");
+    fs::write(&input, SCHEMA).unwrap();
+    let output = dir.path().join("midas");
+    fs::create_dir_all(&output).unwrap();
+    fs::write(output.join(pipeline::GENERATED_SENTINEL), "").unwrap();
+    fs::write(output.join("stale.rs"), "").unwrap();
+
+    let run = Command::new(env!("CARGO_BIN_EXE_pipeline"))
+        .args(["generate", "--dry-run", "--input"])
+        .arg(&input)
+        .arg("--output")
+        .arg(&output)
+        .current_dir(dir.path())
+        .env(codegen::NO_RUSTFMT_ENV, "1")
+        .output()
+        .unwrap();
+    assert!(run.status.success());
+
+    let stdout = String::from_utf8_lossy(&run.stdout);
+    assert!(stdout.contains(&output.join("src/lib.rs").display().to_string()), "{stdout}");
+    assert!(stdout.contains("bytes would be written"), "{stdout}");
+    // The previous crate is neither removed nor overwritten
+    assert!(output.join("stale.rs").exists());
+    assert!(!output.join("Cargo.toml").exists());
+}
+
+#[test]
+fn generate_requires_input() {
+    let output = Command::new(env!("CARGO_BIN_EXE_pipeline"))
+        .args(["generate", "--check"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--input is required"));
+}
+
+#[test]
+fn introspect_writes_a_schema_generate_accepts() {
+    let mut server = mockito::Server::new();
+    let mut mock = |params: serde_json::Value, result: serde_json::Value| {
+        server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::PartialJson(params))
+            .with_body_from_request(move |request| {
+                let request: serde_json::Value =
+                    serde_json::from_slice(request.body().unwrap()).unwrap();
+                serde_json::json!({"result": result, "id": request["id"]}).to_string().into()
+            })
+            .create()
+    };
+    let _network = mock(
+        serde_json::json!({"method": "getnetworkinfo"}),
+        serde_json::json!({"version": 290100}),
+    );
+    let _list = mock(
+        serde_json::json!({"method": "help", "params": []}),
+        serde_json::json!("== Blockchain ==\ngetblockcount\n"),
+    );
+    let _getblockcount = mock(
+        serde_json::json!({"method": "help", "params": ["getblockcount"]}),
+        serde_json::json!("getblockcount\n\nReturns the height of the most-work fully-validated chain.\n\nResult:\nn    (numeric) The current block count\n"),
+    );
+
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("api.json");
+    let status = Command::new(env!("CARGO_BIN_EXE_pipeline"))
+        .args([
+            "introspect",
+            "--rpc-url",
+            &server.url(),
+            "--rpc-user",
+            "user",
+            "--rpc-pass",
+            "pass",
+            "--out",
+        ])
+        .arg(&out)
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let schema: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&out).unwrap()).unwrap();
+    assert_eq!(schema["version"], "v29.1.0");
+    assert_eq!(schema["methods"]["getblockcount"]["results"][0]["type"], "number");
+    assert_eq!(pipeline::check_schema(&out).unwrap(), 1);
+}
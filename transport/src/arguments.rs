@@ -0,0 +1,194 @@
+// transport/src/arguments.rs
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// The type of address a wallet RPC should create, the `address_type` argument of
+/// `getnewaddress`, `getrawchangeaddress`, `addmultisigaddress` and `createmultisig`.
+///
+/// Serializes to the exact string Bitcoin Core expects, so a typo is a compile error rather
+/// than an `RPC_INVALID_PARAMETER` (`-8`) from the node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AddressType {
+    /// `legacy`: pay-to-pubkey-hash.
+    #[serde(rename = "legacy")]
+    Legacy,
+    /// `p2sh-segwit`: segwit v0 wrapped in pay-to-script-hash.
+    #[serde(rename = "p2sh-segwit")]
+    P2shSegwit,
+    /// `bech32`: native segwit v0.
+    #[serde(rename = "bech32")]
+    Bech32,
+    /// `bech32m`: native segwit v1 (taproot).
+    #[serde(rename = "bech32m")]
+    Bech32m,
+}
+
+impl AddressType {
+    /// The string Bitcoin Core uses for this address type, e.g. `p2sh-segwit`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AddressType::Legacy => "legacy",
+            AddressType::P2shSegwit => "p2sh-segwit",
+            AddressType::Bech32 => "bech32",
+            AddressType::Bech32m => "bech32m",
+        }
+    }
+}
+
+impl fmt::Display for AddressType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.write_str(self.as_str()) }
+}
+
+/// How `estimatesmartfee` and the wallet's sending RPCs (`send`, `sendall`, `sendmany`,
+/// `sendtoaddress`) estimate fees, their `estimate_mode` argument.
+///
+/// Serializes to the exact string Bitcoin Core expects.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EstimateMode {
+    /// `unset`: the node's default mode.
+    #[default]
+    #[serde(rename = "unset")]
+    Unset,
+    /// `economical`: react quickly to short-term drops in fee rates.
+    #[serde(rename = "economical")]
+    Economical,
+    /// `conservative`: favour longer-term history, for a lower risk of underpaying.
+    #[serde(rename = "conservative")]
+    Conservative,
+}
+
+impl EstimateMode {
+    /// The string Bitcoin Core uses for this mode, e.g. `conservative`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EstimateMode::Unset => "unset",
+            EstimateMode::Economical => "economical",
+            EstimateMode::Conservative => "conservative",
+        }
+    }
+}
+
+impl fmt::Display for EstimateMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.write_str(self.as_str()) }
+}
+
+/// Script functions an output descriptor may start with.
+const DESCRIPTOR_FUNCTIONS: &[&str] = &[
+    "sh",
+    "wsh",
+    "pk",
+    "pkh",
+    "wpkh",
+    "combo",
+    "multi",
+    "sortedmulti",
+    "tr",
+    "addr",
+    "raw",
+    "rawtr",
+];
+
+/// An output descriptor, e.g. `wpkh([d34db33f/84h/0h/0h]xpub.../0/*)#cjjspncu`, for the
+/// `descriptor` argument of `deriveaddresses`, `getdescriptorinfo` and `generatetodescriptor`.
+///
+/// Construction checks the syntax only: a known top-level function, balanced `()`, `[]`
+/// and `{}`, and a well-formed `#` checksum if there is one. Keys, miniscript and the
+/// checksum's value are left to the node. Serializes as the descriptor string.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Descriptor(String);
+
+/// Why a string is not an output descriptor.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum DescriptorError {
+    /// The descriptor does not start with a known script function, e.g. `wpkh(`.
+    #[error("unknown descriptor function `{0}`")]
+    UnknownFunction(String),
+    /// Its brackets do not pair up, or the top-level function is not closed at the end.
+    #[error("unbalanced brackets in descriptor at byte {0}")]
+    Unbalanced(usize),
+    /// The part after `#` is not 8 checksum characters.
+    #[error("malformed descriptor checksum `{0}`")]
+    Checksum(String),
+}
+
+impl Descriptor {
+    /// Check the syntax of `descriptor` (see [`Descriptor`]).
+    pub fn new(descriptor: impl Into<String>) -> Result<Self, DescriptorError> {
+        let descriptor = descriptor.into();
+        let (body, checksum) = match descriptor.split_once('#') {
+            Some((body, checksum)) => (body, Some(checksum)),
+            None => (descriptor.as_str(), None),
+        };
+        if let Some(checksum) = checksum {
+            if checksum.len() != 8 || !checksum.bytes().all(|b| b.is_ascii_alphanumeric()) {
+                return Err(DescriptorError::Checksum(checksum.to_string()));
+            }
+        }
+
+        let function = body.split('(').next().unwrap_or_default();
+        if !body.contains('(') || !DESCRIPTOR_FUNCTIONS.contains(&function) {
+            return Err(DescriptorError::UnknownFunction(function.to_string()));
+        }
+
+        let mut open = Vec::new();
+        for (i, c) in body.char_indices() {
+            let expected = match c {
+                '(' | '[' | '{' => {
+                    open.push(c);
+                    continue;
+                }
+                ')' => '(',
+                ']' => '[',
+                '}' => '{',
+                _ => continue,
+            };
+            // Closing the top-level function must end the descriptor
+            if open.pop() != Some(expected) || (open.is_empty() && i + 1 != body.len()) {
+                return Err(DescriptorError::Unbalanced(i));
+            }
+        }
+        if !open.is_empty() {
+            return Err(DescriptorError::Unbalanced(body.len()));
+        }
+
+        Ok(Descriptor(descriptor))
+    }
+
+    /// The descriptor string.
+    pub fn as_str(&self) -> &str { &self.0 }
+}
+
+impl FromStr for Descriptor {
+    type Err = DescriptorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> { Descriptor::new(s) }
+}
+
+impl TryFrom<String> for Descriptor {
+    type Error = DescriptorError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> { Descriptor::new(s) }
+}
+
+impl TryFrom<&str> for Descriptor {
+    type Error = DescriptorError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> { Descriptor::new(s) }
+}
+
+impl From<Descriptor> for String {
+    fn from(descriptor: Descriptor) -> Self { descriptor.0 }
+}
+
+impl AsRef<str> for Descriptor {
+    fn as_ref(&self) -> &str { &self.0 }
+}
+
+impl fmt::Display for Descriptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.write_str(&self.0) }
+}
@@ -78,8 +78,27 @@ impl BatchTransport {
     /// # Errors
     /// - Returns [`BatchError::NoBatchInProgress`] if no batch was started.
     /// - Returns [`BatchError::Transport`] if the underlying transport fails.
+    /// - Returns [`BatchError::InvalidResponse`] if a request has no response.
     /// - Returns [`BatchError::Rpc`] if any RPC call in the batch returns an error.
     pub async fn end_batch(&self) -> Result<Vec<Value>, BatchError> {
+        self.end_batch_entries()
+            .await?
+            .into_iter()
+            .map(|entry| entry.map_err(BatchError::Rpc))
+            .collect()
+    }
+
+    /// Like [`end_batch`], but keeps each request's outcome separate.
+    ///
+    /// Returns, in the order the requests were queued, each request's `result` or its JSON-RPC
+    /// `error` object, so one failing call does not hide the others' results. Responses are
+    /// matched to requests by `id`.
+    ///
+    /// # Errors
+    /// - Returns [`BatchError::NoBatchInProgress`] if no batch was started.
+    /// - Returns [`BatchError::Transport`] if the underlying transport fails.
+    /// - Returns [`BatchError::InvalidResponse`] if a request has no response.
+    pub async fn end_batch_entries(&self) -> Result<Vec<Result<Value, Value>>, BatchError> {
         // 1) Take the queued calls
         let requests = {
             let mut b = self.batch.lock().unwrap();
@@ -106,19 +125,26 @@ impl BatchTransport {
         //    so you don't need to think about headers or basic_auth here)
         let resp = self.inner.send_batch(&batch_json).await.map_err(BatchError::Transport)?;
 
-        // 4) Parse the array of responses
-        let arr: Vec<Value> = resp;
-
-        // 5) Extract each "result" or bail on the first error
-        let mut results = Vec::with_capacity(arr.len());
-        for obj in arr {
-            if let Some(err) = obj.get("error") {
-                return Err(BatchError::Rpc(err.clone()));
-            }
-            // assume "result" is present
-            results.push(obj.get("result").cloned().unwrap_or(Value::Null));
-        }
-        Ok(results)
+        // 4) Match each request with its response frame and extract its "result" or "error"
+        requests
+            .iter()
+            .map(|req| {
+                let obj = resp
+                    .iter()
+                    .find(|obj| obj.get("id").and_then(Value::as_u64) == Some(req.id as u64))
+                    .ok_or_else(|| {
+                        BatchError::InvalidResponse(format!(
+                            "no response for request id {}",
+                            req.id
+                        ))
+                    })?;
+                match obj.get("error") {
+                    Some(err) if !err.is_null() => Ok(Err(err.clone())),
+                    // assume "result" is present
+                    _ => Ok(Ok(obj.get("result").cloned().unwrap_or(Value::Null))),
+                }
+            })
+            .collect()
     }
 
     /// Check if a batch is currently in progress.
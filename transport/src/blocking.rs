@@ -0,0 +1,70 @@
+// transport/src/blocking.rs
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+use super::{Transport, TransportError};
+
+/// A synchronous facade over [`Transport`] for callers that don't use async.
+///
+/// Each call is driven to completion on a private current-thread Tokio runtime, so the
+/// typed clients can be used from CLI tools and scripts without adopting async.
+///
+/// Must not be used from within an async runtime: blocking on the private runtime from an
+/// async context panics. Use [`Transport`] directly there.
+///
+/// # Example
+/// ```rust,ignore
+/// let client = BlockingRpcClient::new(Transport::new_with_auth(url, "user", "pass"))?;
+/// let height: u64 = client.call("getblockcount", &[] as &[Value])?;
+/// ```
+#[derive(Debug)]
+pub struct BlockingRpcClient {
+    transport: Transport,
+    runtime: tokio::runtime::Runtime,
+}
+
+impl BlockingRpcClient {
+    /// Wrap `transport` with its own current-thread runtime.
+    ///
+    /// # Errors
+    /// Returns an I/O error if the runtime cannot be created.
+    pub fn new(transport: Transport) -> std::io::Result<Self> {
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
+        Ok(Self { transport, runtime })
+    }
+
+    /// The wrapped async transport.
+    pub fn transport(&self) -> &Transport { &self.transport }
+
+    /// Send a JSON‑RPC request and block until its raw `result` arrives.
+    ///
+    /// # Errors
+    /// Returns `TransportError` like [`Transport::send_request`].
+    ///
+    /// # Panics
+    /// Panics if called from within an async runtime.
+    pub fn call_raw<P: Serialize>(
+        &self,
+        method: &str,
+        params: &[P],
+    ) -> Result<Value, TransportError> {
+        self.runtime.block_on(self.transport.send_request(method, params))
+    }
+
+    /// Send a JSON‑RPC request and block until its `result` arrives, deserialized into `R`.
+    ///
+    /// # Errors
+    /// Returns `TransportError` like [`Transport::call`].
+    ///
+    /// # Panics
+    /// Panics if called from within an async runtime.
+    pub fn call<P: Serialize, R: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: &[P],
+    ) -> Result<R, TransportError> {
+        self.runtime.block_on(self.transport.call(method, params))
+    }
+}
@@ -5,24 +5,54 @@
 //!
 //! Features:
 //! - HTTP client setup with optional basic authentication via `new_with_auth`
+//!   or a bitcoind `.cookie` file via `new_with_cookie_file`
 //! - Low‑level `send_request` returning raw `serde_json::Value` for maximum flexibility
 //! - High‑level `call` with automatic serialization/deserialization to Rust types
+//! - `call_streaming`, deserializing large results as the response body arrives
 //! - Unified error handling through the `TransportError` enum, covering HTTP, RPC, and JSON errors
 //! - Batch support for sending multiple RPC calls in a single HTTP request
+//! - Optional retry with exponential backoff for transient failures via `with_retry`
+//! - `MockTransport` with scripted responses for testing without a node
+//! - Optional per-method request, duration and error metrics via `with_metrics`
+//! - `BlockingRpcClient`, a synchronous facade for callers without an async runtime
+//! - Per-method timeout overrides for slow RPCs via `with_method_timeout`
+//! - A bound on concurrent requests via `with_max_concurrency`, queuing the rest
+//! - JSON‑RPC 1.0 or 2.0 requests via `with_jsonrpc_version`, with response `id` validation
+//! - `UnixTransport`, JSON‑RPC over a Unix domain socket, behind the `unix` feature
+//! - `AddressType` and `EstimateMode`, typed `address_type` and `estimate_mode` arguments
+//! - `Descriptor`, an output descriptor whose syntax is checked on construction
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use base64::Engine;
 use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
 use reqwest::Client;
 use serde::de::DeserializeOwned;
-use serde::Serialize;
-use serde_json::{json, Value};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
 use thiserror::Error;
+use tokio::sync::{Semaphore, SemaphorePermit};
 
 /// Encapsulates an HTTP client and endpoint URL for sending JSON‑RPC requests.
 #[derive(Clone)]
 pub struct Transport {
     client: Client,
     url: String,
+    retry: Option<RetryPolicy>,
+    metrics: Option<Arc<dyn RpcMetrics>>,
+    jsonrpc: JsonRpcVersion,
+    /// Request timeouts that replace the client's for specific methods.
+    method_timeouts: HashMap<String, Duration>,
+    /// Source of request ids, shared by clones so concurrent requests never reuse one.
+    next_id: Arc<AtomicU64>,
+    /// Permits for the requests allowed in flight at once, if bounded.
+    concurrency: Option<Arc<Semaphore>>,
+    /// Requests currently in flight, shared by clones like the bound.
+    in_flight: Arc<AtomicUsize>,
 }
 
 impl std::fmt::Debug for Transport {
@@ -30,10 +60,148 @@ impl std::fmt::Debug for Transport {
         f.debug_struct("Transport")
             .field("url", &self.url)
             .field("client", &"<reqwest::Client>")
+            .field("retry", &self.retry)
+            .field("metrics", &self.metrics.is_some())
+            .field("jsonrpc", &self.jsonrpc)
+            .field("method_timeouts", &self.method_timeouts)
+            .field("in_flight", &self.in_flight())
             .finish()
     }
 }
 
+/// The JSON‑RPC protocol version a [`Transport`] speaks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum JsonRpcVersion {
+    /// JSON‑RPC 1.0: requests carry no `jsonrpc` member.
+    V1,
+    /// JSON‑RPC 2.0: requests carry `"jsonrpc": "2.0"`.
+    #[default]
+    V2,
+}
+
+/// Read-only RPCs, which are safe to send again after a request whose outcome is unknown.
+pub const IDEMPOTENT_METHODS: &[&str] = &[
+    "analyzepsbt",
+    "decodepsbt",
+    "decoderawtransaction",
+    "decodescript",
+    "deriveaddresses",
+    "estimaterawfee",
+    "estimatesmartfee",
+    "getaddressesbylabel",
+    "getaddressinfo",
+    "getbalance",
+    "getbalances",
+    "getbestblockhash",
+    "getblock",
+    "getblockchaininfo",
+    "getblockcount",
+    "getblockfilter",
+    "getblockhash",
+    "getblockheader",
+    "getblockstats",
+    "getblocktemplate",
+    "getchaintips",
+    "getchaintxstats",
+    "getconnectioncount",
+    "getdeploymentinfo",
+    "getdescriptorinfo",
+    "getdifficulty",
+    "getindexinfo",
+    "getmempoolancestors",
+    "getmempooldescendants",
+    "getmempoolentry",
+    "getmempoolinfo",
+    "getmininginfo",
+    "getnettotals",
+    "getnetworkhashps",
+    "getnetworkinfo",
+    "getpeerinfo",
+    "getrawmempool",
+    "getrawtransaction",
+    "getreceivedbyaddress",
+    "getreceivedbylabel",
+    "gettransaction",
+    "gettxout",
+    "gettxoutproof",
+    "getwalletinfo",
+    "listbanned",
+    "listdescriptors",
+    "listlabels",
+    "listlockunspent",
+    "listreceivedbyaddress",
+    "listreceivedbylabel",
+    "listsinceblock",
+    "listtransactions",
+    "listunspent",
+    "listwalletdir",
+    "listwallets",
+    "testmempoolaccept",
+    "uptime",
+    "validateaddress",
+    "verifymessage",
+];
+
+/// Controls how `Transport::send_request` retries transient failures.
+///
+/// Connection failures are always retried, since the request never reached the node.
+/// Other network-level errors (the `Http(0, _)` sentinel, e.g. a timeout, excluding body
+/// decode failures) and HTTP responses whose status is listed in `retry_on` may come after
+/// the node ran the request, so they are only retried for `idempotent_methods`: sending
+/// `sendtoaddress` twice would pay twice. RPC error objects returned by the node are
+/// deterministic and are never retried.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Maximum number of retries after the initial attempt.
+    pub max_retries: u32,
+    /// Delay before the first retry; doubled for every subsequent retry.
+    pub base_delay: Duration,
+    /// Upper bound for a single backoff delay.
+    pub max_delay: Duration,
+    /// HTTP status codes that are treated as transient.
+    pub retry_on: Vec<u16>,
+    /// Methods retried after failures other than connecting; defaults to [`IDEMPOTENT_METHODS`].
+    pub idempotent_methods: Vec<String>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_retries: 3,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(5),
+            retry_on: vec![502, 503, 504],
+            idempotent_methods: IDEMPOTENT_METHODS.iter().map(|m| m.to_string()).collect(),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Whether `err`, from a request for `method`, is a transient failure this policy retries.
+    fn should_retry(&self, method: &str, err: &TransportError) -> bool {
+        match err {
+            TransportError::Http(0, e) if e.is_connect() => true,
+            _ if !self.idempotent_methods.iter().any(|m| m == method) => false,
+            TransportError::Http(0, e) => !e.is_decode(),
+            TransportError::Http(status, _) => self.retry_on.contains(status),
+            _ => false,
+        }
+    }
+
+    /// Backoff before retry number `retry` (1-based): exponential, capped at `max_delay`,
+    /// with the upper half jittered so concurrent callers don't retry in lockstep.
+    fn backoff(&self, retry: u32) -> Duration {
+        let delay = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(retry.saturating_sub(1)))
+            .min(self.max_delay);
+        let half = delay / 2;
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().subsec_nanos();
+        let jitter = u64::from(nanos) % (half.as_nanos() as u64).saturating_add(1);
+        half + Duration::from_nanos(jitter)
+    }
+}
+
 /// Errors that can occur while sending or receiving JSON‑RPC requests.
 #[derive(Debug, Error)]
 pub enum TransportError {
@@ -41,23 +209,134 @@ pub enum TransportError {
     #[error("HTTP error (status {0}): {1}")]
     Http(u16, #[source] reqwest::Error),
 
-    /// The JSON‑RPC response contained an error object.
+    /// The JSON‑RPC response contained an error object that is not a standard
+    /// `{ "code", "message" }` object.
     #[error("RPC error: {0}")]
     Rpc(String),
 
+    /// The JSON‑RPC response contained a standard error object.
+    #[error("RPC error {code}: {message}")]
+    RpcCode {
+        /// Bitcoin Core error code (e.g. `-8` invalid parameter, `-28` warming up).
+        code: i32,
+        /// Human-readable error message.
+        message: String,
+        /// Optional additional error data.
+        data: Option<Value>,
+    },
+
     /// Failed to parse JSON response.
     #[error("Invalid JSON: {0}")]
     Serialization(#[from] serde_json::Error),
 
+    /// The `result` of `method` did not match the type it was decoded into, e.g. a field
+    /// the node no longer sends.
+    #[error("{method}: {source}")]
+    ResponseDecode {
+        /// The RPC method whose result failed to decode.
+        method: String,
+        /// The serde error, naming e.g. the missing field.
+        source: serde_json::Error,
+    },
+
+    /// The JSON‑RPC response answered a different request `id` than the one sent.
+    #[error("Response id {actual} does not match request id {expected}")]
+    IdMismatch {
+        /// The id sent with the request.
+        expected: u64,
+        /// The id found in the response (`null` if missing).
+        actual: Value,
+    },
+
+    /// The node, or a proxy in front of it, answered with a body that is not JSON at all,
+    /// e.g. an HTML `502 Bad Gateway` page or bitcoind's plain-text
+    /// `Work queue depth exceeded`.
+    #[error("Non-JSON response (HTTP {status}): {body_snippet}")]
+    NonJsonResponse {
+        /// The HTTP status of the response.
+        status: u16,
+        /// The start of the body, at most 256 bytes of it.
+        body_snippet: String,
+    },
+
     /// The JSON‑RPC response did not include a `result` field.
     #[error("Missing result field in response")]
     MissingResult,
+
+    /// The node rejected the credentials with HTTP 401 or 403.
+    #[error("Unauthorized (HTTP {status}): check rpcuser/rpcpassword or the cookie file")]
+    Unauthorized {
+        /// The HTTP status the node answered with (401 or 403).
+        status: u16,
+    },
+
+    /// Credentials could not be loaded (e.g. a missing or malformed cookie file).
+    #[error("Authentication error: {0}")]
+    Auth(String),
+}
+
+impl TransportError {
+    /// The JSON‑RPC error code, if this error is a standard RPC error object.
+    pub fn rpc_code(&self) -> Option<i32> {
+        match self {
+            TransportError::RpcCode { code, .. } => Some(*code),
+            _ => None,
+        }
+    }
+}
+
+/// Fail with [`TransportError::Unauthorized`] on HTTP 401/403.
+///
+/// bitcoind answers bad credentials with an empty body, so this must run before the body
+/// is parsed as JSON.
+fn check_authorized(response: &reqwest::Response) -> Result<(), TransportError> {
+    match response.status().as_u16() {
+        status @ (401 | 403) => Err(TransportError::Unauthorized { status }),
+        _ => Ok(()),
+    }
+}
+
+/// How much of a non-JSON body [`TransportError::NonJsonResponse`] keeps.
+const BODY_SNIPPET_LEN: usize = 256;
+
+/// Read the body of `response` and parse it as JSON.
+///
+/// A body that is not JSON at all fails with [`TransportError::NonJsonResponse`], while
+/// JSON of the wrong shape for `T` is a [`TransportError::Serialization`] error.
+async fn read_json<T: DeserializeOwned>(response: reqwest::Response) -> Result<T, TransportError> {
+    let status = response.status().as_u16();
+    let body = response.bytes().await?;
+    let Ok(json) = serde_json::from_slice::<Value>(&body) else {
+        return Err(non_json_response(status, &body));
+    };
+    Ok(serde_json::from_value(json)?)
+}
+
+/// A [`TransportError::NonJsonResponse`] keeping the start of `body`.
+fn non_json_response(status: u16, body: &[u8]) -> TransportError {
+    let snippet = &body[..body.len().min(BODY_SNIPPET_LEN)];
+    TransportError::NonJsonResponse {
+        status,
+        body_snippet: String::from_utf8_lossy(snippet).trim().to_string(),
+    }
+}
+
+/// A standard JSON‑RPC error object as returned by Bitcoin Core.
+#[derive(Deserialize)]
+struct RpcErrorObject {
+    code: i32,
+    message: String,
+    #[serde(default)]
+    data: Option<Value>,
 }
 
 impl From<reqwest::Error> for TransportError {
     fn from(e: reqwest::Error) -> Self {
         if let Some(status) = e.status() {
-            TransportError::Http(status.as_u16(), e)
+            match status.as_u16() {
+                status @ (401 | 403) => TransportError::Unauthorized { status },
+                status => TransportError::Http(status, e),
+            }
         } else {
             // Use 0 as a sentinel value for network errors (timeouts, connection refused, etc.)
             // where no HTTP status code is available
@@ -71,9 +350,7 @@ impl Transport {
     ///
     /// # Parameters
     /// - `url`: The HTTP endpoint of the Bitcoin Core JSON‑RPC server.
-    pub fn new<U: Into<String>>(url: U) -> Self {
-        Transport { client: Client::new(), url: url.into() }
-    }
+    pub fn new<U: Into<String>>(url: U) -> Self { Transport::from_client(Client::new(), url) }
 
     /// Create a new transport with HTTP basic authentication.
     ///
@@ -85,13 +362,167 @@ impl Transport {
     /// # Panics
     /// Panics if default headers cannot be constructed.
     pub fn new_with_auth<U: Into<String>>(url: U, rpcuser: &str, rpcpass: &str) -> Self {
-        let mut headers = HeaderMap::new();
-        let auth = base64::engine::general_purpose::STANDARD.encode(format!("{rpcuser}:{rpcpass}"));
-        headers.insert(AUTHORIZATION, HeaderValue::from_str(&format!("Basic {auth}")).unwrap());
+        let client = basic_auth_client(rpcuser, rpcpass).unwrap();
 
-        let client = Client::builder().default_headers(headers).build().unwrap();
+        Transport::from_client(client, url)
+    }
 
-        Transport { client, url: url.into() }
+    /// Create a new transport authenticated with the contents of a bitcoind `.cookie` file.
+    ///
+    /// The cookie has the form `__cookie__:<password>` and is split on the first `:`.
+    ///
+    /// # Parameters
+    /// - `url`: The HTTP endpoint of the Bitcoin Core JSON‑RPC server.
+    /// - `path`: Path to the `.cookie` file in the node's datadir.
+    ///
+    /// # Errors
+    /// Returns `TransportError::Auth` if the file cannot be read or is malformed.
+    pub fn new_with_cookie_file<U: Into<String>>(
+        url: U,
+        path: &Path,
+    ) -> Result<Self, TransportError> {
+        let cookie = std::fs::read_to_string(path).map_err(|e| {
+            TransportError::Auth(format!("failed to read cookie file {}: {e}", path.display()))
+        })?;
+        let (user, pass) = cookie.trim_end().split_once(':').ok_or_else(|| {
+            TransportError::Auth(format!(
+                "malformed cookie file {}: expected `user:password`",
+                path.display()
+            ))
+        })?;
+        let client = basic_auth_client(user, pass)?;
+
+        Ok(Transport::from_client(client, url))
+    }
+
+    /// Create a transport that sends through an existing `reqwest::Client`.
+    ///
+    /// Use this to share one client (and its connection pool, TLS roots, user agent, ...)
+    /// with the rest of an application. The client is used as is: authentication must be
+    /// configured on it by the caller, e.g. as a default `Authorization` header.
+    ///
+    /// # Parameters
+    /// - `client`: The HTTP client to send requests with.
+    /// - `url`: The HTTP endpoint of the Bitcoin Core JSON‑RPC server.
+    pub fn from_client<U: Into<String>>(client: Client, url: U) -> Self {
+        Transport {
+            client,
+            url: url.into(),
+            retry: None,
+            metrics: None,
+            jsonrpc: JsonRpcVersion::default(),
+            method_timeouts: HashMap::new(),
+            next_id: Arc::new(AtomicU64::new(1)),
+            concurrency: None,
+            in_flight: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Create a transport that targets the `/wallet/<name>` endpoint of this node.
+    ///
+    /// The HTTP client (including authentication and retry policy) is shared, and
+    /// `wallet_name` is percent-encoded so names with spaces or slashes are routed correctly.
+    ///
+    /// # Parameters
+    /// - `wallet_name`: The name of a wallet loaded on the node.
+    pub fn for_wallet(&self, wallet_name: &str) -> Transport {
+        Transport {
+            client: self.client.clone(),
+            url: format!(
+                "{}/wallet/{}",
+                self.url.trim_end_matches('/'),
+                encode_wallet_name(wallet_name)
+            ),
+            retry: self.retry.clone(),
+            metrics: self.metrics.clone(),
+            jsonrpc: self.jsonrpc,
+            method_timeouts: self.method_timeouts.clone(),
+            next_id: self.next_id.clone(),
+            concurrency: self.concurrency.clone(),
+            in_flight: self.in_flight.clone(),
+        }
+    }
+
+    /// Retry transient failures of `send_request` according to `policy`.
+    ///
+    /// # Parameters
+    /// - `policy`: The retry policy to apply to every subsequent request.
+    pub fn with_retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = Some(policy);
+        self
+    }
+
+    /// Speak `version` of JSON‑RPC in `send_request` and `send_request_named`.
+    ///
+    /// Responses are checked against the request `id` either way.
+    ///
+    /// # Parameters
+    /// - `version`: The protocol version, e.g. [`JsonRpcVersion::V1`] for older proxies.
+    pub fn with_jsonrpc_version(mut self, version: JsonRpcVersion) -> Self {
+        self.jsonrpc = version;
+        self
+    }
+
+    /// Record request metrics for every `send_request` into `metrics`.
+    ///
+    /// Each request (including its retries) records `rpc_requests_total{method}` and
+    /// `rpc_request_duration_seconds{method}`, plus `rpc_errors_total{method,code}` if it fails.
+    ///
+    /// # Parameters
+    /// - `metrics`: The sink to record into, e.g. [`InMemoryMetrics`] or a Prometheus adapter.
+    pub fn with_metrics(mut self, metrics: Arc<dyn RpcMetrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Give requests for `method` a timeout of `timeout`, in place of the client's.
+    ///
+    /// Use this for RPCs that routinely run for minutes, such as `scantxoutset` or
+    /// `rescanblockchain`, without lifting the timeout for every other call. The timeout
+    /// applies to each attempt when retrying.
+    ///
+    /// # Parameters
+    /// - `method`: The RPC method name.
+    /// - `timeout`: How long a single request for `method` may take.
+    pub fn with_method_timeout(mut self, method: impl Into<String>, timeout: Duration) -> Self {
+        self.method_timeouts.insert(method.into(), timeout);
+        self
+    }
+
+    /// Allow at most `max` requests in flight at once, queuing the rest until one finishes.
+    ///
+    /// bitcoind serves RPCs from a small work queue (`-rpcworkqueue`) and rejects requests
+    /// beyond it with `Work queue depth exceeded`. Bounding requests here makes bursts wait
+    /// instead. The bound is shared by clones and [`for_wallet`](Self::for_wallet)
+    /// transports; a batch counts as one request, and each retry waits for a slot again.
+    ///
+    /// # Parameters
+    /// - `max`: The number of concurrent requests, at least 1.
+    ///
+    /// # Panics
+    /// Panics if `max` is 0.
+    pub fn with_max_concurrency(mut self, max: usize) -> Self {
+        assert!(max > 0, "max_concurrency must be at least 1");
+        self.concurrency = Some(Arc::new(Semaphore::new(max)));
+        self
+    }
+
+    /// The number of requests currently in flight, e.g. for a metrics gauge.
+    ///
+    /// Requests waiting for a slot under [`with_max_concurrency`](Self::with_max_concurrency)
+    /// are not counted until they get one.
+    pub fn in_flight(&self) -> usize { self.in_flight.load(Ordering::Relaxed) }
+
+    /// Wait for a slot under the concurrency bound, if any, and count the request as in flight
+    /// until the returned guard is dropped.
+    async fn start_request(&self) -> InFlight<'_> {
+        let permit = match &self.concurrency {
+            // The semaphore is never closed, so acquiring only fails if it were
+            Some(semaphore) => semaphore.acquire().await.ok(),
+            None => None,
+        };
+        self.in_flight.fetch_add(1, Ordering::Relaxed);
+        InFlight { _permit: permit, in_flight: &self.in_flight }
     }
 
     /// Send a JSON‑RPC request with given `method` and `params`, returning the raw `result` field.
@@ -103,6 +534,10 @@ impl Transport {
     /// - `method`: The RPC method name.
     /// - `params`: The parameters to pass to the RPC call.
     ///
+    /// If a `RetryPolicy` is configured, transient failures are retried with backoff
+    /// before the last error is returned. A timeout set with `with_method_timeout` for
+    /// `method` replaces the client's.
+    ///
     /// # Errors
     /// Returns `TransportError` if the HTTP request fails, the server returns an error object,
     /// or the response cannot be parsed or is missing the `result`.
@@ -111,22 +546,103 @@ impl Transport {
         method: &str,
         params: &[P],
     ) -> Result<Value, TransportError> {
-        let req_body = json!({
-            "jsonrpc": "2.0",
-            "method": method,
-            "params": params,
-            "id": 1,
-        });
-
-        let resp: Value = self.client.post(&self.url).json(&req_body).send().await?.json().await?;
-
-        if let Some(err) = resp.get("error") {
-            Err(TransportError::Rpc(err.to_string()))
-        } else if !resp.is_object() || resp.get("result").is_none() {
-            Err(TransportError::MissingResult)
-        } else {
-            Ok(resp["result"].clone())
+        let req_body = self.request_body(method, json!(params));
+        self.send_body(method, &req_body).await
+    }
+
+    /// Send a JSON‑RPC request whose `params` are passed **by name**, returning the raw `result` field.
+    ///
+    /// Bitcoin Core accepts `"params": { "arg_name": value, … }`, which lets callers skip
+    /// optional positional arguments instead of padding them with `null`.
+    ///
+    /// # Parameters
+    /// - `method`: The RPC method name.
+    /// - `params`: The parameters keyed by argument name.
+    ///
+    /// # Errors
+    /// Returns `TransportError` if the HTTP request fails, the server returns an error object,
+    /// or the response cannot be parsed or is missing the `result`.
+    pub async fn send_request_named(
+        &self,
+        method: &str,
+        params: Map<String, Value>,
+    ) -> Result<Value, TransportError> {
+        let req_body = self.request_body(method, json!(params));
+        self.send_body(method, &req_body).await
+    }
+
+    /// A request body for `method` with a fresh `id`, in the configured protocol version.
+    fn request_body(&self, method: &str, params: Value) -> Value {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let mut body = json!({ "method": method, "params": params, "id": id });
+        if self.jsonrpc == JsonRpcVersion::V2 {
+            body["jsonrpc"] = json!("2.0");
+        }
+        body
+    }
+
+    /// Send a prepared request body, recording metrics if a sink is configured.
+    async fn send_body(&self, method: &str, req_body: &Value) -> Result<Value, TransportError> {
+        let Some(sink) = &self.metrics else {
+            return self.send_with_retry(method, req_body).await;
+        };
+
+        let start = Instant::now();
+        let result = self.send_with_retry(method, req_body).await;
+        metrics::record_request(sink.as_ref(), method, start.elapsed(), result.as_ref().err());
+        result
+    }
+
+    /// Send a prepared request body, applying the retry policy if one is configured.
+    async fn send_with_retry(
+        &self,
+        method: &str,
+        req_body: &Value,
+    ) -> Result<Value, TransportError> {
+        let Some(policy) = &self.retry else {
+            return self.post_request(method, req_body).await;
+        };
+
+        let mut attempts = 1;
+        loop {
+            match self.post_request(method, req_body).await {
+                Err(e) if attempts <= policy.max_retries && policy.should_retry(method, &e) => {
+                    let delay = policy.backoff(attempts);
+                    tracing::debug!(method, attempts, ?delay, error = %e, "retrying RPC request");
+                    tokio::time::sleep(delay).await;
+                    attempts += 1;
+                }
+                result => {
+                    tracing::debug!(method, attempts, ok = result.is_ok(), "RPC request finished");
+                    return result;
+                }
+            }
+        }
+    }
+
+    /// A POST of `req_body`, with the timeout configured for `method` if there is one.
+    fn post(&self, method: &str, req_body: &Value) -> reqwest::RequestBuilder {
+        let request = self.client.post(&self.url).json(req_body);
+        match self.method_timeouts.get(method) {
+            Some(timeout) => request.timeout(*timeout),
+            None => request,
+        }
+    }
+
+    /// POST a single JSON‑RPC request body and extract its `result`.
+    async fn post_request(&self, method: &str, req_body: &Value) -> Result<Value, TransportError> {
+        let _in_flight = self.start_request().await;
+        let response = self.post(method, req_body).send().await?;
+        check_authorized(&response)?;
+        if let Some(policy) = &self.retry {
+            if policy.retry_on.contains(&response.status().as_u16()) {
+                response.error_for_status_ref()?;
+            }
         }
+        let resp: Value = read_json(response).await?;
+
+        check_response_id(&resp, req_body["id"].as_u64().unwrap_or_default())?;
+        response_result(resp)
     }
 
     /// Send a **batch** of raw JSON-RPC objects in one HTTP call.
@@ -140,12 +656,62 @@ impl Transport {
     /// # Errors
     /// Returns `TransportError` if the HTTP request fails or the response cannot be parsed.
     pub async fn send_batch(&self, bodies: &[Value]) -> Result<Vec<Value>, TransportError> {
-        let resp: Vec<Value> =
-            self.client.post(&self.url).json(bodies).send().await?.json().await?;
+        let _in_flight = self.start_request().await;
+        let response = self.client.post(&self.url).json(bodies).send().await?;
+        check_authorized(&response)?;
+        let resp: Vec<Value> = read_json(response).await?;
 
         Ok(resp)
     }
 
+    /// Send several `(method, params)` calls as one JSON‑RPC 2.0 batch in a single HTTP call.
+    ///
+    /// Each call is assigned a unique `id` and responses are matched back by `id`, so the
+    /// returned vector is in the same order as `requests` even if the server reorders them.
+    /// A failing call only affects its own element; calls without a response yield
+    /// `TransportError::MissingResult`.
+    ///
+    /// # Parameters
+    /// - `requests`: The method names and positional parameters to send.
+    ///
+    /// # Errors
+    /// Returns `TransportError` if the HTTP request fails or the response cannot be parsed.
+    pub async fn send_batch_requests(
+        &self,
+        requests: &[(String, Vec<Value>)],
+    ) -> Result<Vec<Result<Value, TransportError>>, TransportError> {
+        if requests.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let bodies: Vec<Value> = requests
+            .iter()
+            .enumerate()
+            .map(|(id, (method, params))| {
+                json!({
+                    "jsonrpc": "2.0",
+                    "method": method,
+                    "params": params,
+                    "id": id,
+                })
+            })
+            .collect();
+
+        let mut results: Vec<Option<Result<Value, TransportError>>> =
+            requests.iter().map(|_| None).collect();
+        for resp in self.send_batch(&bodies).await? {
+            let slot = resp
+                .get("id")
+                .and_then(Value::as_u64)
+                .and_then(|id| results.get_mut(usize::try_from(id).ok()?));
+            if let Some(slot) = slot {
+                *slot = Some(response_result(resp));
+            }
+        }
+
+        Ok(results.into_iter().map(|r| r.unwrap_or(Err(TransportError::MissingResult))).collect())
+    }
+
     /// Send a JSON‑RPC request with given `method` and `params`, deserializing the `result` into `R`.
     ///
     /// # Type Parameters
@@ -157,17 +723,119 @@ impl Transport {
     /// - `params`: The parameters to pass to the RPC call.
     ///
     /// # Errors
-    /// Returns `TransportError` if the HTTP request fails or deserialization fails.
+    /// Returns `TransportError` if the HTTP request fails, or
+    /// [`TransportError::ResponseDecode`] if the `result` does not deserialize into `R`.
     pub async fn call<T: Serialize, R: DeserializeOwned>(
         &self,
         method: &str,
         params: &[T],
     ) -> Result<R, TransportError> {
         let response = self.send_request(method, params).await?;
-        Ok(serde_json::from_value(response)?)
+        serde_json::from_value(response)
+            .map_err(|source| TransportError::ResponseDecode { method: method.to_string(), source })
+    }
+
+    /// Like [`call`](Self::call), but deserializes the `result` into `R` while the response
+    /// body is still arriving.
+    ///
+    /// [`send_request`](Self::send_request) buffers the whole body and parses it into a
+    /// `Value` before `call` converts that into `R`. For large results such as
+    /// `getrawmempool true` or `listunspent` on a big wallet, that holds the body, the
+    /// `Value` tree and `R` at once. This holds only `R` and a few body chunks.
+    ///
+    /// Metrics are recorded as for any request; the retry policy does not apply.
+    ///
+    /// # Errors
+    /// Returns `TransportError` if the HTTP request fails or the server returns an error
+    /// object, or [`TransportError::ResponseDecode`] if the `result` does not deserialize
+    /// into `R`.
+    pub async fn call_streaming<T: Serialize, R: DeserializeOwned + Send + 'static>(
+        &self,
+        method: &str,
+        params: &[T],
+    ) -> Result<R, TransportError> {
+        let req_body = self.request_body(method, json!(params));
+        let start = Instant::now();
+        let result = async {
+            let _in_flight = self.start_request().await;
+            let response = self.post(method, &req_body).send().await?;
+            check_authorized(&response)?;
+            let id = req_body["id"].as_u64().unwrap_or_default();
+            streaming::read_result(response, method, id).await
+        }
+        .await;
+        if let Some(sink) = &self.metrics {
+            metrics::record_request(sink.as_ref(), method, start.elapsed(), result.as_ref().err());
+        }
+        result
+    }
+}
+
+/// A request counted in [`Transport::in_flight`], holding its concurrency slot if any.
+struct InFlight<'a> {
+    _permit: Option<SemaphorePermit<'a>>,
+    in_flight: &'a AtomicUsize,
+}
+
+impl Drop for InFlight<'_> {
+    fn drop(&mut self) { self.in_flight.fetch_sub(1, Ordering::Relaxed); }
+}
+
+/// Percent-encode a wallet name for use as a single URL path segment.
+fn encode_wallet_name(name: &str) -> String {
+    let mut encoded = String::with_capacity(name.len());
+    for byte in name.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' =>
+                encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
+/// Extract the `result` of a single JSON‑RPC response object, or its error.
+fn response_result(mut resp: Value) -> Result<Value, TransportError> {
+    match resp.get("error") {
+        Some(err) if !err.is_null() => Err(rpc_error(err)),
+        _ => resp.get_mut("result").map(Value::take).ok_or(TransportError::MissingResult),
+    }
+}
+
+/// Fail with [`TransportError::IdMismatch`] unless `resp` answers request `expected`.
+///
+/// An error response with a `null` id is let through, since servers send those for requests
+/// they could not parse at all.
+fn check_response_id(resp: &Value, expected: u64) -> Result<(), TransportError> {
+    let actual = resp.get("id").cloned().unwrap_or(Value::Null);
+    let rejected = actual.is_null() && resp.get("error").is_some_and(|e| !e.is_null());
+    if actual.as_u64() == Some(expected) || rejected {
+        Ok(())
+    } else {
+        Err(TransportError::IdMismatch { expected, actual })
+    }
+}
+
+/// Convert a JSON‑RPC `error` value into the most specific `TransportError`.
+fn rpc_error(err: &Value) -> TransportError {
+    match RpcErrorObject::deserialize(err) {
+        Ok(RpcErrorObject { code, message, data }) =>
+            TransportError::RpcCode { code, message, data },
+        Err(_) => TransportError::Rpc(err.to_string()),
     }
 }
 
+/// Build a `reqwest::Client` that sends an HTTP Basic `Authorization` header.
+fn basic_auth_client(user: &str, pass: &str) -> Result<Client, TransportError> {
+    let auth = base64::engine::general_purpose::STANDARD.encode(format!("{user}:{pass}"));
+    let value = HeaderValue::from_str(&format!("Basic {auth}"))
+        .map_err(|e| TransportError::Auth(format!("invalid credentials: {e}")))?;
+    let mut headers = HeaderMap::new();
+    headers.insert(AUTHORIZATION, value);
+
+    Ok(Client::builder().default_headers(headers).build()?)
+}
+
 /// Trait for transport implementations
 pub trait TransportTrait: Send + Sync {
     /// Send a JSON‑RPC request with given `method` and `params`, returning the raw `result` field.
@@ -220,6 +888,39 @@ impl TransportTrait for Transport {
     fn url(&self) -> &str { &self.url }
 }
 
+/// Typed values for string arguments restricted to a fixed set
+pub mod arguments;
+pub use arguments::{AddressType, Descriptor, DescriptorError, EstimateMode};
+
 /// Batch transport
 pub mod batch_transport;
 pub use batch_transport::{BatchError, BatchTransport};
+
+/// Scripted transport for offline tests
+pub mod mock_transport;
+pub use mock_transport::MockTransport;
+
+/// Synchronous client facade
+pub mod blocking;
+pub use blocking::BlockingRpcClient;
+
+/// Request metrics
+pub mod metrics;
+
+/// JSON-RPC over a Unix domain socket
+#[cfg(all(unix, feature = "unix"))]
+pub mod unix_transport;
+#[cfg(all(unix, feature = "unix"))]
+pub use unix_transport::UnixTransport;
+
+mod streaming;
+pub use metrics::{InMemoryMetrics, RpcMetrics};
+
+/// Sources of the modules the generated crate copies verbatim, as `(file name, contents)`,
+/// embedded here so crates generating it need no path into this crate's source tree
+pub const SHARED_SOURCES: &[(&str, &str)] = &[
+    ("arguments.rs", include_str!("arguments.rs")),
+    ("batch_transport.rs", include_str!("batch_transport.rs")),
+    ("mock_transport.rs", include_str!("mock_transport.rs")),
+    ("unix_transport.rs", include_str!("unix_transport.rs")),
+];
@@ -0,0 +1,101 @@
+// transport/src/metrics.rs
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use super::TransportError;
+
+/// Counter incremented once per request, labelled by `method`.
+pub const RPC_REQUESTS_TOTAL: &str = "rpc_requests_total";
+
+/// Histogram of request durations in seconds, labelled by `method`.
+pub const RPC_REQUEST_DURATION_SECONDS: &str = "rpc_request_duration_seconds";
+
+/// Counter incremented once per failed request, labelled by `method` and `code`.
+pub const RPC_ERRORS_TOTAL: &str = "rpc_errors_total";
+
+/// Metric labels as `(name, value)` pairs.
+pub type Labels = Vec<(&'static str, String)>;
+
+/// Sink for the metrics a [`Transport`](crate::Transport) records around each request.
+///
+/// Implement this to forward to a metrics backend (e.g. a Prometheus recorder) and
+/// enable it with [`Transport::with_metrics`](crate::Transport::with_metrics).
+pub trait RpcMetrics: Send + Sync {
+    /// Increment the counter `name` with `labels` by one.
+    fn increment_counter(&self, name: &'static str, labels: &[(&'static str, String)]);
+
+    /// Record `value` in the histogram `name` with `labels`.
+    fn record_histogram(&self, name: &'static str, labels: &[(&'static str, String)], value: f64);
+}
+
+/// Record one finished request: its count, duration, and error (if any).
+pub(crate) fn record_request(
+    metrics: &dyn RpcMetrics,
+    method: &str,
+    elapsed: Duration,
+    error: Option<&TransportError>,
+) {
+    let labels = [("method", method.to_string())];
+    metrics.increment_counter(RPC_REQUESTS_TOTAL, &labels);
+    metrics.record_histogram(RPC_REQUEST_DURATION_SECONDS, &labels, elapsed.as_secs_f64());
+    if let Some(error) = error {
+        metrics.increment_counter(
+            RPC_ERRORS_TOTAL,
+            &[("method", method.to_string()), ("code", error_code(error))],
+        );
+    }
+}
+
+/// The `code` label for `error`: the RPC error code, `http_<status>`, or the error kind.
+fn error_code(error: &TransportError) -> String {
+    match error {
+        TransportError::RpcCode { code, .. } => code.to_string(),
+        TransportError::Http(status, _) | TransportError::Unauthorized { status } =>
+            format!("http_{status}"),
+        TransportError::Rpc(_) => "rpc".to_string(),
+        TransportError::Serialization(_) => "serialization".to_string(),
+        TransportError::ResponseDecode { .. } => "response_decode".to_string(),
+        TransportError::IdMismatch { .. } => "id_mismatch".to_string(),
+        TransportError::NonJsonResponse { .. } => "non_json_response".to_string(),
+        TransportError::MissingResult => "missing_result".to_string(),
+        TransportError::Auth(_) => "auth".to_string(),
+    }
+}
+
+/// An [`RpcMetrics`] sink that keeps everything in memory, for tests and simple inspection.
+#[derive(Debug, Default)]
+pub struct InMemoryMetrics {
+    counters: Mutex<HashMap<(&'static str, Labels), u64>>,
+    histograms: Mutex<HashMap<(&'static str, Labels), Vec<f64>>>,
+}
+
+impl InMemoryMetrics {
+    /// Create an empty sink.
+    pub fn new() -> Self { Self::default() }
+
+    /// Current value of the counter `name` with exactly `labels` (0 if never incremented).
+    pub fn counter(&self, name: &'static str, labels: &[(&'static str, &str)]) -> u64 {
+        self.counters.lock().unwrap().get(&(name, own(labels))).copied().unwrap_or(0)
+    }
+
+    /// Values recorded in the histogram `name` with exactly `labels`, oldest first.
+    pub fn histogram(&self, name: &'static str, labels: &[(&'static str, &str)]) -> Vec<f64> {
+        self.histograms.lock().unwrap().get(&(name, own(labels))).cloned().unwrap_or_default()
+    }
+}
+
+impl RpcMetrics for InMemoryMetrics {
+    fn increment_counter(&self, name: &'static str, labels: &[(&'static str, String)]) {
+        *self.counters.lock().unwrap().entry((name, labels.to_vec())).or_default() += 1;
+    }
+
+    fn record_histogram(&self, name: &'static str, labels: &[(&'static str, String)], value: f64) {
+        self.histograms.lock().unwrap().entry((name, labels.to_vec())).or_default().push(value);
+    }
+}
+
+fn own(labels: &[(&'static str, &str)]) -> Labels {
+    labels.iter().map(|(k, v)| (*k, v.to_string())).collect()
+}
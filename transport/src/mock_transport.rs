@@ -0,0 +1,105 @@
+// transport/src/mock_transport.rs
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+use serde_json::{json, Value};
+
+use super::{TransportError, TransportTrait};
+
+/// A scripted, in-memory transport for testing code that talks to a [`TransportTrait`].
+///
+/// Responses are queued per method with [`expect`](MockTransport::expect) and handed out
+/// in FIFO order; every call records its params so tests can inspect them afterwards with
+/// [`calls`](MockTransport::calls). Calling a method with no scripted response left fails
+/// with [`TransportError::Rpc`].
+///
+/// # Example
+/// ```rust,ignore
+/// let mock = MockTransport::new();
+/// mock.expect("getblockcount", Ok(json!(101)));
+/// assert_eq!(mock.send_request("getblockcount", &[]).await?, json!(101));
+/// assert_eq!(mock.calls("getblockcount"), vec![Vec::<Value>::new()]);
+/// ```
+#[derive(Debug, Default)]
+pub struct MockTransport {
+    responses: Mutex<HashMap<String, VecDeque<Result<Value, TransportError>>>>,
+    calls: Mutex<HashMap<String, Vec<Vec<Value>>>>,
+}
+
+impl MockTransport {
+    /// Create a mock with no scripted responses.
+    pub fn new() -> Self { Self::default() }
+
+    /// Queue `response` as the next result for `method`.
+    pub fn expect(&self, method: &str, response: Result<Value, TransportError>) -> &Self {
+        self.responses.lock().unwrap().entry(method.to_string()).or_default().push_back(response);
+        self
+    }
+
+    /// Params of every call made to `method` so far, oldest first.
+    pub fn calls(&self, method: &str) -> Vec<Vec<Value>> {
+        self.calls.lock().unwrap().get(method).cloned().unwrap_or_default()
+    }
+
+    /// Record a call and pop the next scripted response for it.
+    fn respond(&self, method: &str, params: Vec<Value>) -> Result<Value, TransportError> {
+        self.calls.lock().unwrap().entry(method.to_string()).or_default().push(params);
+        let next = self.responses.lock().unwrap().get_mut(method).and_then(VecDeque::pop_front);
+        next.unwrap_or_else(|| {
+            Err(TransportError::Rpc(format!("MockTransport: no response scripted for {method}")))
+        })
+    }
+}
+
+impl TransportTrait for MockTransport {
+    fn send_request<'a>(
+        &'a self,
+        method: &'a str,
+        params: &'a [Value],
+    ) -> std::pin::Pin<
+        Box<dyn std::future::Future<Output = Result<Value, TransportError>> + Send + 'a>,
+    > {
+        let response = self.respond(method, params.to_vec());
+        Box::pin(async move { response })
+    }
+
+    /// Answer each frame from the script, as a JSON-RPC response frame with the same `id`.
+    ///
+    /// Named (object) params are recorded as a single-element call.
+    fn send_batch<'a>(
+        &'a self,
+        bodies: &'a [Value],
+    ) -> std::pin::Pin<
+        Box<dyn std::future::Future<Output = Result<Vec<Value>, TransportError>> + Send + 'a>,
+    > {
+        let responses = bodies
+            .iter()
+            .map(|body| {
+                let method = body.get("method").and_then(Value::as_str).unwrap_or_default();
+                let params = match body.get("params") {
+                    Some(Value::Array(params)) => params.clone(),
+                    None | Some(Value::Null) => Vec::new(),
+                    Some(other) => vec![other.clone()],
+                };
+                let id = body.get("id").cloned().unwrap_or(Value::Null);
+                match self.respond(method, params) {
+                    Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+                    Err(TransportError::RpcCode { code, message, data }) => json!({
+                        "jsonrpc": "2.0",
+                        "id": id,
+                        "error": { "code": code, "message": message, "data": data },
+                    }),
+                    Err(e) => json!({
+                        "jsonrpc": "2.0",
+                        "id": id,
+                        "error": { "code": -1, "message": e.to_string() },
+                    }),
+                }
+            })
+            .collect();
+        Box::pin(async move { Ok(responses) })
+    }
+
+    fn url(&self) -> &str { "mock://" }
+}
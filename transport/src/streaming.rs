@@ -0,0 +1,112 @@
+// transport/src/streaming.rs
+
+use std::io::{self, BufReader, Read};
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::error::Category;
+use serde_json::Value;
+use tokio::sync::mpsc;
+
+use super::TransportError;
+
+/// Chunks buffered between the body reader and the parser before reading pauses.
+const CHUNKS_IN_FLIGHT: usize = 8;
+
+/// A JSON‑RPC response with its `result` deserialized straight into `R`.
+#[derive(Deserialize)]
+#[serde(bound = "R: DeserializeOwned")]
+struct Envelope<R> {
+    #[serde(default)]
+    result: Option<R>,
+    #[serde(default)]
+    error: Option<Value>,
+    #[serde(default)]
+    id: Value,
+}
+
+/// Blocking [`Read`] over body chunks sent from the async side.
+struct ChunkReader {
+    chunks: mpsc::Receiver<Vec<u8>>,
+    current: Vec<u8>,
+    pos: usize,
+}
+
+impl Read for ChunkReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.pos == self.current.len() {
+            match self.chunks.blocking_recv() {
+                Some(chunk) => (self.current, self.pos) = (chunk, 0),
+                // The body ended (or the download failed, which the caller reports)
+                None => return Ok(0),
+            }
+        }
+        let n = buf.len().min(self.current.len() - self.pos);
+        buf[..n].copy_from_slice(&self.current[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+/// Deserialize the JSON‑RPC response in `response` into its `result` as `R`, parsing each
+/// chunk as it arrives instead of buffering the body or building a `Value`.
+///
+/// Only the chunks in flight and the `R` being built are held in memory. The response
+/// must answer request `expected_id`. A body that is not JSON at all fails with
+/// [`TransportError::NonJsonResponse`], keeping the start of the body as it arrived, and
+/// JSON that does not decode into `R` with [`TransportError::ResponseDecode`] for `method`.
+pub(crate) async fn read_result<R: DeserializeOwned + Send + 'static>(
+    mut response: reqwest::Response,
+    method: &str,
+    expected_id: u64,
+) -> Result<R, TransportError> {
+    let status = response.status().as_u16();
+    let mut head = Vec::new();
+    let (tx, rx) = mpsc::channel(CHUNKS_IN_FLIGHT);
+    let parser = tokio::task::spawn_blocking(move || {
+        let reader = ChunkReader { chunks: rx, current: Vec::new(), pos: 0 };
+        serde_json::from_reader::<_, Envelope<R>>(BufReader::new(reader))
+    });
+
+    let mut download = Ok(());
+    loop {
+        match response.chunk().await {
+            // A closed channel means the parser already failed; its error is reported below
+            Ok(Some(chunk)) => {
+                let keep = (super::BODY_SNIPPET_LEN - head.len()).min(chunk.len());
+                head.extend_from_slice(&chunk[..keep]);
+                if tx.send(chunk.to_vec()).await.is_err() {
+                    break;
+                }
+            }
+            Ok(None) => break,
+            Err(e) => {
+                download = Err(e);
+                break;
+            }
+        }
+    }
+    drop(tx);
+
+    let parsed =
+        parser.await.map_err(|e| TransportError::Rpc(format!("response parser panicked: {e}")))?;
+    download?;
+    // Syntax errors (or an empty body) mean the body is not JSON, while JSON of the wrong
+    // shape for `R` fails to decode as it would in `call`
+    let envelope = parsed.map_err(|source| match source.classify() {
+        Category::Syntax | Category::Eof => super::non_json_response(status, &head),
+        _ => TransportError::ResponseDecode { method: method.to_string(), source },
+    })?;
+
+    let actual = envelope.id;
+    let rejected = actual.is_null() && envelope.error.as_ref().is_some_and(|e| !e.is_null());
+    if actual.as_u64() != Some(expected_id) && !rejected {
+        return Err(TransportError::IdMismatch { expected: expected_id, actual });
+    }
+    match (envelope.error, envelope.result) {
+        (Some(err), _) if !err.is_null() => Err(super::rpc_error(&err)),
+        (_, Some(result)) => Ok(result),
+        // A `null` result (e.g. from a method returning nothing) is valid for some `R`
+        (_, None) => R::deserialize(Value::Null).map_err(|_| TransportError::MissingResult),
+    }
+}
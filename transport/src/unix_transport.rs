@@ -0,0 +1,162 @@
+// transport/src/unix_transport.rs
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use base64::Engine;
+use http_body_util::{BodyExt, Full};
+use hyper::body::Bytes;
+use hyper::header::{AUTHORIZATION, CONTENT_TYPE, HOST};
+use hyper::Request;
+use hyper_util::rt::TokioIo;
+use serde_json::{json, Value};
+use tokio::net::UnixStream;
+
+use super::{TransportError, TransportTrait};
+
+/// How much of a non-JSON body [`TransportError::NonJsonResponse`] keeps.
+const BODY_SNIPPET_LEN: usize = 256;
+
+/// A transport speaking JSON-RPC over HTTP on a Unix domain socket, such as one a local
+/// proxy exposes in front of bitcoind's RPC server.
+///
+/// Each request opens its own connection to the socket. Failures to reach the socket or
+/// to read its answer are reported as [`TransportError::Rpc`] naming the socket, and an
+/// answer that is not JSON as [`TransportError::NonJsonResponse`].
+///
+/// # Example
+/// ```rust,ignore
+/// let transport = UnixTransport::new("/run/bitcoind/rpc.sock").with_auth("user", "pass");
+/// let height = transport.send_request("getblockcount", &[]).await?;
+/// ```
+#[derive(Debug)]
+pub struct UnixTransport {
+    path: PathBuf,
+    url: String,
+    authorization: Option<String>,
+    next_id: AtomicU64,
+}
+
+impl UnixTransport {
+    /// Create a transport connecting to the socket at `path`.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let url = format!("unix://{}", path.display());
+        Self { path, url, authorization: None, next_id: AtomicU64::new(1) }
+    }
+
+    /// Send HTTP Basic credentials with every request.
+    pub fn with_auth(mut self, user: &str, pass: &str) -> Self {
+        let credentials =
+            base64::engine::general_purpose::STANDARD.encode(format!("{user}:{pass}"));
+        self.authorization = Some(format!("Basic {credentials}"));
+        self
+    }
+
+    /// The path of the socket.
+    pub fn path(&self) -> &Path { &self.path }
+
+    /// POST `body` to the socket and parse the response body as JSON.
+    async fn post(&self, body: &Value) -> Result<Value, TransportError> {
+        let stream = UnixStream::connect(&self.path).await.map_err(|e| self.error(e))?;
+        let (mut sender, connection) = hyper::client::conn::http1::handshake(TokioIo::new(stream))
+            .await
+            .map_err(|e| self.error(e))?;
+        tokio::spawn(connection);
+
+        let mut request =
+            Request::post("/").header(HOST, "localhost").header(CONTENT_TYPE, "application/json");
+        if let Some(authorization) = &self.authorization {
+            request = request.header(AUTHORIZATION, authorization);
+        }
+        let request = request
+            .body(Full::new(Bytes::from(serde_json::to_vec(body)?)))
+            .map_err(|e| self.error(e))?;
+
+        let response = sender.send_request(request).await.map_err(|e| self.error(e))?;
+        let status = response.status().as_u16();
+        // bitcoind answers bad credentials with an empty body
+        if matches!(status, 401 | 403) {
+            return Err(TransportError::Unauthorized { status });
+        }
+        let bytes = response.into_body().collect().await.map_err(|e| self.error(e))?.to_bytes();
+        serde_json::from_slice(&bytes).map_err(|_| {
+            let snippet = &bytes[..bytes.len().min(BODY_SNIPPET_LEN)];
+            TransportError::NonJsonResponse {
+                status,
+                body_snippet: String::from_utf8_lossy(snippet).trim().to_string(),
+            }
+        })
+    }
+
+    fn error(&self, error: impl std::fmt::Display) -> TransportError {
+        TransportError::Rpc(format!("{}: {error}", self.url))
+    }
+}
+
+/// The error of a JSON-RPC error object, as a standard `RpcCode` error if it has one.
+fn rpc_error(error: &Value) -> TransportError {
+    let code = error.get("code").and_then(Value::as_i64).and_then(|c| i32::try_from(c).ok());
+    let message = error.get("message").and_then(Value::as_str);
+    match (code, message) {
+        (Some(code), Some(message)) => TransportError::RpcCode {
+            code,
+            message: message.to_string(),
+            data: error.get("data").cloned(),
+        },
+        _ => TransportError::Rpc(error.to_string()),
+    }
+}
+
+/// Fail with [`TransportError::IdMismatch`] unless `response` answers request `expected`,
+/// letting through an error response with a `null` id (one for a request the server could
+/// not parse).
+fn check_response_id(response: &Value, expected: u64) -> Result<(), TransportError> {
+    let actual = response.get("id").cloned().unwrap_or(Value::Null);
+    let rejected = actual.is_null() && response.get("error").is_some_and(|e| !e.is_null());
+    if actual.as_u64() == Some(expected) || rejected {
+        Ok(())
+    } else {
+        Err(TransportError::IdMismatch { expected, actual })
+    }
+}
+
+impl TransportTrait for UnixTransport {
+    fn send_request<'a>(
+        &'a self,
+        method: &'a str,
+        params: &'a [Value],
+    ) -> std::pin::Pin<
+        Box<dyn std::future::Future<Output = Result<Value, TransportError>> + Send + 'a>,
+    > {
+        Box::pin(async move {
+            let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+            let body = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+            let response = self.post(&body).await?;
+            check_response_id(&response, id)?;
+            if let Some(error) = response.get("error").filter(|e| !e.is_null()) {
+                return Err(rpc_error(error));
+            }
+            response
+                .get("result")
+                .cloned()
+                .ok_or_else(|| TransportError::Rpc("No result field".to_string()))
+        })
+    }
+
+    fn send_batch<'a>(
+        &'a self,
+        bodies: &'a [Value],
+    ) -> std::pin::Pin<
+        Box<dyn std::future::Future<Output = Result<Vec<Value>, TransportError>> + Send + 'a>,
+    > {
+        Box::pin(async move {
+            match self.post(&Value::Array(bodies.to_vec())).await? {
+                Value::Array(responses) => Ok(responses),
+                other => Err(self.error(format!("batch response is not an array: {other}"))),
+            }
+        })
+    }
+
+    fn url(&self) -> &str { &self.url }
+}
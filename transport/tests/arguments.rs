@@ -0,0 +1,70 @@
+// transport/tests/arguments.rs
+
+use serde_json::json;
+use transport::{AddressType, Descriptor, DescriptorError, EstimateMode};
+
+#[test]
+fn address_types_serialize_to_core_strings() {
+    let cases = [
+        (AddressType::Legacy, "legacy"),
+        (AddressType::P2shSegwit, "p2sh-segwit"),
+        (AddressType::Bech32, "bech32"),
+        (AddressType::Bech32m, "bech32m"),
+    ];
+    for (address_type, core) in cases {
+        assert_eq!(serde_json::to_value(address_type).unwrap(), json!(core));
+        assert_eq!(serde_json::from_value::<AddressType>(json!(core)).unwrap(), address_type);
+        assert_eq!(address_type.to_string(), core);
+    }
+
+    assert!(serde_json::from_value::<AddressType>(json!("p2sh_segwit")).is_err());
+    assert_eq!(serde_json::to_value(None::<AddressType>).unwrap(), json!(null));
+}
+
+#[test]
+fn estimate_modes_serialize_to_core_strings() {
+    assert_eq!(serde_json::to_value(EstimateMode::Conservative).unwrap(), json!("conservative"));
+    assert_eq!(serde_json::to_value(EstimateMode::Economical).unwrap(), json!("economical"));
+    assert_eq!(serde_json::to_value(EstimateMode::default()).unwrap(), json!("unset"));
+    assert_eq!(
+        serde_json::from_value::<EstimateMode>(json!("conservative")).unwrap(),
+        EstimateMode::Conservative
+    );
+    assert!(serde_json::from_value::<EstimateMode>(json!("CONSERVATIVE")).is_err());
+}
+
+#[test]
+fn valid_descriptors_are_accepted_and_serialize_as_strings() {
+    let valid = [
+        "wpkh([d34db33f/84h/0h/0h]xpub6DJ2dNUysrn5Vt36jH2KLBT2i1auw1tTSSomg8PhqNiUtx8QX2SvC9nrHu81fT41fvDUnhMjEzQgXnQjKEu3oaqMSzhSrHMxyyoEAmUHQbY/0/*)#cjjspncu",
+        "sh(wpkh(03a34b99f22c790c4e36b2b3c2c35a36db06226e41c692fc82b8b56ac1c540c5bd))",
+        "tr(c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5,{pk(A),pk(B)})",
+        "combo(L4rK1yDtCWekvXuE6oXD9jCYfFNV2cWRpVuPLBcCU2z8TrisoyY1)",
+        "addr(bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080)",
+        "raw(51)",
+    ];
+    for descriptor in valid {
+        let parsed = descriptor.parse::<Descriptor>().unwrap();
+        assert_eq!(parsed.as_str(), descriptor);
+        assert_eq!(serde_json::to_value(&parsed).unwrap(), json!(descriptor));
+        assert_eq!(serde_json::from_value::<Descriptor>(json!(descriptor)).unwrap(), parsed);
+    }
+}
+
+#[test]
+fn malformed_descriptors_are_rejected() {
+    let malformed = [
+        ("wpkh(02aa", DescriptorError::Unbalanced(9)),
+        ("wpkh(02aa))", DescriptorError::Unbalanced(9)),
+        ("sh(wpkh(02aa)", DescriptorError::Unbalanced(13)),
+        ("wpkh(02aa)pk(02bb)", DescriptorError::Unbalanced(9)),
+        ("tr(02aa,{pk(A),pk(B))", DescriptorError::Unbalanced(20)),
+        ("wpk(02aa)", DescriptorError::UnknownFunction("wpk".to_string())),
+        ("02aa", DescriptorError::UnknownFunction("02aa".to_string())),
+        ("wpkh(02aa)#abc", DescriptorError::Checksum("abc".to_string())),
+    ];
+    for (descriptor, error) in malformed {
+        assert_eq!(Descriptor::new(descriptor), Err(error), "{descriptor}");
+    }
+    assert!(serde_json::from_value::<Descriptor>(json!("wpkh(02aa")).is_err());
+}
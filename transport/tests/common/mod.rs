@@ -0,0 +1,30 @@
+// transport/tests/common/mod.rs
+//
+// Helpers for the tests that answer HTTP requests by hand.
+
+use serde_json::Value;
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+/// Read one HTTP request from `stream` and parse its body as JSON.
+pub async fn read_json_request(stream: &mut (impl AsyncRead + Unpin)) -> Value {
+    let mut request = Vec::new();
+    let mut buf = [0; 1024];
+    loop {
+        let n = stream.read(&mut buf).await.unwrap();
+        assert_ne!(n, 0, "connection closed before the request was complete");
+        request.extend_from_slice(&buf[..n]);
+        let text = String::from_utf8_lossy(&request);
+        let Some((head, body)) = text.split_once("\r\n\r\n") else { continue };
+        let length = head
+            .lines()
+            .find_map(|line| {
+                line.to_ascii_lowercase()
+                    .strip_prefix("content-length:")
+                    .map(|len| len.trim().parse::<usize>().unwrap())
+            })
+            .unwrap();
+        if body.len() >= length {
+            return serde_json::from_str(body).unwrap();
+        }
+    }
+}
@@ -0,0 +1,120 @@
+// transport/tests/streaming_alloc.rs
+//
+// Compares the peak heap use of `call` and `call_streaming` on a large array result, shaped
+// like `listunspent` on a big wallet.
+// Kept in its own test binary because it installs a counting global allocator.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use serde::Deserialize;
+use transport::Transport;
+
+struct PeakAlloc;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for PeakAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            let current = CURRENT.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK.fetch_max(current, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static ALLOC: PeakAlloc = PeakAlloc;
+
+/// Serve `response` to every connection on a background thread, returning the URL.
+///
+/// The response is built up front, so serving it allocates next to nothing.
+fn serve(response: &'static [u8]) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    std::thread::spawn(move || {
+        let mut request = [0u8; 4096];
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            // Requests are small enough to arrive in one read
+            let _ = stream.read(&mut request).unwrap();
+            stream.write_all(response).unwrap();
+        }
+    });
+    url
+}
+
+/// Peak heap growth while `rt` runs `call`, keeping its result alive until measured.
+fn peak_during<R>(
+    rt: &tokio::runtime::Runtime,
+    call: impl std::future::Future<Output = R>,
+) -> usize {
+    let baseline = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(baseline, Ordering::Relaxed);
+    let result = rt.block_on(call);
+    let peak = PEAK.load(Ordering::Relaxed) - baseline;
+    drop(result);
+    peak
+}
+
+/// One `listunspent` entry, trimmed to a few fields
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct Unspent {
+    txid: String,
+    vout: u32,
+    amount: f64,
+    confirmations: u64,
+    spendable: bool,
+}
+
+#[test]
+fn call_streaming_peaks_lower_than_buffered_call() {
+    let unspent = (0..50_000)
+        .map(|i| {
+            serde_json::json!({
+                "txid": format!("{i:064x}"),
+                "vout": i % 4,
+                "amount": 0.001,
+                "confirmations": 6,
+                "spendable": true,
+            })
+        })
+        .collect::<Vec<_>>();
+    let body = serde_json::json!({ "result": unspent, "error": null, "id": 1 }).to_string();
+    drop(unspent);
+    let response = format!(
+        "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    let url = serve(Box::leak(response.into_bytes().into_boxed_slice()));
+    let rt = tokio::runtime::Builder::new_multi_thread().enable_all().build().unwrap();
+
+    // A fresh transport per call, so every request has id 1
+    let buffered = peak_during(&rt, async {
+        let tx = Transport::new(url.clone());
+        tx.call::<_, Vec<Unspent>>("listunspent", &[] as &[u8]).await.unwrap()
+    });
+    let streamed = peak_during(&rt, async {
+        let tx = Transport::new(url.clone());
+        tx.call_streaming::<_, Vec<Unspent>>("listunspent", &[] as &[u8]).await.unwrap()
+    });
+
+    // Both hold the decoded `R`; only `call` also holds the body and its `Value` tree, so
+    // the exact ratio depends on the allocator and chunking, but not which peaks lower
+    assert!(
+        streamed < buffered,
+        "peak heap for a {} byte result: call {buffered} bytes, call_streaming {streamed} bytes",
+        body.len()
+    );
+}
@@ -0,0 +1,102 @@
+// transport/tests/unix_transport.rs
+
+#![cfg(all(unix, feature = "unix"))]
+
+mod common;
+
+use serde_json::{json, Value};
+use tokio::io::AsyncWriteExt;
+use tokio::net::{UnixListener, UnixStream};
+use transport::{TransportError, TransportTrait, UnixTransport};
+
+/// Answer one HTTP request on `stream` with a JSON-RPC response echoing its method and params.
+async fn echo(mut stream: UnixStream) {
+    let body = common::read_json_request(&mut stream).await;
+
+    let (status, response) = match &body["method"] {
+        // bitcoind's plain-text answer when its RPC work queue is full
+        Value::String(method) if method == "busy" =>
+            ("500 Internal Server Error", "Work queue depth exceeded".to_string()),
+        // bitcoind's answer to bad credentials has no body at all
+        Value::String(method) if method == "unauthorized" => ("401 Unauthorized", String::new()),
+        // A reply to some other request
+        Value::String(method) if method == "wrongid" =>
+            ("200 OK", json!({ "jsonrpc": "2.0", "id": 999, "result": null }).to_string()),
+        // A parse error, which answers with a null id
+        Value::String(method) if method == "garbled" => (
+            "200 OK",
+            json!({
+                "jsonrpc": "2.0",
+                "id": null,
+                "error": { "code": -32700, "message": "Parse error" },
+            })
+            .to_string(),
+        ),
+        Value::String(method) if method == "fail" => (
+            "200 OK",
+            json!({
+                "jsonrpc": "2.0",
+                "id": body["id"],
+                "error": { "code": -8, "message": "Invalid parameter" },
+            })
+            .to_string(),
+        ),
+        method => (
+            "200 OK",
+            json!({
+                "jsonrpc": "2.0",
+                "id": body["id"],
+                "result": { "method": method, "params": body["params"] },
+            })
+            .to_string(),
+        ),
+    };
+    let reply = format!(
+        "HTTP/1.1 {status}\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{response}",
+        response.len()
+    );
+    stream.write_all(reply.as_bytes()).await.unwrap();
+}
+
+#[tokio::test]
+async fn unix_transport_round_trips_through_a_socket() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("rpc.sock");
+    let listener = UnixListener::bind(&path).unwrap();
+    tokio::spawn(async move {
+        loop {
+            let (stream, _) = listener.accept().await.unwrap();
+            tokio::spawn(echo(stream));
+        }
+    });
+
+    let transport = UnixTransport::new(&path).with_auth("user", "pass");
+    assert_eq!(transport.url(), format!("unix://{}", path.display()));
+
+    let result = transport.send_request("getblockhash", &[json!(0)]).await.unwrap();
+    assert_eq!(result, json!({ "method": "getblockhash", "params": [0] }));
+
+    let err = transport.send_request("fail", &[]).await.unwrap_err();
+    assert!(matches!(err, TransportError::RpcCode { code: -8, .. }), "{err:?}");
+
+    let err = transport.send_request("wrongid", &[]).await.unwrap_err();
+    assert!(
+        matches!(err, TransportError::IdMismatch { ref actual, .. } if *actual == 999),
+        "{err:?}"
+    );
+
+    let err = transport.send_request("garbled", &[]).await.unwrap_err();
+    assert!(matches!(err, TransportError::RpcCode { code: -32700, .. }), "{err:?}");
+
+    let err = transport.send_request("busy", &[]).await.unwrap_err();
+    assert!(matches!(err, TransportError::NonJsonResponse { status: 500, .. }), "{err:?}");
+    assert_eq!(err.to_string(), "Non-JSON response (HTTP 500): Work queue depth exceeded");
+
+    let err = transport.send_request("unauthorized", &[]).await.unwrap_err();
+    assert!(matches!(err, TransportError::Unauthorized { status: 401 }), "{err:?}");
+
+    // Nothing listens here
+    let missing = UnixTransport::new(dir.path().join("missing.sock"));
+    let err = missing.send_request("getblockcount", &[]).await.unwrap_err();
+    assert!(matches!(err, TransportError::Rpc(ref msg) if msg.contains("missing.sock")), "{err:?}");
+}